@@ -0,0 +1,156 @@
+// Copyright 2022 Martin Pool
+
+//! Annotations on missed mutants, in the format understood by CI systems, so that
+//! they're shown against the source in pull requests.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::mutate::Mutation;
+
+/// Which format, if any, to print annotations in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Annotations {
+    #[default]
+    None,
+    /// GitHub Actions workflow commands, like `::warning file=...`.
+    GitHub,
+}
+
+impl FromStr for Annotations {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "none" => Ok(Annotations::None),
+            "github" => Ok(Annotations::GitHub),
+            _ => Err(anyhow!(
+                "unknown annotation format {:?}: expected github or none",
+                s
+            )),
+        }
+    }
+}
+
+impl Annotations {
+    /// Choose annotations when they're not given explicitly: GitHub annotations if we're
+    /// running under GitHub Actions, otherwise none.
+    pub fn from_env() -> Annotations {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+            Annotations::GitHub
+        } else {
+            Annotations::None
+        }
+    }
+}
+
+/// How seriously to report missed mutants in annotations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationLevel {
+    #[default]
+    Warning,
+    Error,
+}
+
+impl FromStr for AnnotationLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "warning" => Ok(AnnotationLevel::Warning),
+            "error" => Ok(AnnotationLevel::Error),
+            _ => Err(anyhow!(
+                "unknown annotation level {:?}: expected warning or error",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AnnotationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AnnotationLevel::Warning => "warning",
+            AnnotationLevel::Error => "error",
+        })
+    }
+}
+
+/// Return a GitHub Actions annotation for a missed mutant, or None if annotations are off.
+pub fn missed_mutant_annotation(
+    mutation: &Mutation,
+    annotations: Annotations,
+    level: AnnotationLevel,
+) -> Option<String> {
+    match annotations {
+        Annotations::None => None,
+        Annotations::GitHub => Some(github_annotation(
+            level,
+            &mutation.source_file.tree_relative_slashes(),
+            mutation.span().start.line,
+            &format!("mutant not caught: {}", mutation.describe_change()),
+        )),
+    }
+}
+
+/// Format a GitHub Actions workflow command to annotate a line.
+///
+/// See <https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions>.
+fn github_annotation(level: AnnotationLevel, file: &str, line: usize, message: &str) -> String {
+    format!(
+        "::{} file={},line={}::{}",
+        level,
+        escape_property(file),
+        line,
+        escape_data(message)
+    )
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_github_annotation() {
+        assert_eq!(
+            github_annotation(
+                AnnotationLevel::Warning,
+                "src/foo.rs",
+                42,
+                "mutant not caught: replace f with Default::default()"
+            ),
+            "::warning file=src/foo.rs,line=42::mutant not caught: replace f with Default::default()"
+        );
+        assert_eq!(
+            github_annotation(AnnotationLevel::Error, "src/a,b.rs", 1, "100%\nsure"),
+            "::error file=src/a%2Cb.rs,line=1::100%25%0Asure"
+        );
+    }
+
+    #[test]
+    fn parse_annotation_options() {
+        assert_eq!(
+            "github".parse::<Annotations>().unwrap(),
+            Annotations::GitHub
+        );
+        assert_eq!("none".parse::<Annotations>().unwrap(), Annotations::None);
+        assert!("gitlab".parse::<Annotations>().is_err());
+        assert_eq!(
+            "error".parse::<AnnotationLevel>().unwrap(),
+            AnnotationLevel::Error
+        );
+        assert!("fatal".parse::<AnnotationLevel>().is_err());
+    }
+}
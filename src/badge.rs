@@ -0,0 +1,99 @@
+// Copyright 2022 Martin Pool
+
+//! A `badge.json` with the mutation score, in the
+//! [shields.io endpoint](https://shields.io/endpoint) format.
+
+use serde_json::{json, Value};
+
+use crate::report::LabOutcomeRecord;
+
+/// Badge colors and the minimum score, as a percentage, for each, if no others are
+/// configured.
+///
+/// Scores below all of these are red.
+pub const DEFAULT_BADGE_COLORS: &[(&str, f64)] = &[
+    ("brightgreen", 90.0),
+    ("green", 75.0),
+    ("yellow", 60.0),
+    ("orange", 40.0),
+];
+
+/// Return the badge for the mutation score of a run.
+///
+/// `colors` are badge colors with the minimum score for each: the color with the highest
+/// minimum that the score reaches is used, or red if none.
+pub fn badge(record: &LabOutcomeRecord, colors: &[(&str, f64)]) -> Value {
+    let (message, color) = match record.totals().score() {
+        Some(score) => (format!("{:.0}%", score.floor()), score_color(score, colors)),
+        None => ("no mutants tested".to_owned(), "lightgrey"),
+    };
+    json!({
+        "schemaVersion": 1,
+        "label": "mutation score",
+        "message": message,
+        "color": color,
+    })
+}
+
+fn score_color<'a>(score: f64, colors: &[(&'a str, f64)]) -> &'a str {
+    colors
+        .iter()
+        .filter(|(_, min)| score >= *min)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).expect("thresholds are comparable"))
+        .map_or("red", |(color, _)| color)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn default_colors() {
+        assert_eq!(score_color(100.0, DEFAULT_BADGE_COLORS), "brightgreen");
+        assert_eq!(score_color(90.0, DEFAULT_BADGE_COLORS), "brightgreen");
+        assert_eq!(score_color(87.0, DEFAULT_BADGE_COLORS), "green");
+        assert_eq!(score_color(60.0, DEFAULT_BADGE_COLORS), "yellow");
+        assert_eq!(score_color(45.5, DEFAULT_BADGE_COLORS), "orange");
+        assert_eq!(score_color(0.0, DEFAULT_BADGE_COLORS), "red");
+    }
+
+    #[test]
+    fn configured_colors_in_any_order() {
+        let colors = [("blue", 50.0), ("purple", 80.0)];
+        assert_eq!(score_color(85.0, &colors), "purple");
+        assert_eq!(score_color(79.9, &colors), "blue");
+        assert_eq!(score_color(10.0, &colors), "red");
+    }
+
+    #[test]
+    fn badge_json() {
+        let record: LabOutcomeRecord = serde_json::from_str(
+            r#"{ "outcomes": [
+                { "scenario": "Baseline", "phase_results": [] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs",
+                    "span": { "start": { "line": 2, "column": 1 }, "end": { "line": 4, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0",
+                    "description": "replace f with 0 in src/lib.rs:2"
+                } } },
+                "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Failure" }
+                ] }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            badge(&record, DEFAULT_BADGE_COLORS),
+            json!({
+                "schemaVersion": 1,
+                "label": "mutation score",
+                "message": "100%",
+                "color": "brightgreen",
+            })
+        );
+        let empty: LabOutcomeRecord = serde_json::from_str(r#"{ "outcomes": [] }"#).unwrap();
+        assert_eq!(badge(&empty, DEFAULT_BADGE_COLORS)["color"], "lightgrey");
+    }
+}
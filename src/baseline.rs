@@ -0,0 +1,236 @@
+// Copyright 2022 Martin Pool
+
+//! Decide whether to test the unmutated baseline, for `--baseline`, and remember a
+//! successful baseline so that a later run of an unchanged tree can reuse it.
+//!
+//! The tree is identified by a hash of the path and content of every file that's copied
+//! to the scratch directory, other than `target` and version control metadata in `.git`,
+//! together with the options that change how it's built and tested.
+//!
+//! Files ignored by git are still hashed, because they're copied too and may affect the
+//! build.
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use path_slash::PathExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::output::{OUTDIR_NAME, ROTATED_NAME};
+use crate::*;
+
+/// The name of the file in the output directory recording a successful baseline.
+pub const BASELINE_FILE_NAME: &str = "baseline.json";
+
+/// Whether to test the unmutated tree before the mutants.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BaselineStrategy {
+    /// Always test the baseline.
+    Run,
+    /// Reuse the baseline of the previous run if the tree hasn't changed, and otherwise
+    /// test it.
+    #[default]
+    Auto,
+    /// Trust that the tree builds and passes its tests.
+    Skip,
+}
+
+impl FromStr for BaselineStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "run" => Ok(BaselineStrategy::Run),
+            "auto" => Ok(BaselineStrategy::Auto),
+            "skip" => Ok(BaselineStrategy::Skip),
+            _ => Err(anyhow!(
+                "unknown baseline strategy {:?}: expected run, auto, or skip",
+                s
+            )),
+        }
+    }
+}
+
+/// A successful baseline, recorded in `baseline.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineRecord {
+    /// The hash of the tree and options, from [tree_hash].
+    pub tree_hash: String,
+    /// How long the longest baseline check or build took, to set the build timeout.
+    #[serde(default)]
+    pub build_duration: Option<Duration>,
+    /// How long the baseline tests took, to set the test timeout.
+    pub test_duration: Option<Duration>,
+    /// The number of tests run by the baseline, if known.
+    pub tests_run: Option<usize>,
+}
+
+impl BaselineRecord {
+    /// Read the record from an output directory, or return None if there is none.
+    pub fn read(output_dir: &Path) -> Result<Option<BaselineRecord>> {
+        let path = output_dir.join(BASELINE_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path)
+            .with_context(|| format!("read baseline from {}", path.to_slash_lossy()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("parse baseline from {}", path.to_slash_lossy()))
+    }
+
+    /// Write the record into an output directory.
+    pub fn write(&self, output_dir: &Path) -> Result<()> {
+        let path = output_dir.join(BASELINE_FILE_NAME);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).with_context(|| format!("write {}", path.to_slash_lossy()))
+    }
+}
+
+/// Return a hex hash of the files in a source tree that are copied to the scratch
+/// directory, and of the options that affect the baseline.
+pub fn tree_hash(root: &Path, options: &Options) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(
+        format!(
+            "{:?}\0",
+            (
+                options.check_only,
+                &options.additional_cargo_args,
+                &options.additional_cargo_test_args,
+                &options.test_args,
+                &options.features,
+                &options.profile,
+                options.workspace,
+                &options.packages,
+                &options.exclude_packages,
+            )
+        )
+        .as_bytes(),
+    );
+    let skipped_dirs = [
+        Path::new("target"),
+        Path::new(OUTDIR_NAME),
+        Path::new(ROTATED_NAME),
+    ];
+    let walk = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            // `.git` is a directory in a repository, or a file in a worktree or submodule,
+            // and changes with every fetch or commit, without changing the tree.
+            entry.file_name() != ".git"
+                && !(entry.file_type().is_dir()
+                    && entry
+                        .path()
+                        .strip_prefix(root)
+                        .is_ok_and(|path| skipped_dirs.contains(&path)))
+        });
+    for entry in walk {
+        let entry = entry.with_context(|| format!("walk {}", root.to_slash_lossy()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root).unwrap().to_slash_lossy();
+        let content = fs::read(entry.path())
+            .with_context(|| format!("read {}", entry.path().to_slash_lossy()))?;
+        hasher.update(relative.as_bytes());
+        hasher.update(b"\0");
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// True if the compiler errors in a cargo log all point at files other than the mutated
+/// one, which suggests that the unmutated tree doesn't build either.
+///
+/// `mutated_file` is relative to the tree, with forward slashes; paths in the log may
+/// be relative to a package within it.
+pub fn errors_are_elsewhere(log: &str, mutated_file: &str) -> bool {
+    let mut error_paths = Vec::new();
+    let mut in_error = false;
+    for line in log.lines() {
+        if line.starts_with("error") {
+            in_error = true;
+        } else if line.starts_with("warning") {
+            in_error = false;
+        } else if let Some(location) = line.trim_start().strip_prefix("--> ") {
+            if in_error {
+                let path = location.split(':').next().unwrap().replace('\\', "/");
+                error_paths.push(path);
+                in_error = false;
+            }
+        }
+    }
+    !error_paths.is_empty()
+        && !error_paths
+            .iter()
+            .any(|path| mutated_file == path || mutated_file.ends_with(&format!("/{}", path)))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_strategies() {
+        assert_eq!(
+            "skip".parse::<BaselineStrategy>().unwrap(),
+            BaselineStrategy::Skip
+        );
+        assert_eq!(BaselineStrategy::default(), BaselineStrategy::Auto);
+        assert_eq!(
+            "never".parse::<BaselineStrategy>().unwrap_err().to_string(),
+            "unknown baseline strategy \"never\": expected run, auto, or skip"
+        );
+    }
+
+    #[test]
+    fn tree_hash_changes_with_source_but_not_target_or_git() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        let options = Options::default();
+        let hash = tree_hash(root, &options).unwrap();
+        fs::write(root.join("target/debug/output"), "built").unwrap();
+        assert_eq!(tree_hash(root, &options).unwrap(), hash);
+        fs::create_dir_all(root.join(".git/refs")).unwrap();
+        fs::write(root.join(".git/refs/HEAD"), "abc").unwrap();
+        fs::create_dir_all(root.join("vendor/dep")).unwrap();
+        fs::write(
+            root.join("vendor/dep/.git"),
+            "gitdir: ../../.git/modules/dep",
+        )
+        .unwrap();
+        let hash = tree_hash(root, &options).unwrap();
+        fs::write(root.join(".git/refs/HEAD"), "def").unwrap();
+        fs::write(root.join("vendor/dep/.git"), "gitdir: elsewhere").unwrap();
+        assert_eq!(tree_hash(root, &options).unwrap(), hash);
+        fs::write(root.join("src/lib.rs"), "fn b() {}\n").unwrap();
+        assert_ne!(tree_hash(root, &options).unwrap(), hash);
+    }
+
+    #[test]
+    fn errors_in_other_files_are_elsewhere() {
+        let log = "\
+warning: unused variable: `x`
+ --> src/lib.rs:2:9
+error[E0308]: mismatched types
+  --> src/other.rs:10:5
+   |
+";
+        assert!(errors_are_elsewhere(log, "src/lib.rs"));
+        assert!(!errors_are_elsewhere(log, "src/other.rs"));
+        assert!(!errors_are_elsewhere(log, "utils/src/other.rs"));
+        assert!(!errors_are_elsewhere("test result: FAILED", "src/lib.rs"));
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2022 Martin Pool
+
+//! Remember the outcome of each mutant, so that `--iterate` can skip mutants that were
+//! already caught and whose code hasn't changed since.
+//!
+//! Mutants are keyed by a hash of the text of the function containing them, together
+//! with the change made, so that editing the function, or any change in what's
+//! generated for it, causes its mutants to be tested again. The position of the
+//! function in the file doesn't matter, so unrelated edits elsewhere in the file don't
+//! invalidate the cache.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use path_slash::PathExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::mutate::Mutation;
+use crate::outcome::MutantStatus;
+use crate::*;
+
+/// The name of the cache file within the output directory.
+pub const CACHE_FILE_NAME: &str = "cache.json";
+
+/// The outcomes of mutants from previous runs, by their key.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    mutants: BTreeMap<String, MutantStatus>,
+}
+
+impl Cache {
+    /// Read the cache from the given output directory, or return an empty cache if there
+    /// is none.
+    pub fn read(output_dir: &Path) -> Result<Cache> {
+        let path = output_dir.join(CACHE_FILE_NAME);
+        if !path.exists() {
+            return Ok(Cache::default());
+        }
+        let json = fs::read_to_string(&path)
+            .with_context(|| format!("read cache from {}", path.to_slash_lossy()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("parse cache from {}", path.to_slash_lossy()))
+    }
+
+    /// Write the cache into the given output directory.
+    pub fn write(&self, output_dir: &Path) -> Result<()> {
+        let path = output_dir.join(CACHE_FILE_NAME);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).with_context(|| format!("write {}", path.to_slash_lossy()))
+    }
+
+    /// True if this mutant, with its function unchanged, was caught before.
+    pub fn was_caught(&self, mutation: &Mutation) -> bool {
+        self.mutants.get(&mutation_key(mutation)) == Some(&MutantStatus::Caught)
+    }
+
+    /// Remember the status of a mutant.
+    pub fn insert(&mut self, mutation: &Mutation, status: MutantStatus) {
+        self.mutants.insert(mutation_key(mutation), status);
+    }
+}
+
+/// Return a hex hash identifying a mutant and the code of the function it's in.
+pub fn mutation_key(mutation: &Mutation) -> String {
+    let function_span = mutation.function_span();
+    let span = mutation.span();
+    // The position of the mutation relative to the start of the function body, so that
+    // mutants of identical text within one function are distinguished.
+    let line = span.start.line - function_span.start.line;
+    let column = if line == 0 {
+        span.start.column - function_span.start.column
+    } else {
+        span.start.column
+    };
+    let mut hasher = Sha256::new();
+    for part in [
+        mutation.source_file.tree_relative_slashes(),
+        mutation.function_name().to_owned(),
+        mutation.function_text(),
+        format!("{:?}", mutation.genre()),
+        format!("{}:{}", line, column),
+        mutation.original_text().to_owned(),
+        mutation.replacement_text().to_owned(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::mutate::Genre;
+    use crate::source::SourceFile;
+
+    fn discover(dir: &Path, code: &str) -> Vec<Mutation> {
+        fs::write(dir.join("lib.rs"), code).unwrap();
+        let source_file = SourceFile::new(dir, Path::new("lib.rs")).unwrap();
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        source_file.discover(&options).unwrap().mutations
+    }
+
+    /// Return the keys of the operator mutants in a file with this code.
+    fn operator_keys(code: &str) -> Vec<String> {
+        let temp = tempfile::tempdir().unwrap();
+        discover(temp.path(), code)
+            .iter()
+            .filter(|m| m.genre() == Genre::BinaryOperator)
+            .map(mutation_key)
+            .collect()
+    }
+
+    const ADD: &str = "fn add(a: u32, b: u32) -> u32 {\n    a + b + 1\n}\n";
+
+    #[test]
+    fn keys_are_distinct_within_a_function() {
+        let keys = operator_keys(ADD);
+        assert_eq!(keys.len(), 2);
+        let unique: std::collections::BTreeSet<&String> = keys.iter().collect();
+        assert_eq!(unique.len(), keys.len());
+    }
+
+    #[test]
+    fn keys_are_unchanged_when_the_function_moves() {
+        let moved = format!("// A comment.\n\nfn other() {{}}\n\n{}", ADD);
+        let keys = operator_keys(ADD);
+        assert!(!keys.is_empty());
+        assert_eq!(keys, operator_keys(&moved));
+    }
+
+    #[test]
+    fn keys_change_when_the_function_changes() {
+        let edited = ADD.replace("1", "2");
+        let keys = operator_keys(ADD);
+        assert!(!keys.is_empty());
+        assert!(operator_keys(&edited).iter().all(|k| !keys.contains(k)));
+    }
+
+    #[test]
+    fn only_caught_mutants_are_remembered_as_caught() {
+        let temp = tempfile::tempdir().unwrap();
+        let mutations = discover(temp.path(), ADD);
+        let mut cache = Cache::default();
+        cache.insert(&mutations[0], MutantStatus::Caught);
+        cache.insert(&mutations[1], MutantStatus::Missed);
+        cache.write(temp.path()).unwrap();
+
+        let cache = Cache::read(temp.path()).unwrap();
+        assert!(cache.was_caught(&mutations[0]));
+        assert!(!cache.was_caught(&mutations[1]));
+        assert!(!cache.was_caught(&mutations[2]));
+    }
+
+    #[test]
+    fn missing_cache_is_empty() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(Cache::read(temp.path()).unwrap().mutants.is_empty());
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2022 Martin Pool
+
+//! Generate shell completion scripts, for `--completions`.
+//!
+//! The flags are read from the `--help` text that argh generates from the definition of
+//! the arguments, so the scripts always match the real flags. Only the values of a few
+//! options, such as `--color`, are listed here; other options that take a value
+//! complete file names, where the shell can.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use regex::Regex;
+
+use crate::*;
+
+/// A shell that completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl FromStr for Shell {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" => Ok(Shell::Powershell),
+            _ => Err(anyhow!(
+                "unknown shell {:?}: expected bash, zsh, fish, or powershell",
+                s
+            )),
+        }
+    }
+}
+
+/// The values of options that take one of a fixed set.
+const OPTION_VALUES: &[(&str, &[&str])] = &[
+    ("--annotation-level", &["warning", "error"]),
+    ("--annotations", &["github", "none"]),
+    ("--baseline", &["run", "auto", "skip"]),
+    ("--color", &["auto", "always", "never"]),
+    ("--completions", &["bash", "zsh", "fish", "powershell"]),
+];
+
+/// One flag, as described by `--help`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Flag {
+    long: String,
+    short: Option<char>,
+    takes_value: bool,
+    description: String,
+}
+
+impl Flag {
+    /// The fixed values this flag can take, if it's one of those listed.
+    fn values(&self) -> Option<&'static [&'static str]> {
+        OPTION_VALUES
+            .iter()
+            .find(|(name, _)| *name == self.long)
+            .map(|(_, values)| *values)
+    }
+
+    /// The long name, and the short name if there is one.
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.short.iter().map(|c| format!("-{}", c)).collect();
+        names.push(self.long.clone());
+        names
+    }
+}
+
+/// Return a completion script for `cargo mutants` and `cargo-mutants`, from the `--help`
+/// text of the arguments.
+pub fn completion_script(shell: Shell, help: &str) -> String {
+    let flags = flags_from_help(help);
+    match shell {
+        Shell::Bash => bash_script(&flags),
+        Shell::Zsh => zsh_script(&flags),
+        Shell::Fish => fish_script(&flags),
+        Shell::Powershell => powershell_script(&flags),
+    }
+}
+
+/// Find the flags in argh's `--help` text.
+///
+/// The options section lists each flag's names and description, and the usage line
+/// shows which of them take a value.
+fn flags_from_help(help: &str) -> Vec<Flag> {
+    let usage = help.lines().next().unwrap_or_default();
+    let valued: Vec<&str> = Regex::new(r"\[(-[-\w]+) <")
+        .unwrap()
+        .captures_iter(usage)
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .collect();
+    let names = Regex::new(r"^  (?:-(\w), )?(--[-\w]+)\s*(.*)$").unwrap();
+    let mut flags: Vec<Flag> = Vec::new();
+    for line in help
+        .lines()
+        .skip_while(|line| *line != "Options:")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+    {
+        if let Some(captures) = names.captures(line) {
+            let short = captures
+                .get(1)
+                .and_then(|short| short.as_str().chars().next());
+            let long = captures[2].to_owned();
+            let takes_value = valued.iter().any(|name| {
+                *name == long || short.is_some_and(|short| *name == format!("-{}", short))
+            });
+            flags.push(Flag {
+                long,
+                short,
+                takes_value,
+                description: captures[3].to_owned(),
+            });
+        } else if let Some(flag) = flags.last_mut() {
+            if !flag.description.is_empty() {
+                flag.description.push(' ');
+            }
+            flag.description.push_str(line.trim());
+        }
+    }
+    flags
+}
+
+fn bash_script(flags: &[Flag]) -> String {
+    let mut script = String::from(
+        "# bash completion for cargo-mutants\n\
+         \n\
+         _cargo_mutants() {\n\
+         \x20   local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n\
+         \x20   local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n\
+         \x20   if [[ ${COMP_WORDS[0]} == cargo && ${COMP_WORDS[1]} != mutants ]]; then\n\
+         \x20       # Leave other cargo subcommands to cargo's own completion, if any.\n\
+         \x20       if declare -F _cargo >/dev/null; then\n\
+         \x20           _cargo \"$@\"\n\
+         \x20       fi\n\
+         \x20       return\n\
+         \x20   fi\n\
+         \x20   case \"$prev\" in\n",
+    );
+    for flag in flags.iter().filter(|flag| flag.takes_value) {
+        let completion = match flag.values() {
+            Some(values) => format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                values.join(" ")
+            ),
+            // Leave it to the default completion, of file names.
+            None => "COMPREPLY=()".to_owned(),
+        };
+        writeln!(
+            script,
+            "        {})\n            {}\n            return\n            ;;",
+            flag.names().join("|"),
+            completion
+        )
+        .unwrap();
+    }
+    let all_names: Vec<String> = flags.iter().flat_map(Flag::names).collect();
+    write!(
+        script,
+        "    esac\n    \
+         COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n\
+         }}\n\
+         \n\
+         complete -o default -F _cargo_mutants cargo-mutants\n\
+         complete -o default -F _cargo_mutants cargo\n",
+        all_names.join(" ")
+    )
+    .unwrap();
+    script
+}
+
+fn zsh_script(flags: &[Flag]) -> String {
+    let mut script = String::from(
+        "#compdef cargo-mutants\n\
+         \n\
+         _cargo-mutants() {\n\
+         \x20   _arguments -s \\\n",
+    );
+    for flag in flags {
+        let description = zsh_escape(&flag.description);
+        let value = if !flag.takes_value {
+            String::new()
+        } else if let Some(values) = flag.values() {
+            format!(
+                ":{}:({})",
+                flag.long.trim_start_matches('-'),
+                values.join(" ")
+            )
+        } else {
+            format!(":{}:_files", flag.long.trim_start_matches('-'))
+        };
+        let spec = match flag.short {
+            Some(short) => format!(
+                "'(-{short} {long})'{{-{short},{long}}}'[{}]{}'",
+                description,
+                value,
+                short = short,
+                long = flag.long
+            ),
+            None => format!("'{}[{}]{}'", flag.long, description, value),
+        };
+        writeln!(script, "        {} \\", spec).unwrap();
+    }
+    script.push_str(
+        "        '*::cargo arguments:_files'\n\
+         }\n\
+         \n\
+         compdef _cargo-mutants cargo-mutants\n",
+    );
+    script
+}
+
+/// Escape a description for a single-quoted `_arguments` spec.
+fn zsh_escape(description: &str) -> String {
+    description
+        .replace('\\', r"\\")
+        .replace('\'', r"'\''")
+        .replace('`', r"\`")
+        .replace('$', r"\$")
+        .replace('[', r"\[")
+        .replace(']', r"\]")
+        .replace(':', r"\:")
+}
+
+fn fish_script(flags: &[Flag]) -> String {
+    let mut script = String::from(
+        "# fish completion for cargo-mutants\n\
+         complete -c cargo -n __fish_use_subcommand -f -a mutants -d 'Find inadequately-tested code'\n",
+    );
+    for (command, condition) in [
+        ("cargo", " -n '__fish_seen_subcommand_from mutants'"),
+        ("cargo-mutants", ""),
+    ] {
+        for flag in flags {
+            write!(
+                script,
+                "complete -c {}{} -l {}",
+                command,
+                condition,
+                flag.long.trim_start_matches('-')
+            )
+            .unwrap();
+            if let Some(short) = flag.short {
+                write!(script, " -s {}", short).unwrap();
+            }
+            if let Some(values) = flag.values() {
+                write!(script, " -x -a '{}'", values.join(" ")).unwrap();
+            } else if flag.takes_value {
+                script.push_str(" -r");
+            }
+            writeln!(script, " -d {}", fish_quote(&flag.description)).unwrap();
+        }
+    }
+    script
+}
+
+/// Quote a string for fish.
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn powershell_script(flags: &[Flag]) -> String {
+    let mut script = String::from(
+        "# PowerShell completion for cargo-mutants\n\
+         \n\
+         Register-ArgumentCompleter -Native -CommandName cargo, cargo-mutants -ScriptBlock {\n\
+         \x20   param($wordToComplete, $commandAst, $cursorPosition)\n\
+         \x20   $elements = @($commandAst.CommandElements | ForEach-Object { $_.ToString() })\n\
+         \x20   if ($elements.Count -lt 2 -or $elements[1] -ne 'mutants') {\n\
+         \x20       return\n\
+         \x20   }\n\
+         \x20   $flags = @(\n",
+    );
+    for flag in flags {
+        for name in flag.names() {
+            writeln!(
+                script,
+                "        @{{ Name = '{}'; Description = '{}' }}",
+                name,
+                flag.description.replace('\'', "''")
+            )
+            .unwrap();
+        }
+    }
+    script.push_str(
+        "    )\n\
+         \x20   $flags | Where-Object { $_.Name -like \"$wordToComplete*\" } | ForEach-Object {\n\
+         \x20       [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterName', $_.Description)\n\
+         \x20   }\n\
+         }\n",
+    );
+    script
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const HELP: &str = "\
+Usage: mutants [--all-logs] [--color <color>] [-d <dir>] [-v] [--error <error...>]
+
+Find inadequately-tested code.
+
+Options:
+  --all-logs        show cargo output for all invocations (very verbose).
+  --color           color output: auto, always, or never; by default, colors are
+                    used on terminals.
+  -d, --dir         rust crate directory to examine.
+  -v, --caught      print mutations that were caught by tests.
+  --error           return this error value from functions returning Result:
+                    can be repeated.
+  --help            display usage information
+
+Error codes:
+  0 all mutants were caught.
+";
+
+    #[test]
+    fn parse_shells() {
+        assert_eq!("fish".parse::<Shell>().unwrap(), Shell::Fish);
+        assert_eq!(
+            "csh".parse::<Shell>().unwrap_err().to_string(),
+            "unknown shell \"csh\": expected bash, zsh, fish, or powershell"
+        );
+    }
+
+    #[test]
+    fn flags_are_read_from_help() {
+        let flags = flags_from_help(HELP);
+        assert_eq!(
+            flags
+                .iter()
+                .map(|flag| flag.long.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "--all-logs",
+                "--color",
+                "--dir",
+                "--caught",
+                "--error",
+                "--help"
+            ]
+        );
+        assert_eq!(
+            flags[1],
+            Flag {
+                long: "--color".to_owned(),
+                short: None,
+                takes_value: true,
+                description:
+                    "color output: auto, always, or never; by default, colors are used on terminals."
+                        .to_owned(),
+            }
+        );
+        assert_eq!((flags[2].short, flags[2].takes_value), (Some('d'), true));
+        assert_eq!((flags[3].short, flags[3].takes_value), (Some('v'), false));
+        assert!(flags[4].takes_value);
+        assert!(!flags[5].takes_value);
+    }
+
+    #[test]
+    fn scripts_complete_flags_and_values() {
+        let bash = completion_script(Shell::Bash, HELP);
+        assert!(bash.contains("-d|--dir)\n            COMPREPLY=()\n"));
+        assert!(bash.contains("compgen -W \"auto always never\""));
+        assert!(bash.contains("\"--all-logs --color -d --dir -v --caught --error --help\""));
+
+        let zsh = completion_script(Shell::Zsh, HELP);
+        assert!(
+            zsh.contains("'(-d --dir)'{-d,--dir}'[rust crate directory to examine.]:dir:_files'")
+        );
+        assert!(zsh.contains(
+            "'--color[color output\\: auto, always, or never; by default, colors are used on terminals.]:color:(auto always never)'"
+        ));
+
+        let fish = completion_script(Shell::Fish, HELP);
+        assert!(fish.contains(
+            "complete -c cargo-mutants -l caught -s v -d 'print mutations that were caught by tests.'\n"
+        ));
+        assert!(fish.contains(
+            "complete -c cargo -n '__fish_seen_subcommand_from mutants' -l dir -s d -r -d"
+        ));
+
+        let powershell = completion_script(Shell::Powershell, HELP);
+        assert!(powershell
+            .contains("@{ Name = '--all-logs'; Description = 'show cargo output for all invocations (very verbose).' }"));
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright 2022 Martin Pool
+
+//! `.cargo/mutants.toml` configuration file.
+//!
+//! The config file is read from the source tree, so that the same settings are used
+//! everywhere the tree is tested, including in CI.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use path_slash::PathExt;
+use serde::Deserialize;
+
+use crate::source::SourceTree;
+use crate::*;
+
+/// The path of the config file, relative to the root of the source tree.
+pub const CONFIG_PATH: &str = ".cargo/mutants.toml";
+
+/// Configuration read from a config file.
+///
+/// This is similar to [Options], and eventually merged into it, but separate because it
+/// can be read from a file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Additional arguments to every `cargo check`, `cargo build`, and `cargo test`,
+    /// before those from the command line.
+    pub additional_cargo_args: Vec<String>,
+
+    /// Additional arguments to `cargo test`: those after `--` are passed to the test
+    /// binaries, before those from the command line.
+    pub additional_cargo_test_args: Vec<String>,
+
+    /// Colors for `badge.json`, with the minimum mutation score for each.
+    pub badge_colors: BTreeMap<String, f64>,
+
+    /// The timeout, in seconds, for each `cargo check` or `cargo build`, like
+    /// `--build-timeout`.
+    pub build_timeout: Option<f64>,
+
+    /// The baseline build time is multiplied by this to set the build timeout, like
+    /// `--build-timeout-multiplier`.
+    pub build_timeout_multiplier: Option<f64>,
+
+    /// Expressions to return inside `Err(...)` from functions returning `Result`, after
+    /// those given with `--error`.
+    pub error_values: Vec<String>,
+
+    /// If not empty, only files whose tree-relative paths match one of these globs are
+    /// mutated.
+    pub examine_globs: Vec<String>,
+
+    /// If not empty, only mutants whose names match one of these regexps are tested.
+    pub examine_re: Vec<String>,
+
+    /// Regexps matched against the names of functions that should not be mutated.
+    pub exclude_functions: Vec<String>,
+
+    /// Files whose tree-relative paths match any of these globs are not mutated.
+    pub exclude_globs: Vec<String>,
+
+    /// Names of workspace packages that are not mutated or tested.
+    pub exclude_packages: Vec<String>,
+
+    /// Mutants whose names match any of these regexps are not tested.
+    pub exclude_re: Vec<String>,
+
+    /// The shortest test timeout, in seconds, that's set automatically from the baseline.
+    pub minimum_test_timeout: Option<f64>,
+
+    /// Print mutations that were caught by tests, like `--caught`.
+    pub print_caught: Option<bool>,
+
+    /// Print mutations that failed to check or build, like `--unviable`.
+    pub print_unviable: Option<bool>,
+
+    /// Show cargo output for all invocations, like `--all-logs`.
+    pub show_all_logs: Option<bool>,
+
+    /// Print times and tree sizes: false is like `--no-times`.
+    pub show_times: Option<bool>,
+
+    /// Names of functions and methods whose calls should not be deleted, as well as those
+    /// given with `--skip-calls`.
+    pub skip_calls: Vec<String>,
+
+    /// False to delete calls to the default `skip_calls`, like `drop` and `lock`.
+    pub skip_calls_defaults: Option<bool>,
+
+    /// The timeout, in seconds, for each `cargo test`, like `--timeout`.
+    pub timeout: Option<f64>,
+
+    /// The baseline test time is multiplied by this to set the test timeout.
+    pub timeout_multiplier: Option<f64>,
+
+    /// Expressions to return from functions returning these types, by type name.
+    pub type_replacements: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// Read the config from a file.
+    pub fn read_file(path: &Path) -> Result<Config> {
+        let toml = fs::read_to_string(path)
+            .with_context(|| format!("read config {}", path.to_slash_lossy()))?;
+        Config::from_str(&toml).with_context(|| format!("parse config {}", path.to_slash_lossy()))
+    }
+
+    /// Read the config from a source tree, or return the default if there's no config
+    /// file.
+    pub fn read_tree_config(source_tree: &SourceTree) -> Result<Config> {
+        let path = source_tree.root().join(CONFIG_PATH);
+        if path.exists() {
+            Config::read_file(&path)
+        } else {
+            Ok(Config::default())
+        }
+    }
+
+    fn from_str(toml: &str) -> Result<Config> {
+        toml::de::from_str(toml).map_err(anyhow::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_exclude_functions() {
+        let config = Config::from_str(
+            r#"
+            exclude_functions = ["^ffi_", "::fmt$"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.exclude_functions, ["^ffi_", "::fmt$"]);
+    }
+
+    #[test]
+    fn parse_examine_and_exclude_re() {
+        let config = Config::from_str(
+            r#"
+            examine_re = ["replace parse_"]
+            exclude_re = ["impl Debug", "fmt::Display"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.examine_re, ["replace parse_"]);
+        assert_eq!(config.exclude_re, ["impl Debug", "fmt::Display"]);
+    }
+
+    #[test]
+    fn parse_examine_and_exclude_globs() {
+        let config = Config::from_str(
+            r#"
+            examine_globs = ["src/core/**"]
+            exclude_globs = ["src/generated/**", "**/schema.rs"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.examine_globs, ["src/core/**"]);
+        assert_eq!(config.exclude_globs, ["src/generated/**", "**/schema.rs"]);
+    }
+
+    #[test]
+    fn parse_additional_cargo_args() {
+        let config = Config::from_str(
+            r#"
+            additional_cargo_args = ["--features", "test-helpers"]
+            additional_cargo_test_args = ["--", "--test-threads=1"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.additional_cargo_args, ["--features", "test-helpers"]);
+        assert_eq!(
+            config.additional_cargo_test_args,
+            ["--", "--test-threads=1"]
+        );
+    }
+
+    #[test]
+    fn parse_error_values() {
+        let config = Config::from_str(
+            r#"
+            error_values = ["::anyhow::anyhow!(\"mutated\")", "crate::Error::Mutated"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.error_values,
+            ["::anyhow::anyhow!(\"mutated\")", "crate::Error::Mutated"]
+        );
+    }
+
+    #[test]
+    fn parse_skip_calls() {
+        let config = Config::from_str(
+            r#"
+            skip_calls = ["measure", "with_context"]
+            skip_calls_defaults = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.skip_calls, ["measure", "with_context"]);
+        assert_eq!(config.skip_calls_defaults, Some(false));
+    }
+
+    #[test]
+    fn parse_exclude_packages() {
+        let config = Config::from_str(r#"exclude_packages = ["fuzz", "xtask"]"#).unwrap();
+        assert_eq!(config.exclude_packages, ["fuzz", "xtask"]);
+    }
+
+    #[test]
+    fn parse_minimum_test_timeout() {
+        let config = Config::from_str("minimum_test_timeout = 60").unwrap();
+        assert_eq!(config.minimum_test_timeout, Some(60.0));
+        let config = Config::from_str("minimum_test_timeout = 2.5").unwrap();
+        assert_eq!(config.minimum_test_timeout, Some(2.5));
+    }
+
+    #[test]
+    fn parse_timeout_multiplier() {
+        let config = Config::from_str("timeout_multiplier = 1.5").unwrap();
+        assert_eq!(config.timeout_multiplier, Some(1.5));
+    }
+
+    #[test]
+    fn parse_timeouts() {
+        let config = Config::from_str(
+            r#"
+            timeout = 300
+            build_timeout = 120.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.timeout, Some(300.0));
+        assert_eq!(config.build_timeout, Some(120.5));
+        assert_eq!(config.build_timeout_multiplier, None);
+        let config = Config::from_str("build_timeout_multiplier = 3").unwrap();
+        assert_eq!(config.build_timeout_multiplier, Some(3.0));
+    }
+
+    #[test]
+    fn parse_output_switches() {
+        let config = Config::from_str(
+            r#"
+            print_caught = true
+            print_unviable = false
+            show_all_logs = true
+            show_times = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.print_caught, Some(true));
+        assert_eq!(config.print_unviable, Some(false));
+        assert_eq!(config.show_all_logs, Some(true));
+        assert_eq!(config.show_times, Some(false));
+        assert!(Config::from_str("show_times = \"no\"").is_err());
+    }
+
+    #[test]
+    fn parse_badge_colors() {
+        let config = Config::from_str(
+            r#"
+            [badge_colors]
+            green = 80
+            yellow = 50.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.badge_colors["green"], 80.0);
+        assert_eq!(config.badge_colors["yellow"], 50.5);
+    }
+
+    #[test]
+    fn parse_type_replacements() {
+        let config = Config::from_str(
+            r#"
+            [type_replacements]
+            Meters = ["Meters(0.0)", "Meters(1.0)"]
+            "net::Port" = ["net::Port(80)"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.type_replacements.keys().collect::<Vec<_>>(),
+            ["Meters", "net::Port"]
+        );
+        assert_eq!(
+            config.type_replacements["Meters"],
+            ["Meters(0.0)", "Meters(1.0)"]
+        );
+    }
+
+    #[test]
+    fn empty_config_is_default() {
+        assert_eq!(Config::from_str("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn unknown_keys_are_errors() {
+        let err = Config::from_str("exclude_function = []").unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{}", err);
+    }
+}
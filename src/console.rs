@@ -2,74 +2,386 @@
 
 //! Print messages and progress bars on the terminal.
 
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-use ::console::{style, StyledObject};
-use anyhow::Result;
-use indicatif::{ProgressBar, ProgressStyle};
+use ::console::{style, Color, StyledObject, Term};
+use anyhow::{anyhow, Result};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use itertools::Itertools;
+use similar::{ChangeTag, TextDiff};
 
+use crate::annotation::missed_mutant_annotation;
+use crate::events;
 use crate::lab::Scenario;
-use crate::mutate::Mutation;
-use crate::outcome::{Outcome, Phase};
+use crate::mutate::{Genre, Mutation};
+use crate::outcome::{LabOutcome, MutantStatus, Outcome, Phase, Warning};
+use crate::tap;
+use crate::visit::SkipReason;
 use crate::*;
 
+/// The number of slowest mutants listed at the end of a run, if no other number is given.
+pub const DEFAULT_SLOWEST: usize = 10;
+
+/// The progress bar of a scenario.
+const ACTIVITY_TEMPLATE: &str = "{msg} ... {elapsed:.cyan} {spinner:.cyan}";
+
+/// How results are printed on stdout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Text for people to read, with progress bars.
+    #[default]
+    Human,
+    /// TAP, with other messages as comments.
+    Tap,
+    /// Newline-delimited JSON events, with other messages on stderr.
+    Json,
+}
+
+/// Whether to color output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Colors {
+    /// Color output to terminals, following `CLICOLOR` and `CLICOLOR_FORCE`.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for Colors {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Colors::Auto),
+            "always" => Ok(Colors::Always),
+            "never" => Ok(Colors::Never),
+            _ => Err(anyhow!(
+                "unknown color choice {:?}: expected auto, always, or never",
+                s
+            )),
+        }
+    }
+}
+
 /// Top-level UI object that manages the state of an interactive console: mostly progress bars and
 /// messages.
 pub struct Console {
     show_times: bool,
+    /// Mutants are only checked, not tested.
+    check_only: bool,
+    /// Progress bars are only drawn in [OutputMode::Human].
+    mode: OutputMode,
 }
 
 impl Console {
     /// Construct a new rich text UI.
     pub fn new(options: &Options) -> Console {
+        let enabled = match options.colors {
+            Colors::Auto => None,
+            Colors::Always => Some(true),
+            Colors::Never => Some(false),
+        };
+        if let Some(enabled) = enabled {
+            ::console::set_colors_enabled(enabled);
+            ::console::set_colors_enabled_stderr(enabled);
+        }
         Console {
             show_times: options.show_times,
+            check_only: options.check_only,
+            mode: options.output_mode,
         }
     }
 
-    pub fn start_scenario(&self, scenario: &Scenario) -> Activity {
-        match scenario {
-            Scenario::SourceTree => self.start_activity("source tree"),
-            Scenario::Baseline => self.start_activity("unmutated baseline"),
-            Scenario::Mutant {
-                mutation,
-                i_mutation,
-                n_mutations,
-            } => {
-                let mut activity = self.start_activity(&style_mutation(mutation));
-                activity.overall_progress = Some((i_mutation + 1, *n_mutations));
-                activity
+    /// Print a line of text: as a TAP comment in TAP mode, or to stderr in JSON mode.
+    pub fn message(&self, text: &str) {
+        match self.mode {
+            OutputMode::Human => println!("{}", text),
+            OutputMode::Tap => print!("{}", tap::comment(text)),
+            OutputMode::Json => eprintln!("{}", text),
+        }
+    }
+
+    /// Print a JSON event, in JSON mode.
+    fn event(&self, event: impl FnOnce() -> serde_json::Value) {
+        if self.mode == OutputMode::Json {
+            println!("{}", event());
+        }
+    }
+
+    /// Announce how many mutants will be tested, which in TAP mode is also the plan.
+    pub fn found_mutations(&self, n_mutations: usize, seed: Option<u64>) {
+        self.message(&format!(
+            "found {} {} to test",
+            n_mutations,
+            if n_mutations == 1 {
+                "mutation"
+            } else {
+                "mutations"
             }
+        ));
+        if self.mode == OutputMode::Tap {
+            println!("{}", tap::plan(n_mutations));
+        }
+        self.event(|| events::start(n_mutations, seed));
+    }
+
+    /// Print how many functions were not mutated, for each reason.
+    pub fn print_skipped(&self, skipped: &BTreeMap<SkipReason, usize>) {
+        for (reason, &count) in skipped {
+            self.message(&format!(
+                "skipped {} {}: {}",
+                count,
+                reason.noun(count),
+                reason
+            ));
+        }
+    }
+
+    /// Print the number of mutants with each outcome, and the mutation score.
+    pub fn print_summary(&self, lab_outcome: &LabOutcome) {
+        let totals = lab_outcome.totals();
+        let timeouts = if totals.timeout == 1 {
+            "timeout"
+        } else {
+            "timeouts"
+        };
+        let (verb, counts) = if self.check_only {
+            (
+                "checked",
+                format!(
+                    "{} ok, {} unviable, {} {}",
+                    totals.untested, totals.unviable, totals.timeout, timeouts
+                ),
+            )
+        } else {
+            (
+                "tested",
+                format!(
+                    "{} caught, {} missed, {} unviable, {} {}",
+                    totals.caught, totals.missed, totals.unviable, totals.timeout, timeouts
+                ),
+            )
+        };
+        let n = totals.total;
+        let noun = if n == 1 { "mutant" } else { "mutants" };
+        if self.show_times {
+            self.message(&format!(
+                "{} {} {} in {}: {}",
+                n,
+                noun,
+                verb,
+                format_duration(lab_outcome.elapsed()),
+                counts
+            ));
+        } else {
+            self.message(&format!("{} {} {}: {}", n, noun, verb, counts));
+        }
+        let cached = lab_outcome.cached_caught();
+        if cached > 0 {
+            self.message(&format!(
+                "{} {} caught (cached) in a previous run, not retested",
+                cached,
+                if cached == 1 { "mutant" } else { "mutants" }
+            ));
+        }
+        if let Some(score) = totals.score() {
+            self.message(&format!(
+                "mutation score: {:.1}% ({} of {} tested mutants caught)",
+                score,
+                totals.caught,
+                totals.caught + totals.missed
+            ));
+        }
+        if self.show_times && n > 0 {
+            let mutant_duration = lab_outcome.mutant_duration();
+            self.message(&format!(
+                "mutants took {} in total, {} on average",
+                format_duration(mutant_duration),
+                format_duration(mutant_duration / n as u32)
+            ));
         }
+        self.event(|| events::summary(lab_outcome));
     }
 
-    /// Start a general-purpose activity.
-    pub fn start_activity(&self, task: &str) -> Activity {
-        let progress_bar = ProgressBar::new(0)
-            .with_message(task.to_owned())
-            .with_style(
-                ProgressStyle::default_spinner()
-                    .template("{msg} ... {elapsed:.cyan} {spinner:.cyan}"),
-            );
+    /// Print the `n` slowest mutants with the time for each phase, and the total time
+    /// the mutants spent in each phase, unless times aren't shown.
+    pub fn print_slowest(&self, lab_outcome: &LabOutcome, n: usize) {
+        let slowest = lab_outcome.slowest_mutants(n);
+        if !self.show_times || slowest.is_empty() {
+            return;
+        }
+        self.message("slowest mutants:");
+        for outcome in slowest {
+            if let Scenario::Mutant { mutation, .. } = &outcome.scenario {
+                self.message(&format!(
+                    "  {} {} ({})",
+                    format_duration(outcome.duration()),
+                    style_mutation(mutation),
+                    outcome
+                        .phase_results()
+                        .iter()
+                        .map(|pr| format!("{} {}", pr.phase, format_duration(pr.duration)))
+                        .join(", ")
+                ));
+            }
+        }
+        self.message(&format!(
+            "total time in each phase: {}",
+            lab_outcome
+                .mutant_phase_durations()
+                .iter()
+                .map(|(phase, duration)| format!("{} {}", phase, format_duration(*duration)))
+                .join(", ")
+        ));
+    }
+
+    /// Print a warning about the results of the run.
+    pub fn print_warning(&self, warning: Warning) {
+        let msg = warning.message();
+        match self.mode {
+            OutputMode::Human => println!("{}: {}", style("warning").bold().yellow(), msg),
+            OutputMode::Tap => print!("{}", tap::comment(&format!("warning: {}", msg))),
+            OutputMode::Json => {
+                eprintln!("warning: {}", msg);
+                self.event(|| events::warning(warning));
+            }
+        }
+    }
+
+    /// Print an error that stops the lab, which in TAP mode bails out of the test run.
+    pub fn print_error(&self, msg: &str) {
+        match self.mode {
+            OutputMode::Human => println!("{}: {}", style("error").bold().red(), msg),
+            OutputMode::Tap => println!("Bail out! {}", msg),
+            OutputMode::Json => {
+                eprintln!("error: {}", msg);
+                self.event(|| events::error(msg));
+            }
+        }
+    }
+
+    fn new_progress_bar(&self, message: &str, template: &str) -> ProgressBar {
+        let progress_bar = if self.mode == OutputMode::Human {
+            ProgressBar::new(0)
+        } else {
+            ProgressBar::hidden()
+        };
+        let progress_bar = progress_bar
+            .with_message(message.to_owned())
+            .with_style(ProgressStyle::default_spinner().template(template));
         progress_bar.set_draw_rate(5); // updates per second
+        progress_bar
+    }
+
+    /// Start an activity for a scenario, shown on the progress bar of a job if it's one of
+    /// several running at once, or otherwise on its own progress bar.
+    pub fn start_scenario(&self, scenario: &Scenario, job: Option<&JobProgress>) -> Activity<'_> {
+        if self.mode == OutputMode::Json {
+            if let Some(event) = events::mutant_start(scenario) {
+                println!("{}", event);
+            }
+        }
+        let (task, overall_progress) = match scenario {
+            Scenario::SourceTree => ("source tree".to_owned(), None),
+            Scenario::Baseline => ("unmutated baseline".to_owned(), None),
+            Scenario::Mutant {
+                mutation,
+                i_mutation,
+                n_mutations,
+                ..
+            } => (
+                style_mutation(mutation),
+                Some((i_mutation + 1, *n_mutations)),
+            ),
+        };
+        let progress_bar = match job {
+            Some(job) => {
+                job.progress_bar.reset_elapsed();
+                job.progress_bar.set_message(task.clone());
+                job.progress_bar.clone()
+            }
+            None => self.new_progress_bar(&task, ACTIVITY_TEMPLATE),
+        };
         Activity {
-            task: task.to_owned(),
+            task,
             progress_bar,
+            shared: job.is_some(),
             start_time: Instant::now(),
             console: self,
-            overall_progress: None,
+            overall_progress,
         }
     }
 
+    /// Make a progress bar for each of `n_jobs` jobs running scenarios at once.
+    ///
+    /// The bars are drawn together by [JobsDisplay::draw], which returns once every
+    /// [JobProgress] is dropped. They're only drawn if both stdout and stderr are
+    /// terminals, because the outcomes are printed above the bars, on stderr.
+    pub fn start_jobs(&self, n_jobs: usize) -> (Vec<JobProgress>, JobsDisplay) {
+        let multi_progress = if self.mode == OutputMode::Human
+            && Term::stdout().is_term()
+            && Term::stderr().is_term()
+        {
+            Some(MultiProgress::new())
+        } else {
+            None
+        };
+        let jobs = (0..n_jobs)
+            .map(|_| {
+                let progress_bar = ProgressBar::hidden()
+                    .with_style(ProgressStyle::default_spinner().template(ACTIVITY_TEMPLATE));
+                JobProgress {
+                    progress_bar: match &multi_progress {
+                        Some(multi_progress) => multi_progress.add(progress_bar),
+                        None => progress_bar,
+                    },
+                }
+            })
+            .collect();
+        (jobs, JobsDisplay { multi_progress })
+    }
+
     /// Start an Activity for copying a tree.
-    pub fn start_copy_activity(&self, name: &str) -> CopyActivity {
+    pub fn start_copy_activity(&self, name: &str) -> CopyActivity<'_> {
         CopyActivity::new(name, self)
     }
 }
 
+/// The progress bar of one of several jobs running scenarios at once, which shows each
+/// scenario the job runs in turn.
+pub struct JobProgress {
+    progress_bar: ProgressBar,
+}
+
+impl Drop for JobProgress {
+    fn drop(&mut self) {
+        self.progress_bar.finish_and_clear();
+    }
+}
+
+/// Draws the progress bars of jobs running at once.
+pub struct JobsDisplay {
+    multi_progress: Option<MultiProgress>,
+}
+
+impl JobsDisplay {
+    /// Draw the progress bars until every job is finished, on the calling thread.
+    pub fn draw(self) -> Result<()> {
+        if let Some(multi_progress) = self.multi_progress {
+            multi_progress.join_and_clear()?;
+        }
+        Ok(())
+    }
+}
+
 pub struct Activity<'c> {
     pub start_time: Instant,
     progress_bar: ProgressBar,
+    /// True if the progress bar belongs to a job, and is kept after this activity.
+    shared: bool,
     task: String,
     console: &'c Console,
     /// Optionally, progress counter through the overall lab. Shown in the progress bar
@@ -88,8 +400,12 @@ impl<'c> Activity<'c> {
 
     /// Mark this activity as interrupted.
     pub fn interrupted(&mut self) {
-        self.progress_bar.finish_and_clear();
-        println!("{} ... {}", self.task, style("interrupted").bold().red());
+        self.finish();
+        self.console.message(&format!(
+            "{} ... {}",
+            self.task,
+            style("interrupted").bold().red()
+        ));
     }
 
     pub fn tick(&mut self) {
@@ -100,7 +416,18 @@ impl<'c> Activity<'c> {
     ///
     /// Prints the log content if appropriate.
     pub fn outcome(self, outcome: &Outcome, options: &Options) -> Result<()> {
-        self.progress_bar.finish_and_clear();
+        self.finish();
+        match self.console.mode {
+            OutputMode::Human => (),
+            OutputMode::Tap => return self.tap_outcome(outcome, options),
+            OutputMode::Json => {
+                self.console.event(|| events::outcome(outcome));
+                if outcome.should_show_logs() || options.show_all_logs {
+                    eprint!("{}", outcome.get_log_content()?);
+                }
+                return Ok(());
+            }
+        }
         if (outcome.mutant_caught() && !options.print_caught)
             || (outcome.scenario.is_mutant()
                 && outcome.check_or_build_failed()
@@ -109,14 +436,71 @@ impl<'c> Activity<'c> {
             return Ok(());
         }
 
-        print!("{} ... {}", self.task, style_outcome(outcome));
+        // The output is printed all at once, so that it's not mixed with the output of
+        // other jobs.
+        let mut text = format!("{} ... {}", self.task, style_outcome(outcome));
+        if let Some(kept_dir) = outcome.kept_dir() {
+            write!(text, " (tree kept in {})", kept_dir).unwrap();
+        }
         if self.console.show_times {
-            println!(" in {}", self.format_elapsed());
+            writeln!(text, " in {}", self.format_elapsed()).unwrap();
+        } else {
+            text.push('\n');
+        }
+        if outcome.should_show_logs() || options.show_all_logs {
+            text.push_str(&outcome.get_log_content()?);
+        }
+        if let Scenario::Mutant { mutation, .. } = &outcome.scenario {
+            if outcome.mutant_status() == Some(MutantStatus::Missed) {
+                if options.diff_missed {
+                    text.push_str(&style_diff(&mutation.diff()));
+                }
+                // Printed directly, after the progress bar is cleared, so that CI sees
+                // the whole line.
+                if let Some(annotation) = missed_mutant_annotation(
+                    mutation,
+                    options.annotations,
+                    options.annotation_level,
+                ) {
+                    writeln!(text, "{}", annotation).unwrap();
+                }
+            }
+        }
+        if self.shared && !self.progress_bar.is_hidden() {
+            self.progress_bar.println(text);
         } else {
-            println!();
+            print!("{}", text);
+        }
+        Ok(())
+    }
+
+    /// Clear the progress bar, or if it belongs to a job, just this activity's message.
+    fn finish(&self) {
+        if self.shared {
+            self.progress_bar.set_message("");
+        } else {
+            self.progress_bar.finish_and_clear();
+        }
+    }
+
+    /// Report the outcome of a scenario in TAP mode: mutants are test points, and
+    /// everything else is a comment.
+    fn tap_outcome(self, outcome: &Outcome, options: &Options) -> Result<()> {
+        match (&outcome.scenario, outcome.mutant_status()) {
+            (
+                Scenario::Mutant {
+                    mutation,
+                    i_mutation,
+                    ..
+                },
+                Some(status),
+            ) => println!("{}", tap::test_point(i_mutation + 1, mutation, status)),
+            _ => self
+                .console
+                .message(&format!("{} ... {}", self.task, style_outcome(outcome))),
         }
         if outcome.should_show_logs() || options.show_all_logs {
-            print!("{}", outcome.get_log_content()?);
+            self.console.message(&outcome.get_log_content()?);
         }
         Ok(())
     }
@@ -135,10 +519,7 @@ pub struct CopyActivity<'c> {
 
 impl<'c> CopyActivity<'c> {
     fn new(name: &str, console: &'c Console) -> CopyActivity<'c> {
-        let progress_bar = ProgressBar::new(0)
-            .with_message(name.to_owned())
-            .with_style(ProgressStyle::default_spinner().template("{msg}"));
-        progress_bar.set_draw_rate(5); // updates per second
+        let progress_bar = console.new_progress_bar(name, "{msg}");
         CopyActivity {
             name: name.to_owned(),
             progress_bar,
@@ -160,21 +541,26 @@ impl<'c> CopyActivity<'c> {
     pub fn succeed(self, bytes_copied: u64) {
         self.progress_bar.finish_and_clear();
         // Print to stdout even if progress bars weren't drawn.
-        print!("{} ...", self.name);
-        if self.console.show_times {
-            println!(
-                " {} in {}",
+        let result = if self.console.show_times {
+            format!(
+                "{} in {}",
                 style_mb(bytes_copied),
                 style(format_elapsed(self.start_time)).cyan(),
-            );
+            )
         } else {
-            println!(" {}", style("done").green());
-        }
+            style("done").green().to_string()
+        };
+        self.console
+            .message(&format!("{} ... {}", self.name, result));
     }
 
     pub fn fail(self) {
         self.progress_bar.finish_and_clear();
-        println!("{} ... {}", self.name, style("failed").bold().red(),);
+        self.console.message(&format!(
+            "{} ... {}",
+            self.name,
+            style("failed").bold().red()
+        ));
     }
 }
 
@@ -183,10 +569,11 @@ pub fn style_outcome(outcome: &Outcome) -> StyledObject<&'static str> {
     use CargoResult::*;
     use Scenario::*;
     match &outcome.scenario {
-        SourceTree | Baseline => match outcome.last_phase_result() {
-            Success => style("ok").green(),
-            Failure => style("FAILED").red().bold(),
-            Timeout => style("TIMEOUT").red().bold(),
+        SourceTree | Baseline => match (outcome.last_phase(), outcome.last_phase_result()) {
+            (_, Success) => style("ok").green(),
+            (_, Failure) => style("FAILED").red().bold(),
+            (Phase::Test, Timeout) => style("TIMEOUT").red().bold(),
+            (_, Timeout) => style("BUILD TIMEOUT").red().bold(),
         },
         Mutant { .. } => match (outcome.last_phase(), outcome.last_phase_result()) {
             (Phase::Test, Failure) => style("caught").green(),
@@ -195,7 +582,8 @@ pub fn style_outcome(outcome: &Outcome) -> StyledObject<&'static str> {
             (Phase::Check, Success) => style("check ok").green(),
             (Phase::Build, Failure) => style("build failed").yellow(),
             (Phase::Check, Failure) => style("check failed").yellow(),
-            (_, Timeout) => style("TIMEOUT").red().bold(),
+            (Phase::Test, Timeout) => style("TIMEOUT").red().bold(),
+            (_, Timeout) => style("BUILD TIMEOUT").red().bold(),
         },
     }
 }
@@ -204,12 +592,163 @@ pub fn list_mutations(mutations: &[Mutation], show_diffs: bool) {
     for mutation in mutations {
         println!("{}", style_mutation(mutation));
         if show_diffs {
-            println!("{}", mutation.diff());
+            println!("{}", style_diff(&mutation.diff()));
         }
     }
 }
 
+/// Color a unified diff: removed lines red, added lines green, and hunk headers cyan.
+///
+/// Where lines are replaced, the words that changed are also highlighted, like
+/// `git diff --color-words`. With colors off, the text is unchanged.
+fn style_diff(diff: &str) -> String {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut styled: Vec<String> = Vec::with_capacity(lines.len());
+    let mut in_hunk = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let text = lines[i];
+        if text.starts_with("@@") {
+            in_hunk = true;
+            styled.push(style(text).cyan().to_string());
+        } else if !in_hunk {
+            styled.push(style(text).bold().to_string());
+        } else if text.starts_with('-') || text.starts_with('+') {
+            let removed = count_lines_starting_with(&lines[i..], '-');
+            let added = count_lines_starting_with(&lines[i + removed..], '+');
+            styled.extend(style_changed_lines(
+                &lines[i..i + removed],
+                &lines[i + removed..i + removed + added],
+            ));
+            i += removed + added;
+            continue;
+        } else {
+            styled.push(text.to_owned());
+        }
+        i += 1;
+    }
+    let mut styled = styled.join("\n");
+    if diff.ends_with('\n') {
+        styled.push('\n');
+    }
+    styled
+}
+
+fn count_lines_starting_with(lines: &[&str], sign: char) -> usize {
+    lines.iter().take_while(|l| l.starts_with(sign)).count()
+}
+
+/// Style a block of removed lines followed by the lines added in their place,
+/// highlighting the words that changed between them.
+fn style_changed_lines(removed: &[&str], added: &[&str]) -> Vec<String> {
+    let old = removed.iter().map(|l| &l[1..]).join("\n");
+    let new = added.iter().map(|l| &l[1..]).join("\n");
+    let mut old_parts = Vec::new();
+    let mut new_parts = Vec::new();
+    if removed.is_empty() || added.is_empty() {
+        // Nothing to compare against, so no words are highlighted.
+        push_word_part(&mut old_parts, false, &old);
+        push_word_part(&mut new_parts, false, &new);
+    } else {
+        for change in TextDiff::from_words(&old, &new).iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Equal => {
+                    push_word_part(&mut old_parts, false, change.value());
+                    push_word_part(&mut new_parts, false, change.value());
+                }
+                ChangeTag::Delete => push_word_part(&mut old_parts, true, change.value()),
+                ChangeTag::Insert => push_word_part(&mut new_parts, true, change.value()),
+            }
+        }
+    }
+    let mut styled = Vec::with_capacity(removed.len() + added.len());
+    if !removed.is_empty() {
+        styled.extend(style_word_parts('-', Color::Red, &old_parts));
+    }
+    if !added.is_empty() {
+        styled.extend(style_word_parts('+', Color::Green, &new_parts));
+    }
+    styled
+}
+
+/// Add text that did or didn't change, joining it onto the last part if that's the same.
+fn push_word_part(parts: &mut Vec<(bool, String)>, changed: bool, text: &str) {
+    match parts.last_mut() {
+        Some((last_changed, last)) if *last_changed == changed => last.push_str(text),
+        _ => parts.push((changed, text.to_owned())),
+    }
+}
+
+/// Style a run of text split into parts that did or didn't change, as lines that
+/// each start with `sign`.
+fn style_word_parts(sign: char, color: Color, parts: &[(bool, String)]) -> Vec<String> {
+    let mut lines = vec![style(sign).fg(color).to_string()];
+    for (changed, text) in parts {
+        for (j, piece) in text.split('\n').enumerate() {
+            if j > 0 {
+                lines.push(style(sign).fg(color).to_string());
+            }
+            if !piece.is_empty() {
+                let piece = style(piece).fg(color);
+                let piece = if *changed { piece.reverse() } else { piece };
+                lines.last_mut().unwrap().push_str(&piece.to_string());
+            }
+        }
+    }
+    lines
+}
+
+/// Describe a mutation in the same way as [style_mutation], but without colors, to be
+/// written to a file.
+pub fn plain_mutation(mutation: &Mutation) -> String {
+    ::console::strip_ansi_codes(&style_mutation(mutation)).into_owned()
+}
+
 fn style_mutation(mutation: &Mutation) -> String {
+    if mutation.genre() == Genre::SwapBranches {
+        return format!(
+            "{}: swap branches of {} in {}",
+            mutation.describe_location(),
+            style(format!("`if {}`", mutation.original_text())).yellow(),
+            style(mutation.function_name()).bright().magenta(),
+        );
+    } else if mutation.genre() == Genre::MatchArm {
+        return format!(
+            "{}: delete match arm {} in {}",
+            mutation.describe_location(),
+            style(format!("`{} => ...`", mutation.original_text())).yellow(),
+            style(mutation.function_name()).bright().magenta(),
+        );
+    } else if mutation.genre() == Genre::Statement {
+        return format!(
+            "{}: delete call to {} in {}",
+            mutation.describe_location(),
+            style(format!("`{}`", mutation.original_text())).yellow(),
+            style(mutation.function_name()).bright().magenta(),
+        );
+    } else if mutation.genre() == Genre::Closure {
+        return format!(
+            "{}: replace closure in {} with {}",
+            mutation.describe_location(),
+            style(mutation.function_name()).bright().magenta(),
+            style(mutation.replacement_text()).yellow(),
+        );
+    } else if mutation.genre() != Genre::FnValue && mutation.replacement_text().is_empty() {
+        return format!(
+            "{}: delete {} in {}",
+            mutation.describe_location(),
+            style(mutation.original_text()).yellow(),
+            style(mutation.function_name()).bright().magenta(),
+        );
+    } else if mutation.genre() != Genre::FnValue {
+        return format!(
+            "{}: replace {} with {} in {}",
+            mutation.describe_location(),
+            style(mutation.original_text()).yellow(),
+            style(mutation.replacement_text()).yellow(),
+            style(mutation.function_name()).bright().magenta(),
+        );
+    }
     format!(
         "{}: replace {}{}{} with {}",
         mutation.describe_location(),
@@ -224,12 +763,12 @@ fn style_mutation(mutation: &Mutation) -> String {
     )
 }
 
-pub fn print_error(msg: &str) {
-    println!("{}: {}", style("error").bold().red(), msg);
+fn format_elapsed(since: Instant) -> String {
+    format_duration(since.elapsed())
 }
 
-fn format_elapsed(since: Instant) -> String {
-    format!("{:.3}s", since.elapsed().as_secs_f64())
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
 }
 
 fn format_mb(bytes: u64) -> String {
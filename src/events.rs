@@ -0,0 +1,114 @@
+// Copyright 2022 Martin Pool
+
+//! Newline-delimited JSON events describing the progress of a run, printed by
+//! `--json` so that other tools can follow it.
+//!
+//! Each event is an object with an `event` field naming its type.
+
+use serde_json::{json, Value};
+
+use crate::lab::Scenario;
+use crate::outcome::{LabOutcome, Outcome, Warning};
+use crate::CargoResult;
+
+/// The mutants have been found, and are about to be tested.
+pub fn start(n_mutations: usize, seed: Option<u64>) -> Value {
+    json!({
+        "event": "start",
+        "n_mutations": n_mutations,
+        "seed": seed,
+    })
+}
+
+/// Testing of one mutant is starting.
+pub fn mutant_start(scenario: &Scenario) -> Option<Value> {
+    match scenario {
+        Scenario::Mutant {
+            mutation,
+            i_mutation,
+            n_mutations,
+            ..
+        } => Some(json!({
+            "event": "mutant_start",
+            "i_mutation": i_mutation,
+            "n_mutations": n_mutations,
+            "mutation": mutation,
+        })),
+        _ => None,
+    }
+}
+
+/// A scenario finished.
+///
+/// For mutants, the `result` is `caught`, `missed`, `unviable`, `timeout`,
+/// `build_timeout`, or `untested`; for the source tree and baseline, it's the result of the last
+/// cargo command. If a copy of the mutated tree was kept, `kept_dir` says where.
+pub fn outcome(outcome: &Outcome) -> Value {
+    let (scenario, mutation, i_mutation) = match &outcome.scenario {
+        Scenario::SourceTree => ("source_tree", None, None),
+        Scenario::Baseline => ("baseline", None, None),
+        Scenario::Mutant {
+            mutation,
+            i_mutation,
+            ..
+        } => ("mutant", Some(mutation), Some(i_mutation)),
+    };
+    let result = match outcome.mutant_status() {
+        Some(status) => json!(status),
+        None => json!(match outcome.last_phase_result() {
+            CargoResult::Success => "success",
+            CargoResult::Failure => "failure",
+            CargoResult::Timeout => "timeout",
+        }),
+    };
+    let mut event = json!({
+        "event": "outcome",
+        "scenario": scenario,
+        "result": result,
+        "duration_secs": outcome.duration().as_secs_f64(),
+        "log_path": outcome.log_path(),
+    });
+    if let Some(mutation) = mutation {
+        event["mutation"] = json!(mutation);
+        event["i_mutation"] = json!(i_mutation);
+    }
+    if let Some(kept_dir) = outcome.kept_dir() {
+        event["kept_dir"] = json!(kept_dir);
+    }
+    event
+}
+
+/// The run finished, with totals for every mutant.
+pub fn summary(lab_outcome: &LabOutcome) -> Value {
+    let totals = lab_outcome.totals();
+    json!({
+        "event": "summary",
+        "total": totals.total,
+        "caught": totals.caught,
+        "missed": totals.missed,
+        "unviable": totals.unviable,
+        "timeout": totals.timeout,
+        "untested": totals.untested,
+        "cached_caught": lab_outcome.cached_caught(),
+        "score": totals.score(),
+        "warnings": lab_outcome.warnings(),
+        "elapsed_secs": lab_outcome.elapsed().as_secs_f64(),
+    })
+}
+
+/// Something makes the results of the run doubtful.
+pub fn warning(warning: Warning) -> Value {
+    json!({
+        "event": "warning",
+        "warning": warning,
+        "message": warning.message(),
+    })
+}
+
+/// The run stopped because of an error in the source tree or baseline.
+pub fn error(message: &str) -> Value {
+    json!({
+        "event": "error",
+        "message": message,
+    })
+}
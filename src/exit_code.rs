@@ -23,5 +23,19 @@ pub const FOUND_PROBLEMS: i32 = 2;
 /// One or more tests timed out: probably the mutant caused an infinite loop, or the timeout is too low.
 pub const TIMEOUT: i32 = 3;
 
-/// The tests are already failing in an unmutated tree.
+/// The source tree failed to build, or the tests are already failing in an unmutated tree.
 pub const CLEAN_TESTS_FAILED: i32 = 4;
+
+/// No mutants were found, or the baseline ran no tests, with `--fail-on-empty`; or
+/// `--list-files` found no files.
+pub const EMPTY: i32 = 5;
+
+/// An internal error, such as failing to write the output directory or to run cargo.
+///
+/// (70 is the conventional `EX_SOFTWARE` from `sysexits.h`.)
+pub const SOFTWARE: i32 = 70;
+
+/// Interrupted by ctrl-c.
+///
+/// (128 plus the number of `SIGINT`, as shells report it.)
+pub const INTERRUPTED: i32 = 130;
@@ -0,0 +1,199 @@
+// Copyright 2022 Martin Pool
+
+//! Select cargo features, for `--features`, `--all-features`, and
+//! `--no-default-features`, and work out which features are known to be off, so that
+//! code that's only built with them isn't mutated.
+//!
+//! A feature is only known to be off if it's declared in the package's `[features]`
+//! table and isn't enabled, directly or through other features. Features that are
+//! implied by optional dependencies, or enabled by other packages, aren't tracked, so
+//! code that depends on them is still mutated: at worst its mutants are unviable or
+//! missed, rather than silently not tested.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+/// The features selected on the command line, passed to every cargo command.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Features {
+    /// Features to enable, from `--features`.
+    pub features: Vec<String>,
+    /// Enable every feature of the selected packages.
+    pub all_features: bool,
+    /// Don't enable the `default` feature.
+    pub no_default_features: bool,
+}
+
+impl Features {
+    /// Build the selection from the command line, where each `--features` value can name
+    /// several features, separated by commas or spaces, as it can for cargo.
+    pub fn new(features: &[String], all_features: bool, no_default_features: bool) -> Features {
+        Features {
+            features: features
+                .iter()
+                .flat_map(|value| value.split(|c: char| c == ',' || c.is_whitespace()))
+                .filter(|name| !name.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            all_features,
+            no_default_features,
+        }
+    }
+
+    /// Return the arguments that select these features for cargo.
+    pub fn cargo_args(&self) -> Vec<&str> {
+        let mut args = Vec::new();
+        for feature in &self.features {
+            args.extend(["--features", feature]);
+        }
+        if self.all_features {
+            args.push("--all-features");
+        }
+        if self.no_default_features {
+            args.push("--no-default-features");
+        }
+        args
+    }
+
+    /// Work out which of a package's declared features are enabled, given its
+    /// `[features]` table, or return None if they all are.
+    pub fn known_features(
+        &self,
+        declared: &BTreeMap<String, Vec<String>>,
+    ) -> Option<KnownFeatures> {
+        if self.all_features {
+            return None;
+        }
+        let mut pending: Vec<&str> = Vec::new();
+        for feature in &self.features {
+            // `package/feature` names a feature of a workspace member or a dependency;
+            // either way, both names are taken to be enabled.
+            pending.extend(feature.split('/'));
+        }
+        if !self.no_default_features {
+            pending.push("default");
+        }
+        let mut enabled: BTreeSet<String> = BTreeSet::new();
+        while let Some(feature) = pending.pop() {
+            if !enabled.insert(feature.to_owned()) {
+                continue;
+            }
+            for implied in declared.get(feature).into_iter().flatten() {
+                // `dep:name` enables an optional dependency but no feature, and
+                // `name/feature` or `name?/feature` enables a feature of a dependency,
+                // and perhaps the dependency itself.
+                if implied.starts_with("dep:") {
+                    continue;
+                }
+                let name = implied.split('/').next().unwrap().trim_end_matches('?');
+                pending.push(name);
+            }
+        }
+        Some(KnownFeatures {
+            declared: declared.keys().cloned().collect(),
+            enabled,
+        })
+    }
+}
+
+/// The features of a package that are declared, and those that are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownFeatures {
+    declared: BTreeSet<String>,
+    enabled: BTreeSet<String>,
+}
+
+impl KnownFeatures {
+    /// True if this feature is declared by the package but not enabled.
+    pub fn is_off(&self, feature: &str) -> bool {
+        self.declared.contains(feature) && !self.enabled.contains(feature)
+    }
+}
+
+/// Return the `[features]` table of a parsed `Cargo.toml`.
+pub fn manifest_features(manifest: &toml::Value) -> BTreeMap<String, Vec<String>> {
+    manifest
+        .get("features")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flatten()
+        .map(|(name, implied)| {
+            let implied = implied
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|value| value.as_str().map(str::to_owned))
+                .collect();
+            (name.clone(), implied)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn declared() -> BTreeMap<String, Vec<String>> {
+        manifest_features(
+            &r#"
+            [features]
+            default = ["std"]
+            std = ["alloc"]
+            alloc = []
+            fancy = ["dep:colors", "serde?/derive"]
+            extra = []
+            "#
+            .parse()
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn features_are_split_and_passed_to_cargo() {
+        let features = Features::new(&["a,b".to_owned(), "c  d".to_owned()], false, true);
+        assert_eq!(features.features, ["a", "b", "c", "d"]);
+        assert_eq!(
+            features.cargo_args(),
+            [
+                "--features",
+                "a",
+                "--features",
+                "b",
+                "--features",
+                "c",
+                "--features",
+                "d",
+                "--no-default-features"
+            ]
+        );
+        assert_eq!(Features::default().cargo_args(), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn default_features_and_their_implications_are_on() {
+        let known = Features::default().known_features(&declared()).unwrap();
+        assert!(!known.is_off("std"));
+        assert!(!known.is_off("alloc"));
+        assert!(known.is_off("fancy"));
+        assert!(known.is_off("extra"));
+        // Undeclared features, such as optional dependencies, aren't known to be off.
+        assert!(!known.is_off("serde"));
+    }
+
+    #[test]
+    fn selected_features_replace_or_add_to_the_defaults() {
+        let known = Features::new(&["extra".to_owned()], false, true)
+            .known_features(&declared())
+            .unwrap();
+        assert!(known.is_off("std"));
+        assert!(known.is_off("alloc"));
+        assert!(!known.is_off("extra"));
+        assert_eq!(
+            Features::new(&[], true, true).known_features(&declared()),
+            None
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2022 Martin Pool
+
+//! A plain-text table of how many mutants were caught and missed in each source file,
+//! built from `outcomes.json`, showing where tests are weakest.
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::report::{LabOutcomeRecord, Totals};
+
+/// Return a table with a row for each source file, with the lowest mutation scores first.
+///
+/// Files with the same score are ordered by the number of missed mutants, most first,
+/// and then by name. Files where no mutants were caught or missed have no score, and
+/// come last.
+pub fn file_table(record: &LabOutcomeRecord) -> String {
+    let mut rows: Vec<(&str, Totals)> = record.totals_by_file().into_iter().collect();
+    rows.sort_by(|(a_name, a), (b_name, b)| {
+        compare_scores(a.score(), b.score())
+            .then(b.missed.cmp(&a.missed))
+            .then(a_name.cmp(b_name))
+    });
+    let headers = ["File", "Mutants", "Caught", "Missed", "Score"];
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|(name, totals)| {
+            [
+                name.to_string(),
+                totals.total.to_string(),
+                totals.caught.to_string(),
+                totals.missed.to_string(),
+                totals
+                    .score()
+                    .map_or_else(|| "-".to_owned(), |score| format!("{:.1}%", score)),
+            ]
+        })
+        .collect();
+    let mut widths = headers.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut table = String::new();
+    write_row(&mut table, &widths, headers.iter().copied());
+    for row in &cells {
+        write_row(&mut table, &widths, row.iter().map(String::as_str));
+    }
+    table
+}
+
+/// Write one row, with the file name left-aligned and the numbers right-aligned.
+fn write_row<'a>(table: &mut String, widths: &[usize], cells: impl Iterator<Item = &'a str>) {
+    let mut line = String::new();
+    for (i, (cell, &width)) in cells.zip(widths).enumerate() {
+        if i == 0 {
+            write!(line, "{:<width$}", cell, width = width).unwrap();
+        } else {
+            write!(line, "  {:>width$}", cell, width = width).unwrap();
+        }
+    }
+    table.push_str(line.trim_end());
+    table.push('\n');
+}
+
+/// Order scores from lowest to highest, with no score last.
+fn compare_scores(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn outcome(file: &str, line: usize, test_result: &str) -> String {
+        let phase = if test_result == "Unviable" {
+            r#"{ "phase": "Build", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Failure" }"#
+                .to_owned()
+        } else {
+            format!(
+                r#"{{ "phase": "Test", "duration": {{ "secs": 1, "nanos": 0 }}, "cargo_result": "{}" }}"#,
+                test_result
+            )
+        };
+        format!(
+            r#"{{ "scenario": {{ "Mutant": {{ "mutation": {{
+                "file": "{file}",
+                "span": {{ "start": {{ "line": {line}, "column": 1 }}, "end": {{ "line": {line}, "column": 2 }} }},
+                "function": "f", "replacement": "0", "change": "replace f with 0",
+                "description": "replace f with 0 in {file}:{line}"
+            }} }} }},
+            "phase_results": [{phase}] }}"#,
+            file = file,
+            line = line,
+            phase = phase,
+        )
+    }
+
+    #[test]
+    fn files_are_sorted_by_score() {
+        let outcomes = [
+            outcome("src/good.rs", 1, "Failure"),
+            outcome("src/good.rs", 2, "Failure"),
+            outcome("src/unviable.rs", 1, "Unviable"),
+            outcome("src/weak.rs", 1, "Success"),
+            outcome("src/weak.rs", 2, "Failure"),
+            outcome("src/a_bit_weak.rs", 1, "Success"),
+            outcome("src/a_bit_weak.rs", 2, "Failure"),
+            outcome("src/a_bit_weak.rs", 3, "Failure"),
+            outcome("src/also_weak.rs", 1, "Success"),
+            outcome("src/also_weak.rs", 2, "Failure"),
+        ];
+        let record: LabOutcomeRecord =
+            serde_json::from_str(&format!(r#"{{ "outcomes": [{}] }}"#, outcomes.join(", ")))
+                .unwrap();
+        assert_eq!(
+            file_table(&record),
+            "\
+File               Mutants  Caught  Missed   Score
+src/also_weak.rs         2       1       1   50.0%
+src/weak.rs              2       1       1   50.0%
+src/a_bit_weak.rs        3       2       1   66.7%
+src/good.rs              2       2       0  100.0%
+src/unviable.rs          1       0       0       -
+"
+        );
+    }
+}
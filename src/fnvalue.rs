@@ -0,0 +1,981 @@
+// Copyright 2022 Martin Pool
+
+//! Mutations that replace the whole body of a function with a value of its return type.
+//!
+//! These are guided by the syntax of the return type: cargo-mutants doesn't know
+//! what the type resolves to, but common types can often be recognized by name.
+
+use crate::*;
+
+/// The error expression returned by `Err` mutants, if no others are configured.
+pub const DEFAULT_ERROR_VALUE: &str = r#"anyhow::anyhow!("mutated")"#;
+
+/// Values returned from functions returning integers, if no others are configured.
+///
+/// Negative values are only used for signed types.
+pub const DEFAULT_INT_VALUES: &[&str] = &["0", "1", "-1"];
+
+/// The maximum number of mutants generated for a function returning a tuple, if no other
+/// limit is configured.
+pub const DEFAULT_MAX_TUPLE_MUTANTS: usize = 4;
+
+/// Values returned from functions returning `Ordering`, like `cmp`.
+///
+/// `Equal` in particular catches tests that don't check the order of things that are
+/// sorted.
+const ORDERING_VALUES: &[&str] = &[
+    "std::cmp::Ordering::Less",
+    "std::cmp::Ordering::Equal",
+    "std::cmp::Ordering::Greater",
+];
+
+/// Values returned from functions returning some well-known standard library types,
+/// matched by the last component of the type's path.
+const STD_TYPE_REPLACEMENTS: &[(&str, &[&str])] = &[
+    (
+        "Duration",
+        &[
+            "std::time::Duration::ZERO",
+            "std::time::Duration::from_secs(1)",
+        ],
+    ),
+    (
+        "PathBuf",
+        &[
+            "std::path::PathBuf::new()",
+            r#"std::path::PathBuf::from("/")"#,
+        ],
+    ),
+    ("IpAddr", &["std::net::Ipv4Addr::LOCALHOST.into()"]),
+    ("Ipv4Addr", &["std::net::Ipv4Addr::LOCALHOST"]),
+    ("Ipv6Addr", &["std::net::Ipv6Addr::LOCALHOST"]),
+    ("SystemTime", &["std::time::UNIX_EPOCH"]),
+];
+
+const SIGNED_INT_TYPES: &[&str] = &["i8", "i16", "i32", "i64", "i128", "isize"];
+const UNSIGNED_INT_TYPES: &[&str] = &["u8", "u16", "u32", "u64", "u128", "usize"];
+
+/// Return the expressions that could replace the body of a function with the given return
+/// type, as Rust source.
+///
+/// `options.error_values` are expressions to try inside `Err(...)` for functions returning
+/// `Result`, and `options.int_values` are values for functions returning integers. If they're
+/// empty, [DEFAULT_ERROR_VALUE] and [DEFAULT_INT_VALUES] are used.
+///
+/// An empty list means there's no replacement known to be viable.
+pub fn return_type_replacements(return_type: &syn::ReturnType, options: &Options) -> Vec<String> {
+    match return_type {
+        syn::ReturnType::Default => vec!["()".into()],
+        syn::ReturnType::Type(_rarrow, box_typ) => type_replacements(box_typ, options),
+    }
+}
+
+/// Return expressions of the given type, as Rust source.
+pub(crate) fn type_replacements(ty: &syn::Type, options: &Options) -> Vec<String> {
+    let error_values = &options.error_values;
+    let mut reps: Vec<String> = Vec::new();
+    match ty {
+        syn::Type::Path(syn::TypePath { path, .. }) => {
+            if let Some(known) = known_type_replacements(path, options) {
+                reps.extend(known);
+            } else if path_is_bool(path) {
+                // `Default::default()` would just be `false`, so there's no separate mutant
+                // for that.
+                reps.push("true".into());
+                reps.push("false".into());
+            } else if let Some(int_type) = int_type_name(path) {
+                reps.extend(int_replacements(&int_type, &options.int_values));
+            } else if path.is_ident("f32") || path.is_ident("f64") {
+                reps.push("0.0".into());
+                reps.push("1.0".into());
+            } else if path.is_ident("String") {
+                reps.push("\"\".into()".into());
+                reps.push("\"xyzzy\".into()".into());
+            } else if path_is_result(path) {
+                // TODO: Recursively generate for types inside the Ok side of the Result.
+                reps.push("Ok(Default::default())".into());
+                if error_values.is_empty() {
+                    reps.push(format!("Err({})", DEFAULT_ERROR_VALUE));
+                } else {
+                    reps.extend(error_values.iter().map(|e| format!("Err({})", e)));
+                }
+            } else if path_ends_with(path, "Cow") {
+                // Borrow a literal of the right type; other `Cow`s can only be guessed at.
+                let cow = path_without_arguments(path);
+                match first_type_argument(path) {
+                    Some(ty) if type_is_str(ty) => {
+                        reps.push(format!("{}::Borrowed(\"\")", cow));
+                        reps.push(format!("{}::Borrowed(\"xyzzy\")", cow));
+                    }
+                    Some(syn::Type::Slice(_)) => reps.push(format!("{}::Borrowed(&[])", cow)),
+                    _ => reps.push(format!("{}::Owned(Default::default())", cow)),
+                }
+            } else if path_ends_with(path, "Vec") {
+                // `Default::default()` would be empty, which is the same as `vec![]`.
+                reps.push("vec![]".into());
+                reps.push("vec![Default::default()]".into());
+            } else if path_is_cmp_ordering(path) {
+                reps.extend(ORDERING_VALUES.iter().map(|v| (*v).to_owned()));
+            } else if path_is_option(path)
+                && first_type_argument(path).is_some_and(type_is_ordering)
+            {
+                // This is what `partial_cmp` returns: `None` is hardly ever interesting, but
+                // each ordering is.
+                reps.extend(ORDERING_VALUES.iter().map(|v| format!("Some({})", v)));
+            } else if path_is_option(path) {
+                // `Default::default()` would be `None`, so there's no separate mutant for that;
+                // `Some(Default::default())` will be unviable if the inner type is not Default.
+                reps.push("None".into());
+                reps.push("Some(Default::default())".into());
+            } else if ["Box", "Rc", "Arc"]
+                .iter()
+                .any(|name| path_ends_with(path, name))
+            {
+                // The pointee must be sized and constructed by `Default` to be passed to
+                // `new`; functions returning unsized pointees like `Box<dyn Trait>` are
+                // skipped.
+                if first_type_argument(path).is_some_and(type_is_sized_and_nameable) {
+                    reps.push(format!(
+                        "{}::new(Default::default())",
+                        path_without_arguments(path)
+                    ));
+                }
+            } else if path_ends_with(path, "HashMap") || path_ends_with(path, "BTreeMap") {
+                // `Default::default()` gives the empty map.
+                reps.push("Default::default()".into());
+                reps.push(format!(
+                    "{{ let mut map = {}::new(); map.insert(Default::default(), Default::default()); map }}",
+                    path_without_arguments(path)
+                ));
+            } else if path_ends_with(path, "HashSet") || path_ends_with(path, "BTreeSet") {
+                reps.push("Default::default()".into());
+                reps.push(format!(
+                    "{{ let mut set = {}::new(); set.insert(Default::default()); set }}",
+                    path_without_arguments(path)
+                ));
+            } else {
+                reps.push("Default::default()".into());
+            }
+        }
+        syn::Type::Reference(syn::TypeReference {
+            mutability: None,
+            elem,
+            ..
+        }) if type_is_str(elem) => {
+            // A string literal is `&'static str`, which can be used for any lifetime.
+            reps.push("\"\"".into());
+            reps.push("\"xyzzy\"".into());
+        }
+        syn::Type::Reference(syn::TypeReference {
+            mutability: None,
+            elem,
+            ..
+        }) if matches!(**elem, syn::Type::Slice(_)) => {
+            // A one-element slice can't be borrowed from a temporary, so it's leaked
+            // instead.
+            reps.push("&[]".into());
+            reps.push("Vec::leak(vec![Default::default()])".into());
+        }
+        syn::Type::ImplTrait(syn::TypeImplTrait { bounds, .. }) => {
+            reps.extend(impl_trait_replacements(bounds, options));
+        }
+        syn::Type::Reference(syn::TypeReference {
+            mutability: Some(_),
+            elem,
+            ..
+        }) if type_is_str(elem) || matches!(**elem, syn::Type::Slice(_)) => {
+            // Empty mutable strs and slices do implement `Default`.
+            reps.push("Default::default()".into());
+        }
+        // These can never be built by `Default::default()`: `!` has no values, raw and
+        // function pointers and trait objects don't implement `Default`, and there's no
+        // value to borrow for other references.
+        syn::Type::Never(_)
+        | syn::Type::Ptr(_)
+        | syn::Type::BareFn(_)
+        | syn::Type::TraitObject(_)
+        | syn::Type::Reference(_) => (),
+        syn::Type::Tuple(syn::TypeTuple { elems, .. }) => {
+            let elem_reps: Vec<Vec<String>> = elems
+                .iter()
+                .map(|elem| type_replacements(elem, options))
+                .collect();
+            let max = options
+                .max_tuple_mutants
+                .unwrap_or(DEFAULT_MAX_TUPLE_MUTANTS);
+            reps.extend(tuple_replacements(&elem_reps, max));
+        }
+        _ => reps.push("Default::default()".into()),
+    }
+    reps
+}
+
+/// Return replacements for a type named in `options.type_replacements`, or in
+/// [STD_TYPE_REPLACEMENTS].
+///
+/// Configured names containing `::` are matched against the whole path, and others
+/// against its last component.
+fn known_type_replacements(path: &syn::Path, options: &Options) -> Option<Vec<String>> {
+    let full_path = path_without_arguments(path);
+    options
+        .type_replacements
+        .iter()
+        .find(|(name, _)| {
+            if name.contains("::") {
+                full_path == **name
+            } else {
+                path_ends_with(path, name)
+            }
+        })
+        .map(|(_, reps)| reps.clone())
+        .or_else(|| {
+            STD_TYPE_REPLACEMENTS
+                .iter()
+                .find(|(name, _)| path_ends_with(path, name))
+                .map(|(_, reps)| reps.iter().map(|rep| (*rep).to_owned()).collect())
+        })
+}
+
+/// True if a replacement expression can be evaluated in a `const fn`.
+///
+/// This is a conservative approximation: literals, paths to constants or unit variants,
+/// and tuples, arrays, references, and `Some`/`Ok`/`Err` of those are accepted, but
+/// general function calls, method calls, and macros like `vec![]` are not.
+pub fn replacement_is_const(replacement: &str) -> bool {
+    syn::parse_str::<syn::Expr>(replacement).is_ok_and(|expr| expr_is_const(&expr))
+}
+
+fn expr_is_const(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(_) | syn::Expr::Path(_) => true,
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => expr_is_const(expr),
+        syn::Expr::Paren(syn::ExprParen { expr, .. })
+        | syn::Expr::Reference(syn::ExprReference {
+            mutability: None,
+            expr,
+            ..
+        }) => expr_is_const(expr),
+        syn::Expr::Tuple(syn::ExprTuple { elems, .. })
+        | syn::Expr::Array(syn::ExprArray { elems, .. }) => elems.iter().all(expr_is_const),
+        syn::Expr::Struct(syn::ExprStruct { fields, rest, .. }) => {
+            rest.is_none() && fields.iter().all(|field| expr_is_const(&field.expr))
+        }
+        syn::Expr::Call(syn::ExprCall { func, args, .. }) => {
+            matches!(&**func, syn::Expr::Path(syn::ExprPath { path, .. })
+                if ["Some", "Ok", "Err"].iter().any(|name| path_ends_with(path, name)))
+                && args.iter().all(expr_is_const)
+        }
+        _ => false,
+    }
+}
+
+/// Traits that don't constrain which value is returned, and so can be ignored alongside
+/// a trait from [IMPL_TRAIT_REPLACEMENTS].
+const MARKER_TRAITS: &[&str] = &["Send", "Sync", "Unpin"];
+
+/// Values that implement commonly-returned traits, for functions returning `impl Trait`.
+const IMPL_TRAIT_REPLACEMENTS: &[(&str, &[&str])] = &[
+    ("Debug", &[r#""""#]),
+    ("Display", &[r#""""#]),
+    ("Future", &["std::future::ready(Default::default())"]),
+    (
+        "Iterator",
+        &["std::iter::empty()", "std::iter::once(Default::default())"],
+    ),
+];
+
+/// Return replacements for a function returning `impl` the given bounds.
+///
+/// `Default::default()` can't build a value of an opaque type, so only traits with
+/// a known implementation are mutated. If any bound, other than lifetimes and marker
+/// traits, is not known, there are no replacements.
+///
+/// A `Future` with a known `Output` type is ready with each replacement for that type.
+fn impl_trait_replacements(
+    bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+    options: &Options,
+) -> Vec<String> {
+    let mut reps: Option<Vec<String>> = None;
+    for bound in bounds {
+        let trait_bound = match bound {
+            syn::TypeParamBound::Trait(trait_bound) => trait_bound,
+            syn::TypeParamBound::Lifetime(_) => continue,
+        };
+        let last = match trait_bound.path.segments.last() {
+            Some(segment) => segment,
+            None => return Vec::new(),
+        };
+        let name = last.ident.to_string();
+        if MARKER_TRAITS.contains(&name.as_str()) {
+            continue;
+        }
+        let trait_reps: Vec<String> = if let Some(output) = (name == "Future")
+            .then(|| associated_type(last, "Output"))
+            .flatten()
+        {
+            type_replacements(output, options)
+                .into_iter()
+                .map(|rep| format!("std::future::ready({})", rep))
+                .collect()
+        } else if let Some((_, values)) = IMPL_TRAIT_REPLACEMENTS
+            .iter()
+            .find(|(trait_name, _)| *trait_name == name)
+        {
+            values.iter().map(|v| (*v).to_owned()).collect()
+        } else if let ("Fn" | "FnMut" | "FnOnce", syn::PathArguments::Parenthesized(args)) =
+            (name.as_str(), &last.arguments)
+        {
+            let params = vec!["_"; args.inputs.len()].join(", ");
+            vec![format!("|{}| Default::default()", params)]
+        } else {
+            return Vec::new();
+        };
+        // The first known trait chooses the values; any others are hopefully
+        // also implemented by them, like `impl Iterator + Clone`.
+        reps.get_or_insert(trait_reps);
+    }
+    reps.unwrap_or_default()
+}
+
+/// Return the type bound to an associated type in a path segment, like `T` for
+/// `Future<Output = T>`.
+fn associated_type<'a>(segment: &'a syn::PathSegment, name: &str) -> Option<&'a syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Binding(binding) if binding.ident == name => Some(&binding.ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Combine replacements for each element of a tuple into at most `max` tuple expressions.
+///
+/// The first combinations take the i-th replacement for every element (or its last, if
+/// it has fewer), so that each element's replacements are all tried early. The remaining
+/// combinations follow in order, until `max` is reached. If any element has no viable
+/// replacement then neither does the tuple.
+fn tuple_replacements(elem_reps: &[Vec<String>], max: usize) -> Vec<String> {
+    if elem_reps.iter().any(Vec::is_empty) {
+        return Vec::new();
+    }
+    let longest = elem_reps.iter().map(Vec::len).max().unwrap_or(1);
+    let mut combinations: Vec<Vec<usize>> = (0..longest)
+        .map(|i| elem_reps.iter().map(|r| i.min(r.len() - 1)).collect())
+        .collect();
+    combinations.dedup();
+    let total: usize = elem_reps.iter().map(Vec::len).product();
+    for mut k in 0..total {
+        if combinations.len() >= max {
+            break;
+        }
+        let mut indices = vec![0; elem_reps.len()];
+        for (j, r) in elem_reps.iter().enumerate().rev() {
+            indices[j] = k % r.len();
+            k /= r.len();
+        }
+        if !combinations.contains(&indices) {
+            combinations.push(indices);
+        }
+    }
+    combinations.truncate(max);
+    combinations
+        .into_iter()
+        .map(|indices| {
+            let parts: Vec<&str> = indices
+                .iter()
+                .zip(elem_reps)
+                .map(|(&i, r)| r[i].as_str())
+                .collect();
+            if parts.len() == 1 {
+                format!("({},)", parts[0])
+            } else {
+                format!("({})", parts.join(", "))
+            }
+        })
+        .collect()
+}
+
+/// True if the last segment of the path has the given name, ignoring any type parameters.
+pub(crate) fn path_ends_with(path: &syn::Path, name: &str) -> bool {
+    path.segments
+        .last()
+        .map(|segment| segment.ident == name)
+        .unwrap_or_default()
+}
+
+/// Render a path as Rust source without any generic arguments, so that it can name
+/// an associated function like `HashMap::new`.
+fn path_without_arguments(path: &syn::Path) -> String {
+    let idents: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let joined = idents.join("::");
+    if path.leading_colon.is_some() {
+        format!("::{}", joined)
+    } else {
+        joined
+    }
+}
+
+/// Return the first generic type argument of the last segment of a path, like `T` in
+/// `Box<T>`.
+fn first_type_argument(path: &syn::Path) -> Option<&syn::Type> {
+    match &path.segments.last()?.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// False if the type is certainly unsized, like `str`, `[T]` or `dyn Trait`, or if it's
+/// an opaque `impl Trait` that can't be constructed by `Default`.
+fn type_is_sized_and_nameable(ty: &syn::Type) -> bool {
+    !(type_is_str(ty)
+        || matches!(
+            ty,
+            syn::Type::Slice(_) | syn::Type::TraitObject(_) | syn::Type::ImplTrait(_)
+        ))
+}
+
+/// True if the path is `Ordering`, or `std::cmp::Ordering`, but not the atomic
+/// `Ordering`.
+fn path_is_cmp_ordering(path: &syn::Path) -> bool {
+    path_ends_with(path, "Ordering") && !path.segments.iter().any(|s| s.ident == "atomic")
+}
+
+/// True if the type is [path_is_cmp_ordering].
+fn type_is_ordering(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { path, .. }) if path_is_cmp_ordering(path))
+}
+
+/// True if the type is `str`.
+fn type_is_str(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("str"))
+}
+
+/// Return the integer values to try for a given integer type.
+///
+/// `MAX` and `MIN` are expanded to the type's constants, like `u32::MAX`.
+fn int_replacements(int_type: &str, int_values: &[String]) -> Vec<String> {
+    let signed = SIGNED_INT_TYPES.contains(&int_type);
+    let values: Vec<&str> = if int_values.is_empty() {
+        DEFAULT_INT_VALUES.to_vec()
+    } else {
+        int_values.iter().map(String::as_str).collect()
+    };
+    values
+        .into_iter()
+        .filter(|v| signed || !v.starts_with('-'))
+        .map(|v| match v {
+            "MAX" | "MIN" => format!("{}::{}", int_type, v),
+            _ => v.to_owned(),
+        })
+        .collect()
+}
+
+/// If the path is a primitive integer type, return its name.
+fn int_type_name(path: &syn::Path) -> Option<String> {
+    let ident = path.get_ident()?.to_string();
+    if SIGNED_INT_TYPES.contains(&ident.as_str()) || UNSIGNED_INT_TYPES.contains(&ident.as_str()) {
+        Some(ident)
+    } else {
+        None
+    }
+}
+
+/// True if the path is `bool`, possibly qualified as `std::primitive::bool`.
+fn path_is_bool(path: &syn::Path) -> bool {
+    let idents: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    path.is_ident("bool") || idents.ends_with(&["primitive".to_owned(), "bool".to_owned()])
+}
+
+fn path_is_result(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .map(|segment| segment.ident == "Result")
+        .unwrap_or_default()
+}
+
+fn path_is_option(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .map(|segment| segment.ident == "Option")
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn cow_replacements() {
+        for (return_type, expected) in [
+            (
+                syn::parse_quote! { -> Cow<'static, str> },
+                &[r#"Cow::Borrowed("")"#, r#"Cow::Borrowed("xyzzy")"#][..],
+            ),
+            (
+                syn::parse_quote! { -> std::borrow::Cow<'_, [u8]> },
+                &["std::borrow::Cow::Borrowed(&[])"],
+            ),
+            (
+                syn::parse_quote! { -> Cow<'a, Path> },
+                &["Cow::Owned(Default::default())"],
+            ),
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn std_type_replacements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> std::time::Duration };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "std::time::Duration::ZERO",
+                "std::time::Duration::from_secs(1)"
+            ]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> Result<IpAddr> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "Ok(Default::default())",
+                "Err(anyhow::anyhow!(\"mutated\"))"
+            ]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> IpAddr };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["std::net::Ipv4Addr::LOCALHOST.into()"]
+        );
+    }
+
+    #[test]
+    fn configured_type_replacements() {
+        let mut options = Options::default();
+        options.type_replacements.insert(
+            "Meters".to_owned(),
+            vec!["Meters(0.0)".to_owned(), "Meters(1.0)".to_owned()],
+        );
+        options
+            .type_replacements
+            .insert("net::Port".to_owned(), vec!["net::Port(80)".to_owned()]);
+        options
+            .type_replacements
+            .insert("Duration".to_owned(), vec!["Duration::MAX".to_owned()]);
+        for (return_type, expected) in [
+            (
+                syn::parse_quote! { -> units::Meters },
+                &["Meters(0.0)", "Meters(1.0)"][..],
+            ),
+            (syn::parse_quote! { -> net::Port }, &["net::Port(80)"]),
+            (syn::parse_quote! { -> Port }, &["Default::default()"]),
+            (syn::parse_quote! { -> Duration }, &["Duration::MAX"]),
+        ] {
+            assert_eq!(return_type_replacements(&return_type, &options), expected);
+        }
+    }
+
+    #[test]
+    fn const_replacements() {
+        for rep in [
+            "()",
+            "0",
+            "-1",
+            "1.0",
+            "false",
+            "None",
+            "u32::MAX",
+            "Ordering::Less",
+            "Some(Ordering::Less)",
+            "\"\"",
+            "&[]",
+            "(0, false)",
+            "Self { count: 0, ready: false }",
+        ] {
+            assert!(replacement_is_const(rep), "{} should be const", rep);
+        }
+        for rep in [
+            "Default::default()",
+            "\"xyzzy\".into()",
+            "vec![]",
+            "Some(Default::default())",
+            "Ok(Default::default())",
+            "Box::new(Default::default())",
+            "Self { count: 0, name: \"\".into() }",
+        ] {
+            assert!(!replacement_is_const(rep), "{} should not be const", rep);
+        }
+    }
+
+    #[test]
+    fn path_is_result() {
+        let path: syn::Path = syn::parse_quote! { Result<(), ()> };
+        assert!(super::path_is_result(&path));
+    }
+
+    #[test]
+    fn path_is_option() {
+        let path: syn::Path = syn::parse_quote! { std::option::Option<u32> };
+        assert!(super::path_is_option(&path));
+        assert!(!super::path_is_result(&path));
+        let path: syn::Path = syn::parse_quote! { Options };
+        assert!(!super::path_is_option(&path));
+    }
+
+    #[test]
+    fn bool_replacements_are_true_and_false_only() {
+        for return_type in [
+            syn::parse_quote! { -> bool },
+            syn::parse_quote! { -> std::primitive::bool },
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &["true", "false"]
+            );
+        }
+        let return_type: syn::ReturnType = syn::parse_quote! { -> my::bool };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["Default::default()"]
+        );
+    }
+
+    #[test]
+    fn result_replacements_use_default_error_value() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> Result<u32, MyError> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "Ok(Default::default())",
+                r#"Err(anyhow::anyhow!("mutated"))"#
+            ]
+        );
+    }
+
+    #[test]
+    fn result_replacements_use_configured_error_values() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> std::io::Result<()> };
+        let mut options = Options::default();
+        options.error_values = vec![
+            "MyError::Mutated".to_owned(),
+            r#"std::io::Error::other("mutated")"#.to_owned(),
+        ];
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &[
+                "Ok(Default::default())",
+                "Err(MyError::Mutated)",
+                r#"Err(std::io::Error::other("mutated"))"#
+            ]
+        );
+    }
+
+    #[test]
+    fn int_replacements_depend_on_signedness() {
+        let options = Options::default();
+        let return_type: syn::ReturnType = syn::parse_quote! { -> u32 };
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &["0", "1"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> isize };
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &["0", "1", "-1"]
+        );
+    }
+
+    #[test]
+    fn int_replacements_can_be_configured() {
+        let mut options = Options::default();
+        options.int_values = vec!["1".into(), "-1".into(), "MAX".into()];
+        let return_type: syn::ReturnType = syn::parse_quote! { -> u8 };
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &["1", "u8::MAX"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> i64 };
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &["1", "-1", "i64::MAX"]
+        );
+    }
+
+    #[test]
+    fn float_replacements() {
+        for return_type in [syn::parse_quote! { -> f32 }, syn::parse_quote! { -> f64 }] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &["0.0", "1.0"]
+            );
+        }
+    }
+
+    #[test]
+    fn str_replacements() {
+        for return_type in [
+            syn::parse_quote! { -> &str },
+            syn::parse_quote! { -> &'static str },
+            syn::parse_quote! { -> &'a str },
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &[r#""""#, r#""xyzzy""#]
+            );
+        }
+        let return_type: syn::ReturnType = syn::parse_quote! { -> &mut str };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["Default::default()"]
+        );
+    }
+
+    #[test]
+    fn vec_replacements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> std::vec::Vec<String> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["vec![]", "vec![Default::default()]"]
+        );
+    }
+
+    #[test]
+    fn slice_replacements() {
+        for return_type in [
+            syn::parse_quote! { -> &[u8] },
+            syn::parse_quote! { -> &'static [Option<u8>] },
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &["&[]", "Vec::leak(vec![Default::default()])"]
+            );
+        }
+    }
+
+    #[test]
+    fn impl_iterator_replacements() {
+        let return_type: syn::ReturnType =
+            syn::parse_quote! { -> impl Iterator<Item = String> + '_ };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["std::iter::empty()", "std::iter::once(Default::default())"]
+        );
+    }
+
+    #[test]
+    fn unknown_impl_trait_has_no_replacements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> impl MyTrait };
+        assert!(return_type_replacements(&return_type, &Options::default()).is_empty());
+        let return_type: syn::ReturnType =
+            syn::parse_quote! { -> impl Iterator<Item = u8> + MyTrait };
+        assert!(return_type_replacements(&return_type, &Options::default()).is_empty());
+    }
+
+    #[test]
+    fn impl_display_replacement() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> impl std::fmt::Display + Send };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[r#""""#]
+        );
+    }
+
+    #[test]
+    fn impl_future_replacement() {
+        let return_type: syn::ReturnType =
+            syn::parse_quote! { -> impl Future<Output = u8> + Send + 'static };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["std::future::ready(0)", "std::future::ready(1)"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> impl Future };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["std::future::ready(Default::default())"]
+        );
+    }
+
+    #[test]
+    fn impl_fn_replacement_takes_each_argument() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> impl Fn(&str, usize) -> bool };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["|_, _| Default::default()"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> impl FnOnce() };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["|| Default::default()"]
+        );
+    }
+
+    #[test]
+    fn unconstructable_types_have_no_replacements() {
+        for return_type in [
+            syn::parse_quote! { -> ! },
+            syn::parse_quote! { -> *const u8 },
+            syn::parse_quote! { -> fn(u8) -> u8 },
+            syn::parse_quote! { -> &'static Config },
+            syn::parse_quote! { -> &mut Config },
+        ] {
+            assert!(
+                return_type_replacements(&return_type, &Options::default()).is_empty(),
+                "{:?}",
+                return_type
+            );
+        }
+    }
+
+    #[test]
+    fn ordering_replacements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> std::cmp::Ordering };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "std::cmp::Ordering::Less",
+                "std::cmp::Ordering::Equal",
+                "std::cmp::Ordering::Greater"
+            ]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> std::sync::atomic::Ordering };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["Default::default()"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> Option<Ordering> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "Some(std::cmp::Ordering::Less)",
+                "Some(std::cmp::Ordering::Equal)",
+                "Some(std::cmp::Ordering::Greater)"
+            ]
+        );
+    }
+
+    #[test]
+    fn map_replacements_are_empty_and_one_entry() {
+        let return_type: syn::ReturnType =
+            syn::parse_quote! { -> std::collections::HashMap<String, usize> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "Default::default()",
+                "{ let mut map = std::collections::HashMap::new(); map.insert(Default::default(), Default::default()); map }"
+            ]
+        );
+    }
+
+    #[test]
+    fn set_replacements_are_empty_and_one_item() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> BTreeSet<&'a str> };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                "Default::default()",
+                "{ let mut set = BTreeSet::new(); set.insert(Default::default()); set }"
+            ]
+        );
+    }
+
+    #[test]
+    fn smart_pointer_replacements() {
+        for (return_type, expected) in [
+            (
+                syn::parse_quote! { -> Box<u32> },
+                "Box::new(Default::default())",
+            ),
+            (
+                syn::parse_quote! { -> std::rc::Rc<Vec<String>> },
+                "std::rc::Rc::new(Default::default())",
+            ),
+            (
+                syn::parse_quote! { -> Arc<Mutex<State>> },
+                "Arc::new(Default::default())",
+            ),
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &[expected]
+            );
+        }
+    }
+
+    #[test]
+    fn smart_pointers_to_unsized_types_have_no_replacements() {
+        for return_type in [
+            syn::parse_quote! { -> Box<dyn std::error::Error + Send> },
+            syn::parse_quote! { -> Rc<str> },
+            syn::parse_quote! { -> Arc<[u8]> },
+            syn::parse_quote! { -> Box<impl Iterator<Item = u8>> },
+            syn::parse_quote! { -> Box },
+        ] {
+            assert_eq!(
+                return_type_replacements(&return_type, &Options::default()),
+                &[] as &[String]
+            );
+        }
+    }
+
+    #[test]
+    fn tuple_replacements_are_built_from_elements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> (usize, String) };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &[
+                r#"(0, "".into())"#,
+                r#"(1, "xyzzy".into())"#,
+                r#"(0, "xyzzy".into())"#,
+                r#"(1, "".into())"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn tuple_replacements_are_limited() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> (bool, bool, bool, bool, bool) };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()).len(),
+            DEFAULT_MAX_TUPLE_MUTANTS
+        );
+        let mut options = Options::default();
+        options.max_tuple_mutants = Some(1);
+        assert_eq!(
+            return_type_replacements(&return_type, &options),
+            &["(true, true, true, true, true)"]
+        );
+    }
+
+    #[test]
+    fn tuple_replacements_of_one_element_and_unit() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> (f32,) };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["(0.0,)", "(1.0,)"]
+        );
+        let return_type: syn::ReturnType = syn::parse_quote! { -> () };
+        assert_eq!(
+            return_type_replacements(&return_type, &Options::default()),
+            &["()"]
+        );
+    }
+
+    #[test]
+    fn tuple_with_unviable_element_has_no_replacements() {
+        let return_type: syn::ReturnType = syn::parse_quote! { -> (u8, impl Sized) };
+        assert!(return_type_replacements(&return_type, &Options::default()).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2022 Martin Pool
+
+//! A self-contained `mutants.html` report, built from `outcomes.json`.
+
+use std::fmt::Write;
+
+use crate::outcome::MutantStatus;
+use crate::report::{escape, LabOutcomeRecord, Totals};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; }
+td.count { text-align: right; }
+.missed { color: #b00; font-weight: bold; }
+.caught { color: #070; }
+pre { background: #f6f6f6; padding: 0.5em; overflow-x: auto; }
+summary { cursor: pointer; }
+";
+
+/// Return the text of an HTML report on this run.
+pub fn html_report(record: &LabOutcomeRecord) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>cargo-mutants report</title>\n");
+    writeln!(html, "<style>{}</style>", STYLE).unwrap();
+    html.push_str("</head>\n<body>\n<h1>cargo-mutants report</h1>\n");
+
+    let totals = record.totals();
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    let rows = if record.check_only {
+        vec![
+            ("Total", totals.total, ""),
+            ("Viable", totals.untested, ""),
+            ("Unviable", totals.unviable, ""),
+            ("Timeouts", totals.timeout, ""),
+        ]
+    } else {
+        vec![
+            ("Total", totals.total, ""),
+            ("Caught", totals.caught, "caught"),
+            ("Missed", totals.missed, "missed"),
+            ("Unviable", totals.unviable, ""),
+            ("Timeouts", totals.timeout, ""),
+        ]
+    };
+    for (name, count, class) in rows {
+        writeln!(
+            html,
+            "<tr><th>{}</th><td class=\"count {}\">{}</td></tr>",
+            name, class, count
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+    if record.check_only {
+        html.push_str(
+            "<p>The mutants were only checked, with <code>--check</code>, and not tested.</p>\n",
+        );
+    }
+
+    html.push_str("<h2>Files</h2>\n<table>\n");
+    html.push_str(
+        "<tr><th>File</th><th>Total</th><th>Caught</th><th>Missed</th>\
+        <th>Unviable</th><th>Timeouts</th></tr>\n",
+    );
+    for (file, file_totals) in record.totals_by_file() {
+        write_file_row(&mut html, file, &file_totals);
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Missed mutants</h2>\n");
+    let mut any_missed = false;
+    for (mutation, status, outcome) in record.mutants() {
+        if status != MutantStatus::Missed {
+            continue;
+        }
+        any_missed = true;
+        writeln!(
+            html,
+            "<details>\n<summary class=\"missed\">{}</summary>\n<pre>{}</pre>\n<p><a href=\"{}\">log</a></p>\n</details>",
+            escape(&mutation.description),
+            escape(outcome.diff.as_deref().unwrap_or_default()),
+            escape(&outcome.log_path),
+        )
+        .unwrap();
+    }
+    if !any_missed {
+        html.push_str("<p>No mutants were missed.</p>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_file_row(html: &mut String, file: &str, totals: &Totals) {
+    writeln!(
+        html,
+        "<tr><td>{}</td><td class=\"count\">{}</td><td class=\"count caught\">{}</td>\
+        <td class=\"count missed\">{}</td><td class=\"count\">{}</td>\
+        <td class=\"count\">{}</td></tr>",
+        escape(file),
+        totals.total,
+        totals.caught,
+        totals.missed,
+        totals.unviable,
+        totals.timeout,
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_from_outcomes() {
+        let record: LabOutcomeRecord = serde_json::from_str(
+            r#"{ "outcomes": [
+                { "scenario": "Baseline", "log_path": "baseline.log", "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs", "description": "replace a < b with true",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "diff": "-a < b\n+true\n",
+                  "log_path": "log/src__lib.rs_line1_replace_a_<_b_with_true.log",
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs", "description": "replace f with 0",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Failure" }
+                ] }
+            ] }"#,
+        )
+        .unwrap();
+        let html = html_report(&record);
+        assert!(html.contains("<tr><th>Total</th><td class=\"count \">2</td></tr>"));
+        assert!(html.contains("<tr><th>Missed</th><td class=\"count missed\">1</td></tr>"));
+        assert!(html.contains("<tr><td>src/lib.rs</td><td class=\"count\">2</td>"));
+        assert!(html.contains(
+            "<summary class=\"missed\">replace a &lt; b with true</summary>\n<pre>-a &lt; b\n+true\n</pre>\n<p><a href=\"log/src__lib.rs_line1_replace_a_&lt;_b_with_true.log\">log</a></p>"
+        ));
+        assert!(!html.contains("replace f with 0"));
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright 2022 Martin Pool
+
+//! Read a unified diff, for `--in-diff`, to find the lines that it adds or changes, so
+//! that only mutants of those lines are tested.
+//!
+//! Only the new version of each file matters: lines that were removed can't be mutated,
+//! and renamed files are found under their new names.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use path_slash::PathExt;
+
+use crate::mutate::Mutation;
+use crate::*;
+
+/// The lines added or changed in each file by a diff.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    /// One-based line numbers in the new version of each file, by the path in the diff.
+    by_path: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl ChangedLines {
+    /// Read a diff from a file, or from stdin if the path is `-`.
+    pub fn read(path: &Path) -> Result<ChangedLines> {
+        let diff = if path == Path::new("-") {
+            let mut diff = String::new();
+            io::stdin()
+                .read_to_string(&mut diff)
+                .context("read diff from stdin")?;
+            diff
+        } else {
+            fs::read_to_string(path)
+                .with_context(|| format!("read diff from {}", path.to_slash_lossy()))?
+        };
+        ChangedLines::parse(&diff)
+    }
+
+    /// Parse a unified diff, such as from `git diff`.
+    pub fn parse(diff: &str) -> Result<ChangedLines> {
+        let mut by_path: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+        // The file whose hunks are being read, or None if it was deleted.
+        let mut path: Option<String> = None;
+        // The next line number in the new file, and the number of lines of the old and new
+        // files still to come in the current hunk.
+        let mut new_line: usize = 0;
+        let mut old_remaining: usize = 0;
+        let mut new_remaining: usize = 0;
+        for (i, line) in diff.lines().enumerate() {
+            if old_remaining > 0 || new_remaining > 0 {
+                if line.starts_with('+') {
+                    if let Some(path) = &path {
+                        by_path.entry(path.clone()).or_default().insert(new_line);
+                    }
+                    new_line += 1;
+                    new_remaining = new_remaining.saturating_sub(1);
+                } else if line.starts_with('-') {
+                    old_remaining = old_remaining.saturating_sub(1);
+                } else if !line.starts_with('\\') {
+                    // A context line, whose leading space may have been trimmed.
+                    new_line += 1;
+                    old_remaining = old_remaining.saturating_sub(1);
+                    new_remaining = new_remaining.saturating_sub(1);
+                }
+            } else if let Some(header) = line.strip_prefix("+++ ") {
+                // `diff -u` puts a timestamp after a tab.
+                let name = header.split('\t').next().unwrap();
+                path = if name == "/dev/null" {
+                    None
+                } else {
+                    Some(name.strip_prefix("b/").unwrap_or(name).to_owned())
+                };
+            } else if let Some(ranges) = line.strip_prefix("@@ ") {
+                let (old_count, new_start, new_count) =
+                    parse_hunk_header(ranges).ok_or_else(|| {
+                        anyhow!("invalid hunk header on line {} of diff: {:?}", i + 1, line)
+                    })?;
+                old_remaining = old_count;
+                new_line = new_start;
+                new_remaining = new_count;
+            }
+        }
+        Ok(ChangedLines { by_path })
+    }
+
+    /// True if the diff adds or changes any lines in this file.
+    ///
+    /// `path` is relative to the source tree, and `tree_prefix` is the path of the tree
+    /// within its repository, so that paths in the diff can be relative to either.
+    pub fn touches_file(&self, path: &str, tree_prefix: &str) -> bool {
+        self.lines(path, tree_prefix).is_some()
+    }
+
+    /// True if the diff adds or changes any of the lines of this mutant.
+    pub fn touches_mutation(&self, mutation: &Mutation, tree_prefix: &str) -> bool {
+        let span = mutation.span();
+        self.lines(&mutation.source_file.tree_relative_slashes(), tree_prefix)
+            .is_some_and(|lines| {
+                lines
+                    .range(span.start.line..=span.end.line)
+                    .next()
+                    .is_some()
+            })
+    }
+
+    fn lines(&self, path: &str, tree_prefix: &str) -> Option<&BTreeSet<usize>> {
+        self.by_path.get(path).or_else(|| {
+            if tree_prefix.is_empty() {
+                None
+            } else {
+                self.by_path.get(&format!("{}/{}", tree_prefix, path))
+            }
+        })
+    }
+}
+
+/// Parse the rest of a hunk header, like `-1,5 +1,6 @@`, into the number of old lines,
+/// and the first line number and number of lines in the new file.
+fn parse_hunk_header(ranges: &str) -> Option<(usize, usize, usize)> {
+    let mut words = ranges.split(' ');
+    let (_, old_count) = parse_range(words.next()?.strip_prefix('-')?)?;
+    let (new_start, new_count) = parse_range(words.next()?.strip_prefix('+')?)?;
+    Some((old_count, new_start, new_count))
+}
+
+/// Parse a range like `12,3` into its start and count; a missing count means one line.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Return the path of a source tree relative to the root of the git repository containing
+/// it, with forward slashes, or an empty string if it's not in a repository or is at its
+/// root.
+pub fn tree_prefix(root: &Path) -> String {
+    let root = match root.canonicalize() {
+        Ok(root) => root,
+        Err(_) => return String::new(),
+    };
+    root.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .and_then(|repo| root.strip_prefix(repo).ok())
+        .map(|prefix| prefix.to_slash_lossy())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn lines(changed: &ChangedLines, path: &str) -> Vec<usize> {
+        changed.by_path[path].iter().copied().collect()
+    }
+
+    #[test]
+    fn added_and_changed_lines_in_new_files_are_found() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,5 @@
+ fn a() {
+-    1
++    2
++    + 3
+ }
+
+@@ -20,3 +21,3 @@ fn b() {
+ fn c() {
+-    old
++    new
+ }
+diff --git a/src/old_name.rs b/src/new_name.rs
+similarity index 90%
+rename from src/old_name.rs
+rename to src/new_name.rs
+--- a/src/old_name.rs
++++ b/src/new_name.rs
+@@ -3 +3 @@
+-x
++y
+\\ No newline at end of file
+diff --git a/src/gone.rs b/src/gone.rs
+deleted file mode 100644
+--- a/src/gone.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn gone() {
+-}
+";
+        let changed = ChangedLines::parse(diff).unwrap();
+        assert_eq!(lines(&changed, "src/lib.rs"), [2, 3, 22]);
+        assert_eq!(lines(&changed, "src/new_name.rs"), [3]);
+        assert!(!changed.touches_file("src/old_name.rs", ""));
+        assert!(!changed.touches_file("src/gone.rs", ""));
+    }
+
+    #[test]
+    fn paths_can_be_relative_to_the_repository() {
+        let diff = "--- a/crates/x/src/lib.rs\t2022-01-01\n+++ crates/x/src/lib.rs\t2022-01-02\n@@ -1,0 +1 @@\n+fn new() {}\n";
+        let changed = ChangedLines::parse(diff).unwrap();
+        assert!(changed.touches_file("src/lib.rs", "crates/x"));
+        assert!(!changed.touches_file("src/lib.rs", "crates/y"));
+        assert!(!changed.touches_file("src/lib.rs", ""));
+    }
+
+    #[test]
+    fn invalid_hunk_header_is_an_error() {
+        let err = ChangedLines::parse("+++ b/src/lib.rs\n@@ nonsense @@\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid hunk header on line 2 of diff: \"@@ nonsense @@\""
+        );
+    }
+
+    #[test]
+    fn empty_diff_changes_nothing() {
+        assert_eq!(ChangedLines::parse("").unwrap(), ChangedLines::default());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2022 Martin Pool
+
+//! A `junit.xml` report, built from `outcomes.json`, for CI systems that show JUnit
+//! test results.
+//!
+//! Each mutant is a test case: caught mutants pass, missed mutants fail, unviable
+//! mutants are skipped, and mutants that time out are errors.
+
+use std::fmt::Write;
+
+use crate::outcome::MutantStatus;
+use crate::report::{escape, LabOutcomeRecord};
+
+/// Return the text of a JUnit XML report on this run.
+pub fn junit_report(record: &LabOutcomeRecord) -> String {
+    let totals = record.totals();
+    let total_secs: f64 = record
+        .mutants()
+        .map(|(_, _, outcome)| outcome.duration().as_secs_f64())
+        .sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\"",
+        totals.total,
+        totals.missed,
+        totals.timeout,
+        totals.unviable + totals.untested
+    );
+    writeln!(xml, "<testsuites name=\"cargo-mutants\" {}>", counts).unwrap();
+    writeln!(
+        xml,
+        "  <testsuite name=\"cargo-mutants\" {} time=\"{:.3}\">",
+        counts, total_secs
+    )
+    .unwrap();
+    for (mutation, status, outcome) in record.mutants() {
+        write!(
+            xml,
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+            escape(&mutation.file),
+            escape(&mutation.description),
+            outcome.duration().as_secs_f64(),
+        )
+        .unwrap();
+        match status {
+            MutantStatus::Caught => xml.push_str("/>\n"),
+            MutantStatus::Missed => writeln!(
+                xml,
+                ">\n      <failure message=\"mutant was not caught\">{}</failure>\n    </testcase>",
+                escape(outcome.diff.as_deref().unwrap_or_default())
+            )
+            .unwrap(),
+            MutantStatus::Timeout => xml.push_str(
+                ">\n      <error message=\"test timed out\"/>\n    </testcase>\n",
+            ),
+            MutantStatus::BuildTimeout => xml.push_str(
+                ">\n      <error message=\"build timed out\"/>\n    </testcase>\n",
+            ),
+            MutantStatus::Unviable => xml.push_str(
+                ">\n      <skipped message=\"unviable: mutant failed to build\"/>\n    </testcase>\n",
+            ),
+            MutantStatus::Untested => {
+                xml.push_str(">\n      <skipped message=\"not tested\"/>\n    </testcase>\n")
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn junit_from_outcomes() {
+        let record: LabOutcomeRecord = serde_json::from_str(
+            r#"{ "outcomes": [
+                { "scenario": "Baseline", "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs", "description": "replace a < b with true",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "diff": "-a < b\n+true\n",
+                  "phase_results": [
+                    { "phase": "Build", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" },
+                    { "phase": "Test", "duration": { "secs": 0, "nanos": 500000000 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs", "description": "replace f with 0",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 2, "nanos": 0 }, "cargo_result": "Failure" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/main.rs", "description": "replace g with \"\"",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Build", "duration": { "secs": 3, "nanos": 0 }, "cargo_result": "Failure" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/main.rs", "description": "replace h with ()",
+                    "span": { "start": { "line": 1, "column": 1 }, "end": { "line": 1, "column": 2 } },
+                    "function": "f", "replacement": "0", "change": "replace f with 0"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 4, "nanos": 0 }, "cargo_result": "Timeout" }
+                ] }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            junit_report(&record),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="cargo-mutants" tests="4" failures="1" errors="1" skipped="1">
+  <testsuite name="cargo-mutants" tests="4" failures="1" errors="1" skipped="1" time="10.500">
+    <testcase classname="src/lib.rs" name="replace a &lt; b with true" time="1.500">
+      <failure message="mutant was not caught">-a &lt; b
++true
+</failure>
+    </testcase>
+    <testcase classname="src/lib.rs" name="replace f with 0" time="2.000"/>
+    <testcase classname="src/main.rs" name="replace g with &quot;&quot;" time="3.000">
+      <skipped message="unviable: mutant failed to build"/>
+    </testcase>
+    <testcase classname="src/main.rs" name="replace h with ()" time="4.000">
+      <error message="test timed out"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -2,11 +2,15 @@
 
 //! Successively apply mutations to the source code and run cargo to check, build, and test them.
 
-use std::cmp::max;
+use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fs::File;
 use std::io::BufWriter;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
@@ -15,15 +19,37 @@ use rand::prelude::*;
 use serde::Serialize;
 use tempfile::TempDir;
 
-use crate::console::{self, Console};
-use crate::mutate::Mutation;
-use crate::outcome::{LabOutcome, Outcome, Phase};
-use crate::output::OutputDir;
+use crate::baseline::{errors_are_elsewhere, tree_hash, BaselineRecord, BaselineStrategy};
+use crate::cache::Cache;
+use crate::console::{Activity, Console, JobProgress};
+use crate::mutate::{Genre, Mutation};
+use crate::outcome::{count_tests_run, LabOutcome, MutantStatus, Outcome, Phase, Warning};
+use crate::output::{write_diffs, OutputDir, OUTDIR_NAME, ROTATED_NAME};
 use crate::run::run_cargo;
 use crate::*;
 
+/// The most mutated trees that will be kept by `--keep-missed-dirs` or `--leak-dirs`,
+/// since each includes a copy of `target` and can be large.
+///
+/// With `--jobs`, a few more may be kept by jobs that finish at about the same time.
+const MAX_KEPT_DIRS: usize = 20;
+
+/// The shortest test timeout that's set from the baseline, unless
+/// `--minimum-test-timeout` says otherwise, so that a fast baseline on a quiet machine
+/// doesn't cause spurious timeouts on a loaded one.
+pub const DEFAULT_MINIMUM_TEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The baseline test time is multiplied by this to set the test timeout, unless
+/// `--timeout-multiplier` says otherwise.
+pub const DEFAULT_TIMEOUT_MULTIPLIER: f64 = 5.0;
+
+/// The shortest build timeout that's set from the baseline with
+/// `--build-timeout-multiplier`.
+const MINIMUM_BUILD_TIMEOUT: Duration = Duration::from_secs(20);
+
 /// What type of build, check, or test was this?
 #[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum Scenario {
     /// Build in the original source tree.
     SourceTree,
@@ -32,6 +58,9 @@ pub enum Scenario {
     /// Build with a mutant applied.
     Mutant {
         mutation: Mutation,
+        /// The workspace package containing the mutant, whose tests are run, if packages
+        /// were selected.
+        package: Option<String>,
         /// Index of the mutation being applied, to calculate progress.
         i_mutation: usize,
         /// Total number of mutations.
@@ -53,12 +82,39 @@ impl Scenario {
     pub fn is_mutant(&self) -> bool {
         matches!(self, Scenario::Mutant { .. })
     }
+
+    /// A name for the scenario's log file, without the extension.
+    ///
+    /// Mutant logs start with the source file and line, so that they sort together.
+    pub fn log_name(&self) -> String {
+        match self {
+            Scenario::SourceTree => "source_tree".to_owned(),
+            Scenario::Baseline => "baseline".to_owned(),
+            Scenario::Mutant { mutation, .. } => {
+                let span = mutation.span();
+                let position = if mutation.genre() == Genre::FnValue {
+                    format!("line{}", span.start.line)
+                } else {
+                    format!("line{}_col{}", span.start.line, span.start.column)
+                };
+                format!(
+                    "{}_{}_{}",
+                    mutation
+                        .source_file
+                        .tree_relative_slashes()
+                        .replace('/', "__"),
+                    position,
+                    mutation.describe_change()
+                )
+            }
+        }
+    }
 }
 
 /// Run all possible mutation experiments.
 ///
 /// Before testing the mutations, the lab checks that the source tree passes its tests with no
-/// mutations applied.
+/// mutations applied, unless that's skipped or known from a previous run by `--baseline`.
 pub fn test_unmutated_then_all_mutants(
     source_tree: &SourceTree,
     options: &Options,
@@ -66,13 +122,53 @@ pub fn test_unmutated_then_all_mutants(
 ) -> Result<LabOutcome> {
     let mut options: Options = options.clone();
     let mut lab_outcome = LabOutcome::default();
+    if options.check_only {
+        lab_outcome.set_check_only();
+    }
+    lab_outcome.set_cargo_args(
+        &options.additional_cargo_args,
+        &options.additional_cargo_test_args,
+        &options.test_args,
+    );
+    lab_outcome.set_features(&options.features, options.profile.as_deref());
+    if options.verbose {
+        let phases = if options.check_only {
+            &[Phase::Check]
+        } else {
+            Phase::ALL
+        };
+        for &phase in phases {
+            console.message(&format!(
+                "{} command: cargo {}",
+                phase.name(),
+                cargo_args(phase, &Scenario::Baseline, &options).join(" ")
+            ));
+        }
+        console.message(&format!(
+            "test timeout: {}",
+            describe_test_timeout(&options)
+        ));
+        console.message(&format!(
+            "build timeout: {}",
+            describe_build_timeout(&options)
+        ));
+    }
+    // The cache and baseline are read before the previous output directory is rotated away.
+    // Outcomes from this run are added to the previous cache, so that mutants not
+    // tested this time, for example because they're filtered out, are still remembered.
+    let mut cache = Cache::read(&source_tree.root().join(OUTDIR_NAME))?;
+    let previous_baseline = if options.baseline == BaselineStrategy::Auto {
+        BaselineRecord::read(&source_tree.root().join(OUTDIR_NAME))?
+    } else {
+        None
+    };
     let output_dir = OutputDir::new(source_tree.root())?;
 
     if options.build_source {
         let outcome = check_and_build_source_tree(source_tree, &output_dir, &options, console)?;
         lab_outcome.add(&outcome);
         if !outcome.success() {
-            console::print_error(&format!(
+            console.print_error(&format!(
                 "{} failed in source tree, not continuing",
                 outcome.last_phase(),
             ));
@@ -80,72 +176,297 @@ pub fn test_unmutated_then_all_mutants(
         }
     }
 
+    // The tree is hashed after it's built, since building may write `Cargo.lock`.
+    let tree_hash = match options.baseline {
+        BaselineStrategy::Skip => None,
+        _ => Some(tree_hash(source_tree.root(), &options)?),
+    };
     let build_dir = copy_source_to_scratch(source_tree, console, &options)?;
-    let outcome = test_baseline(build_dir.path(), &output_dir, &options, console)?;
-    lab_outcome.add(&outcome);
-    if !outcome.success() {
-        console::print_error(&format!(
-            "cargo {} failed in an unmutated tree, so no mutants were tested",
-            outcome.last_phase(),
+    let build_path = build_dir.path().to_owned();
+    // With --leak-dirs, the scratch directory isn't deleted when the lab finishes.
+    let _build_dir = if options.leak_dirs {
+        build_dir.into_path();
+        None
+    } else {
+        Some(build_dir)
+    };
+    if options.leak_dirs {
+        console.message(&format!(
+            "scratch directory kept in {}",
+            build_path.to_slash_lossy()
         ));
-        return Ok(lab_outcome); // TODO: Maybe should be Err?
     }
-    if !options.has_test_timeout() {
-        if let Some(baseline_duration) = outcome.test_duration() {
-            let auto_timeout = max(Duration::from_secs(20), baseline_duration.mul_f32(5.0));
-            options.set_test_timeout(auto_timeout);
-            if options.show_times {
-                println!(
-                    "auto-set test timeout to {:.1}s",
-                    options.test_timeout().as_secs_f32()
-                );
+    let previous_baseline =
+        previous_baseline.filter(|record| Some(&record.tree_hash) == tree_hash.as_ref());
+    let baseline = if let Some(tree_hash) = tree_hash {
+        let record = if let Some(record) = previous_baseline {
+            console.message("unmutated baseline ... ok (unchanged since the previous run)");
+            record
+        } else {
+            let mut outcome = test_baseline(&build_path, &output_dir, &options, console)?;
+            if options.leak_dirs {
+                outcome.set_kept_dir(&build_path);
+            }
+            lab_outcome.add(&outcome);
+            if !outcome.success() {
+                console.print_error(&format!(
+                    "cargo {} failed in an unmutated tree, so no mutants were tested",
+                    outcome.last_phase(),
+                ));
+                return Ok(lab_outcome); // TODO: Maybe should be Err?
             }
+            BaselineRecord {
+                tree_hash,
+                build_duration: outcome.build_duration(),
+                test_duration: outcome.test_duration(),
+                tests_run: outcome
+                    .test_duration()
+                    .and_then(|_| outcome.get_log_content().ok())
+                    .and_then(|log| count_tests_run(&log)),
+            }
+        };
+        record.write(output_dir.path())?;
+        Some(record)
+    } else {
+        console.message("unmutated baseline ... skipped");
+        None
+    };
+    if baseline
+        .as_ref()
+        .is_some_and(|record| record.tests_run == Some(0))
+    {
+        warn(&mut lab_outcome, Warning::NoTests, console);
+    }
+    if options.has_test_timeout() {
+        lab_outcome.set_test_timeout(options.test_timeout(), None);
+    } else if let Some(baseline_duration) =
+        baseline.as_ref().and_then(|record| record.test_duration)
+    {
+        let auto_timeout = max(
+            options.minimum_test_timeout,
+            baseline_duration.mul_f64(options.timeout_multiplier),
+        );
+        options.set_test_timeout(auto_timeout);
+        lab_outcome.set_test_timeout(auto_timeout, Some(options.timeout_multiplier));
+        lab_outcome.set_minimum_test_timeout(options.minimum_test_timeout);
+        if options.show_times {
+            console.message(&format!(
+                "auto-set test timeout to {:.1}s",
+                options.test_timeout().as_secs_f32()
+            ));
+        }
+    }
+    // The baseline is often built incrementally on the source tree's build, so the build
+    // timeout is only set from it when asked.
+    if options.has_build_timeout() {
+        lab_outcome.set_build_timeout(options.build_timeout(), None);
+    } else if let (Some(multiplier), Some(baseline_duration)) = (
+        options.build_timeout_multiplier,
+        baseline.as_ref().and_then(|record| record.build_duration),
+    ) {
+        let auto_timeout = max(MINIMUM_BUILD_TIMEOUT, baseline_duration.mul_f64(multiplier));
+        options.set_build_timeout(auto_timeout);
+        lab_outcome.set_build_timeout(auto_timeout, Some(multiplier));
+        if options.show_times {
+            console.message(&format!(
+                "auto-set build timeout to {:.1}s",
+                options.build_timeout().as_secs_f32()
+            ));
         }
     }
 
-    let mut mutations = source_tree.mutations()?;
+    let discovery = source_tree.discover(&options)?;
+    let mut mutations = discovery.mutations;
+    if mutations.is_empty() {
+        warn(&mut lab_outcome, Warning::NoMutants, console);
+    }
+    if let Some(diff_dir) = &options.diff_dir {
+        write_diffs(diff_dir, &mutations)?;
+    }
+    if options.iterate {
+        mutations.retain(|mutation| {
+            if cache.was_caught(mutation) {
+                lab_outcome.add_cached_caught();
+                false
+            } else {
+                true
+            }
+        });
+        console.message(&format!(
+            "skipping {} {} caught in a previous run",
+            lab_outcome.cached_caught(),
+            if lab_outcome.cached_caught() == 1 {
+                "mutant"
+            } else {
+                "mutants"
+            }
+        ));
+    }
     if options.shuffle {
-        mutations.shuffle(&mut rand::thread_rng());
+        let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        mutations.shuffle(&mut StdRng::seed_from_u64(seed));
+        lab_outcome.set_seed(seed);
+        console.message(&format!("shuffled mutants with --seed {}", seed));
     }
 
     serde_json::to_writer_pretty(
         BufWriter::new(File::create(output_dir.path().join("mutants.json"))?),
         &mutations,
     )?;
-    println!(
-        "found {} {} to test",
-        mutations.len(),
-        if mutations.len() == 1 {
-            "mutation"
-        } else {
-            "mutations"
-        }
-    );
+    console.found_mutations(mutations.len(), lab_outcome.seed());
 
+    output_dir.write_outcomes(&lab_outcome)?;
+    cache.write(output_dir.path())?;
     let n_mutations = mutations.len();
-    for (i_mutation, mutation) in mutations.into_iter().enumerate() {
-        lab_outcome.add(&test_mutation(
-            &Scenario::Mutant {
-                mutation,
-                i_mutation,
-                n_mutations,
-            },
-            build_dir.path(),
-            &output_dir,
-            &options,
-            console,
-        )?);
 
-        // Rewrite outcomes.json every time, so we can watch it and so it's not
-        // lost if the program stops or is interrupted.
-        serde_json::to_writer_pretty(
-            BufWriter::new(File::create(output_dir.path().join("outcomes.json"))?),
-            &lab_outcome,
+    // Each job tests mutants in its own copy of the baseline tree, including the build
+    // products, so that it doesn't have to start from scratch.
+    let n_jobs = max(1, min(options.jobs, n_mutations));
+    let mut build_paths = vec![build_path];
+    let mut _job_dirs = Vec::new();
+    for i_job in 1..n_jobs {
+        let job_dir = TempDir::new()?;
+        copy_tree(
+            &build_paths[0],
+            job_dir.path(),
+            &format!("copy scratch directory for job {}", i_job + 1),
+            true,
+            console,
         )?;
+        build_paths.push(job_dir.path().to_owned());
+        if options.leak_dirs {
+            job_dir.into_path();
+        } else {
+            _job_dirs.push(job_dir);
+        }
     }
+    let (job_progress, jobs_display) = console.start_jobs(n_jobs);
+    // With only one job, each mutant has its own progress bar, as in the other phases.
+    let job_progress: Vec<Option<JobProgress>> = if n_jobs > 1 {
+        job_progress.into_iter().map(Some).collect()
+    } else {
+        vec![None]
+    };
+
+    let queue = Mutex::new(mutations.into_iter().enumerate());
+    let n_kept_dirs = AtomicUsize::new(0);
+    thread::scope(|scope| -> Result<()> {
+        scope.spawn(|| jobs_display.draw());
+        let (outcome_tx, outcome_rx) = mpsc::channel();
+        for (build_path, job) in build_paths.iter().zip(job_progress) {
+            let outcome_tx = outcome_tx.clone();
+            let (queue, n_kept_dirs, output_dir, options) =
+                (&queue, &n_kept_dirs, &output_dir, &options);
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap().next();
+                let (i_mutation, mutation) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result = test_mutation(
+                    &Scenario::Mutant {
+                        package: mutation.source_file.package().map(str::to_owned),
+                        mutation,
+                        i_mutation,
+                        n_mutations,
+                    },
+                    build_path,
+                    output_dir,
+                    options,
+                    console,
+                    job.as_ref(),
+                    n_kept_dirs.load(Ordering::SeqCst) < MAX_KEPT_DIRS,
+                );
+                if let Ok(outcome) = &result {
+                    if outcome.kept_dir().is_some()
+                        && n_kept_dirs.fetch_add(1, Ordering::SeqCst) + 1 == MAX_KEPT_DIRS
+                    {
+                        console.message(&format!(
+                            "kept {} mutated trees, which is the limit: no more will be kept",
+                            MAX_KEPT_DIRS
+                        ));
+                    }
+                }
+                let failed = result.is_err();
+                if outcome_tx.send((i_mutation, result)).is_err() || failed {
+                    break;
+                }
+            });
+        }
+        drop(outcome_tx);
+
+        // Outcomes are recorded in the order the mutants were dispatched, whichever job
+        // finishes first, so that the results don't depend on timing.
+        let mut finished: BTreeMap<usize, Outcome> = BTreeMap::new();
+        let mut n_recorded = 0;
+        let mut first_error = None;
+        for (i_mutation, result) in outcome_rx {
+            match result {
+                Ok(outcome) => {
+                    finished.insert(i_mutation, outcome);
+                }
+                Err(err) => {
+                    // Stop dispatching mutants, and wait for the other jobs to finish.
+                    queue.lock().unwrap().by_ref().for_each(drop);
+                    first_error.get_or_insert(err);
+                    continue;
+                }
+            }
+            while let Some(outcome) = finished.remove(&n_recorded) {
+                n_recorded += 1;
+                output_dir.add_outcome(&outcome)?;
+                lab_outcome.add(&outcome);
+                if baseline.is_none()
+                    && !lab_outcome
+                        .warnings()
+                        .contains(&Warning::BaselineMayBeBroken)
+                    && mutant_failed_elsewhere(&outcome)?
+                {
+                    warn(&mut lab_outcome, Warning::BaselineMayBeBroken, console);
+                }
+                if let (Scenario::Mutant { mutation, .. }, Some(status)) =
+                    (&outcome.scenario, outcome.mutant_status())
+                {
+                    cache.insert(mutation, status);
+                    cache.write(output_dir.path())?;
+                }
+
+                // Rewrite outcomes.json every time, so we can watch it and so it's not
+                // lost if the program stops or is interrupted.
+                output_dir.write_outcomes(&lab_outcome)?;
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    })?;
+    console.print_skipped(&discovery.skipped);
+    console.print_summary(&lab_outcome);
+    console.print_slowest(&lab_outcome, options.slowest);
+    output_dir.write_reports(&options)?;
     Ok(lab_outcome)
 }
 
+/// Print a warning and record it in the outcome.
+fn warn(lab_outcome: &mut LabOutcome, warning: Warning, console: &Console) {
+    console.print_warning(warning);
+    lab_outcome.add_warning(warning);
+}
+
+/// True if a mutant failed to build only because of errors in files other than the one it
+/// changed.
+fn mutant_failed_elsewhere(outcome: &Outcome) -> Result<bool> {
+    match &outcome.scenario {
+        Scenario::Mutant { mutation, .. }
+            if outcome.mutant_status() == Some(MutantStatus::Unviable) =>
+        {
+            Ok(errors_are_elsewhere(
+                &outcome.get_log_content()?,
+                &mutation.source_file.tree_relative_slashes(),
+            ))
+        }
+        _ => Ok(false),
+    }
+}
+
 /// Successively run cargo check, build, test, and return the overall outcome in a build
 /// directory, which might have a mutation applied or not.
 ///
@@ -159,51 +480,133 @@ fn run_cargo_phases(
     scenario: &Scenario,
     phases: &[Phase],
     console: &Console,
+    job: Option<&JobProgress>,
+) -> Result<Outcome> {
+    let mut activity = console.start_scenario(scenario, job);
+    let outcome = run_phases(
+        build_dir,
+        output_dir,
+        options,
+        scenario,
+        phases,
+        &mut activity,
+    )?;
+    activity.outcome(&outcome, options)?;
+    Ok(outcome)
+}
+
+/// Run the phases of a scenario, showing progress in `activity`, without reporting
+/// the outcome.
+fn run_phases(
+    build_dir: &Path,
+    output_dir: &OutputDir,
+    options: &Options,
+    scenario: &Scenario,
+    phases: &[Phase],
+    activity: &mut Activity,
 ) -> Result<Outcome> {
     let scenario_name = scenario.to_string();
-    let mut log_file = output_dir.create_log(&scenario_name)?;
+    let mut log_file = output_dir.create_log(&scenario.log_name())?;
     log_file.message(&scenario_name);
     if let Scenario::Mutant { mutation, .. } = scenario {
         log_file.message(&mutation.diff());
     }
-    let mut activity = console.start_scenario(scenario);
 
     let mut outcome = Outcome::new(&log_file, scenario.clone());
     for &phase in phases {
         let phase_start = Instant::now();
         activity.set_phase(phase.name());
-        let cargo_args = match phase {
-            Phase::Check => vec!["check", "--tests"],
-            Phase::Build => vec!["build", "--tests"],
-            Phase::Test => std::iter::once("test")
-                .chain(
-                    options
-                        .additional_cargo_test_args
-                        .iter()
-                        .map(String::as_str),
-                )
-                .collect(),
-        };
+        let cargo_args = cargo_args(phase, scenario, options);
         let timeout = match phase {
             Phase::Test => options.test_timeout(),
-            _ => Duration::MAX,
+            Phase::Check | Phase::Build => options.build_timeout(),
         };
-        let cargo_result = run_cargo(
-            &cargo_args,
-            build_dir,
-            &mut activity,
-            &mut log_file,
-            timeout,
-        )?;
+        let cargo_result = run_cargo(&cargo_args, build_dir, activity, &mut log_file, timeout)?;
         outcome.add_phase_result(phase, phase_start.elapsed(), cargo_result);
         if (phase == Phase::Check && options.check_only) || !cargo_result.success() {
             break;
         }
     }
-    activity.outcome(&outcome, options)?;
     Ok(outcome)
 }
 
+/// Describe the test timeout that's been given, or how it will be set from the baseline.
+fn describe_test_timeout(options: &Options) -> String {
+    if options.has_test_timeout() {
+        format!("{:.1}s", options.test_timeout().as_secs_f64())
+    } else {
+        format!(
+            "{}x the baseline test time, at least {:.1}s",
+            options.timeout_multiplier,
+            options.minimum_test_timeout.as_secs_f64()
+        )
+    }
+}
+
+/// Describe the build timeout that's been given, or how it will be set from the
+/// baseline, if it will be.
+fn describe_build_timeout(options: &Options) -> String {
+    if options.has_build_timeout() {
+        format!("{:.1}s", options.build_timeout().as_secs_f64())
+    } else if let Some(multiplier) = options.build_timeout_multiplier {
+        format!(
+            "{}x the baseline build time, at least {:.1}s",
+            multiplier,
+            MINIMUM_BUILD_TIMEOUT.as_secs_f64()
+        )
+    } else {
+        "none".to_owned()
+    }
+}
+
+/// Return the arguments to cargo for one phase of a scenario.
+fn cargo_args<'a>(phase: Phase, scenario: &'a Scenario, options: &'a Options) -> Vec<&'a str> {
+    let mut cargo_args = match phase {
+        Phase::Check => vec!["check", "--tests"],
+        Phase::Build => vec!["build", "--tests"],
+        Phase::Test => vec!["test"],
+    };
+    // Each mutant is tested with its own package's tests; otherwise, the selected
+    // packages are built and tested, or by default, cargo's default packages.
+    match scenario {
+        Scenario::Mutant {
+            package: Some(package),
+            ..
+        } => cargo_args.extend(["-p", package]),
+        _ if options.workspace => {
+            cargo_args.push("--workspace");
+            for package in &options.exclude_packages {
+                cargo_args.extend(["--exclude", package]);
+            }
+        }
+        _ => {
+            for package in &options.packages {
+                cargo_args.extend(["-p", package]);
+            }
+        }
+    }
+    // The same profile is used in every phase, so that the tests run what was built, and
+    // the baseline sets the timeout from tests built the same way as the mutants.
+    if let Some(profile) = &options.profile {
+        cargo_args.extend(["--profile", profile]);
+    }
+    cargo_args.extend(options.features.cargo_args());
+    cargo_args.extend(options.additional_cargo_args.iter().map(String::as_str));
+    if phase == Phase::Test {
+        cargo_args.extend(
+            options
+                .additional_cargo_test_args
+                .iter()
+                .map(String::as_str),
+        );
+        if !options.test_args.is_empty() {
+            cargo_args.push("--");
+            cargo_args.extend(options.test_args.iter().map(String::as_str));
+        }
+    }
+    cargo_args
+}
+
 fn copy_source_to_scratch(
     source: &SourceTree,
     console: &Console,
@@ -211,21 +614,46 @@ fn copy_source_to_scratch(
 ) -> Result<TempDir> {
     let temp_dir = TempDir::new()?;
     let copy_target = options.copy_target;
-    let mut activity = console.start_copy_activity(if copy_target {
-        "copy source and build products to scratch directory"
-    } else {
-        "copy source to scratch directory"
-    });
+    copy_tree(
+        source.root(),
+        temp_dir.path(),
+        if copy_target {
+            "copy source and build products to scratch directory"
+        } else {
+            "copy source to scratch directory"
+        },
+        copy_target,
+        console,
+    )?;
+    Ok(temp_dir)
+}
+
+/// Copy a tree into an existing directory, showing progress as the activity `name`.
+///
+/// The output directories of earlier runs are never copied, and `target` is copied only
+/// if `copy_target` is true.
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    name: &str,
+    copy_target: bool,
+    console: &Console,
+) -> Result<()> {
+    let mut activity = console.start_copy_activity(name);
     let target_path = Path::new("target");
+    // Output from earlier runs is never needed in the scratch directory.
+    let output_paths = [Path::new(OUTDIR_NAME), Path::new(ROTATED_NAME)];
     match cp_r::CopyOptions::new()
         .after_entry_copied(|path, _ft, stats| {
             activity.bytes_copied(stats.file_bytes);
             check_interrupted().map_err(|_| cp_r::Error::new(cp_r::ErrorKind::Interrupted, path))
         })
         .filter(|path, dir_entry| {
-            Ok(copy_target || !(dir_entry.file_type().unwrap().is_dir() && path == target_path))
+            let is_dir = dir_entry.file_type().unwrap().is_dir();
+            Ok(!(is_dir && output_paths.contains(&path))
+                && (copy_target || !(is_dir && path == target_path)))
         })
-        .copy_tree(source.root(), &temp_dir.path())
+        .copy_tree(from, to)
         .context("copy source tree to lab directory")
     {
         Ok(stats) => activity.succeed(stats.file_bytes),
@@ -233,14 +661,14 @@ fn copy_source_to_scratch(
             activity.fail();
             eprintln!(
                 "error copying source tree {} to {}: {:?}",
-                &source.root().to_slash_lossy(),
-                &temp_dir.path().to_slash_lossy(),
+                &from.to_slash_lossy(),
+                &to.to_slash_lossy(),
                 err
             );
             return Err(err);
         }
     }
-    Ok(temp_dir)
+    Ok(())
 }
 
 /// Build tests in the original source tree.
@@ -266,6 +694,7 @@ fn check_and_build_source_tree(
         &Scenario::SourceTree,
         phases,
         console,
+        None,
     )
 }
 
@@ -286,29 +715,63 @@ fn test_baseline(
         &Scenario::Baseline,
         Phase::ALL,
         console,
+        None,
     )
 }
 
-/// Test with one mutation applied.
+/// Test with one mutation applied, showing progress on the job's progress bar if there
+/// are several jobs.
+///
+/// If `may_keep_dir` is true, and `--leak-dirs` is given or the mutant is missed with
+/// `--keep-missed-dirs`, a copy of the mutated tree is kept, before the outcome is
+/// reported so that the report can say where it is.
 fn test_mutation(
     scenario: &Scenario,
     build_dir: &Path,
     output_dir: &OutputDir,
     options: &Options,
     console: &Console,
+    job: Option<&JobProgress>,
+    may_keep_dir: bool,
 ) -> Result<Outcome> {
     if let Scenario::Mutant { mutation, .. } = scenario {
         mutation.with_mutation_applied(build_dir, || {
-            run_cargo_phases(
+            let mut activity = console.start_scenario(scenario, job);
+            let mut outcome = run_phases(
                 build_dir,
                 output_dir,
                 options,
                 scenario,
                 Phase::ALL,
-                console,
-            )
+                &mut activity,
+            )?;
+            if may_keep_dir
+                && (options.leak_dirs || (options.keep_missed_dirs && outcome.mutant_missed()))
+            {
+                activity.set_phase("keep");
+                outcome.set_kept_dir(&keep_mutated_tree(build_dir, console)?);
+            }
+            activity.outcome(&outcome, options)?;
+            Ok(outcome)
         })
     } else {
         unreachable!()
     }
 }
+
+/// Copy the build directory, with a mutation applied, to a new directory that's not
+/// deleted, and return its path.
+fn keep_mutated_tree(build_dir: &Path, console: &Console) -> Result<PathBuf> {
+    let kept_dir = tempfile::Builder::new()
+        .prefix("cargo-mutants-kept-")
+        .tempdir()?
+        .into_path();
+    copy_tree(
+        build_dir,
+        &kept_dir,
+        &format!("keep mutated tree in {}", kept_dir.to_slash_lossy()),
+        true,
+        console,
+    )?;
+    Ok(kept_dir)
+}
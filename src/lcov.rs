@@ -0,0 +1,136 @@
+// Copyright 2022 Martin Pool
+
+//! An lcov-format `lcov.info` of which lines have caught and missed mutants, built from
+//! `outcomes.json`, so that tools that show line coverage can show mutation results.
+//!
+//! Each line with caught or missed mutants has a `DA` record. Its count is the number
+//! of mutants caught on that line, or 0 if any were missed, so that lines with missed
+//! mutants show as uncovered. Each mutant is also a branch, in a `BRDA` record, which
+//! is taken if the mutant was caught. Unviable mutants, and those that timed out or
+//! weren't tested, aren't counted.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::report::{LabOutcomeRecord, Totals};
+
+/// Return the lcov tracefile for the mutants in this record.
+pub fn lcov_report(record: &LabOutcomeRecord) -> String {
+    let mut by_file: BTreeMap<&str, Vec<(usize, Totals)>> = BTreeMap::new();
+    for ((file, line), totals) in record.totals_by_line() {
+        if totals.caught + totals.missed > 0 {
+            by_file.entry(file).or_default().push((line, totals));
+        }
+    }
+    let mut lcov = String::new();
+    for (file, lines) in by_file {
+        lcov.push_str("TN:\n");
+        writeln!(lcov, "SF:{}", file).unwrap();
+        let mut branches = 0;
+        let mut branches_hit = 0;
+        for (line, totals) in &lines {
+            for i in 0..totals.caught + totals.missed {
+                let taken = if i < totals.caught { "1" } else { "0" };
+                writeln!(lcov, "BRDA:{},0,{},{}", line, i, taken).unwrap();
+            }
+            branches += totals.caught + totals.missed;
+            branches_hit += totals.caught;
+        }
+        writeln!(lcov, "BRF:{}", branches).unwrap();
+        writeln!(lcov, "BRH:{}", branches_hit).unwrap();
+        for (line, totals) in &lines {
+            let count = if totals.missed > 0 { 0 } else { totals.caught };
+            writeln!(lcov, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(lcov, "LF:{}", lines.len()).unwrap();
+        writeln!(
+            lcov,
+            "LH:{}",
+            lines
+                .iter()
+                .filter(|(_, totals)| totals.missed == 0)
+                .count()
+        )
+        .unwrap();
+        lcov.push_str("end_of_record\n");
+    }
+    lcov
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn outcome(file: &str, line: usize, phase: &str, cargo_result: &str) -> String {
+        format!(
+            r#"{{ "scenario": {{ "Mutant": {{ "mutation": {{
+                "file": "{file}",
+                "span": {{ "start": {{ "line": {line}, "column": 1 }}, "end": {{ "line": {line}, "column": 2 }} }},
+                "function": "f", "replacement": "0", "change": "replace f with 0",
+                "description": "replace f with 0 in {file}:{line}"
+            }} }} }},
+            "phase_results": [
+                {{ "phase": "{phase}", "duration": {{ "secs": 1, "nanos": 0 }}, "cargo_result": "{cargo_result}" }}
+            ] }}"#,
+            file = file,
+            line = line,
+            phase = phase,
+            cargo_result = cargo_result,
+        )
+    }
+
+    #[test]
+    fn lines_with_missed_mutants_are_not_hit() {
+        let outcomes = [
+            outcome("src/b.rs", 7, "Test", "Success"),
+            outcome("src/a.rs", 3, "Test", "Failure"),
+            outcome("src/a.rs", 3, "Test", "Failure"),
+            outcome("src/a.rs", 10, "Test", "Failure"),
+            outcome("src/a.rs", 10, "Test", "Success"),
+            // Unviable mutants aren't counted.
+            outcome("src/a.rs", 20, "Build", "Failure"),
+        ];
+        let record: LabOutcomeRecord =
+            serde_json::from_str(&format!(r#"{{ "outcomes": [{}] }}"#, outcomes.join(", ")))
+                .unwrap();
+        assert_eq!(
+            lcov_report(&record),
+            "\
+TN:
+SF:src/a.rs
+BRDA:3,0,0,1
+BRDA:3,0,1,1
+BRDA:10,0,0,1
+BRDA:10,0,1,0
+BRF:4
+BRH:3
+DA:3,2
+DA:10,0
+LF:2
+LH:1
+end_of_record
+TN:
+SF:src/b.rs
+BRDA:7,0,0,0
+BRF:1
+BRH:0
+DA:7,0
+LF:1
+LH:0
+end_of_record
+"
+        );
+    }
+
+    #[test]
+    fn no_tested_mutants_give_an_empty_file() {
+        let record: LabOutcomeRecord = serde_json::from_str(&format!(
+            r#"{{ "outcomes": [{}] }}"#,
+            outcome("src/a.rs", 1, "Build", "Failure")
+        ))
+        .unwrap();
+        assert_eq!(lcov_report(&record), "");
+    }
+}
@@ -8,12 +8,17 @@ use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
+use sha2::{Digest, Sha256};
 
 use crate::Result;
 
 /// Text inserted in log files to make important sections more visible.
 pub const LOG_MARKER: &str = "***";
 
+/// The longest name, in bytes, that [clean_filename] returns without shortening it, which
+/// leaves room for a hash, a number, and an extension within the usual limit of 255.
+const MAX_CLEAN_FILENAME: usize = 100;
+
 /// A log file for execution of a single scenario.
 #[derive(Debug)]
 pub struct LogFile {
@@ -76,13 +81,74 @@ impl LogFile {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// The path of the log relative to the parent of its directory, which is the output
+    /// directory, like `log/baseline.log`.
+    pub fn relative_path(&self) -> String {
+        let mut components: Vec<String> = self
+            .path
+            .iter()
+            .rev()
+            .take(2)
+            .map(|c| c.to_string_lossy().into_owned())
+            .collect();
+        components.reverse();
+        components.join("/")
+    }
 }
 
-fn clean_filename(s: &str) -> String {
-    s.chars()
+/// Replace characters that are awkward or not allowed in filenames.
+///
+/// Names longer than [MAX_CLEAN_FILENAME] bytes, such as those describing mutants of
+/// long expressions, are cut short, and a hash of the whole name is added so that they
+/// stay unique.
+pub fn clean_filename(s: &str) -> String {
+    let clean = s
+        .chars()
         .map(|c| match c {
             '/' | '\\' | ' ' | ':' | '<' | '>' | '?' | '*' | '|' | '"' => '_',
             c => c,
         })
-        .collect::<String>()
+        .collect::<String>();
+    if clean.len() <= MAX_CLEAN_FILENAME {
+        return clean;
+    }
+    let mut end = MAX_CLEAN_FILENAME;
+    while !clean.is_char_boundary(end) {
+        end -= 1;
+    }
+    let hash = format!("{:x}", Sha256::digest(s.as_bytes()));
+    format!("{}_{}", &clean[..end], &hash[..8])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clean_filename_replaces_awkward_characters() {
+        assert_eq!(
+            clean_filename("src/lib.rs: replace a -> bool with \"x\""),
+            "src_lib.rs__replace_a_-__bool_with__x_"
+        );
+    }
+
+    #[test]
+    fn long_names_are_shortened_but_stay_unique() {
+        let long_a = format!("src/lib.rs_line3_col5_delete {}(a)", "é".repeat(150));
+        let long_b = format!("src/lib.rs_line3_col5_delete {}(b)", "é".repeat(150));
+        let clean_a = clean_filename(&long_a);
+        let clean_b = clean_filename(&long_b);
+        assert!(clean_a.len() <= MAX_CLEAN_FILENAME + 9, "{}", clean_a);
+        assert!(clean_a.starts_with("src_lib.rs_line3_col5_delete_é"));
+        assert_ne!(clean_a, clean_b);
+        assert_eq!(clean_filename(&long_a), clean_a);
+
+        let log_dir = tempfile::tempdir().unwrap();
+        let log_file = LogFile::create_in(log_dir.path(), &long_a).unwrap();
+        assert_eq!(
+            log_file.path().file_name().unwrap().to_str().unwrap(),
+            format!("{}.log", clean_a)
+        );
+    }
 }
@@ -2,46 +2,114 @@
 
 //! `cargo-mutants`: Find inadequately-tested code that can be removed without any tests failing.
 
+mod annotation;
+mod badge;
+mod baseline;
+mod cache;
+mod completions;
+mod config;
 mod console;
+mod events;
 mod exit_code;
+mod features;
+mod file_table;
+mod fnvalue;
+mod html;
+mod in_diff;
 mod interrupt;
+mod junit;
 mod lab;
+mod lcov;
 mod log_file;
+mod markdown;
 mod mutate;
+mod operator;
 mod options;
 mod outcome;
 mod output;
+mod package;
+mod report;
 mod run;
+mod sarif;
+mod shard;
 mod source;
+mod tap;
 mod textedit;
 mod visit;
 
+use std::collections::BTreeMap;
 use std::env;
+use std::ffi::OsStr;
 use std::io;
 use std::path::PathBuf;
 use std::process::exit;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use argh::FromArgs;
 #[allow(unused)]
 use path_slash::PathExt;
 
 // Imports of public names from this crate.
+use crate::annotation::{AnnotationLevel, Annotations};
+use crate::baseline::BaselineStrategy;
+use crate::completions::Shell;
+use crate::config::Config;
+use crate::console::Colors;
 use crate::interrupt::check_interrupted;
 pub use crate::lab::Scenario;
 use crate::options::Options;
 pub use crate::run::CargoResult;
+use crate::shard::Shard;
 use crate::source::SourceTree;
 
 /// Find inadequately-tested code that can be removed without any tests failing.
 ///
 /// See <https://github.com/sourcefrog/cargo-mutants> for more information.
 #[derive(FromArgs, PartialEq, Debug)]
+#[argh(
+    error_code(
+        0,
+        "all mutants were caught, or missed mutants with --no-fail-on-missed."
+    ),
+    error_code(1, "usage error, such as bad arguments or an invalid config file."),
+    error_code(2, "some mutants were not caught by tests."),
+    error_code(3, "some tests timed out."),
+    error_code(
+        4,
+        "the source tree failed to build, or its tests failed before mutation."
+    ),
+    error_code(
+        5,
+        "with --fail-on-empty, no mutants were found, including by --count, or the unmutated tree ran no tests; or --list-files found no files."
+    ),
+    error_code(70, "internal error."),
+    error_code(130, "interrupted by ctrl-c.")
+)]
 struct Args {
+    /// annotate missed mutants as warning or error: default warning.
+    #[argh(option)]
+    annotation_level: Option<AnnotationLevel>,
+
+    /// print annotations on missed mutants for CI: github or none, by default github when GITHUB_ACTIONS=true.
+    #[argh(option)]
+    annotations: Option<Annotations>,
+
     /// show cargo output for all invocations (very verbose).
     #[argh(switch)]
     all_logs: bool,
 
+    /// test the unmutated tree first: run, auto, or skip; by default, auto reuses the previous run's baseline if the tree is unchanged.
+    #[argh(option)]
+    baseline: Option<BaselineStrategy>,
+
+    /// pass this argument to every cargo check, build, and test: can be repeated.
+    #[argh(option)]
+    cargo_arg: Vec<String>,
+
+    /// enable all cargo features of the selected packages.
+    #[argh(switch)]
+    all_features: bool,
+
     /// print mutations that were caught by tests.
     #[argh(switch, short = 'v')]
     caught: bool,
@@ -50,50 +118,254 @@ struct Args {
     #[argh(switch)]
     check: bool,
 
+    /// print a completion script for this shell: bash, zsh, fish, or powershell.
+    #[argh(option)]
+    completions: Option<Shell>,
+
+    /// color output: auto, always, or never; by default, colors are used on terminals.
+    #[argh(option)]
+    color: Option<Colors>,
+
     /// show the mutation diffs.
     #[argh(switch)]
     diff: bool,
 
-    /// rust crate directory to examine.
-    #[argh(option, short = 'd', default = r#"PathBuf::from(".")"#)]
-    dir: PathBuf,
+    /// write the diff of each mutant into a .patch file in this directory.
+    #[argh(option)]
+    diff_dir: Option<PathBuf>,
+
+    /// print the diff of each mutant that's not caught, under its result.
+    #[argh(switch)]
+    diff_missed: bool,
+
+    /// rust crate directory to examine: by default, the current directory.
+    #[argh(option, short = 'd')]
+    dir: Option<PathBuf>,
+
+    /// return this error value from functions returning Result: for example, `::anyhow::anyhow!("mutated")`.
+    #[argh(option)]
+    error: Vec<String>,
+
+    /// don't mutate files whose path relative to the tree matches this glob, such as `src/generated/**`: can be repeated.
+    #[argh(option, short = 'e')]
+    exclude: Vec<String>,
+
+    /// don't test mutants whose name, as shown by --list, matches this regexp: can be repeated.
+    #[argh(option)]
+    exclude_re: Vec<String>,
 
-    /// output json (only for --list).
+    /// don't mutate or test this package in the workspace: can be repeated.
+    #[argh(option)]
+    exclude_package: Vec<String>,
+
+    /// exit with an error if no mutants are found, or if the unmutated tree runs no tests.
+    #[argh(switch)]
+    fail_on_empty: bool,
+
+    /// enable these cargo features, separated by commas or spaces, for every cargo command: can be repeated.
+    #[argh(option, short = 'F')]
+    features: Vec<String>,
+
+    /// only mutate files whose path relative to the tree matches this glob, such as `src/parser/**.rs`: can be repeated.
+    #[argh(option, short = 'f')]
+    file: Vec<String>,
+
+    /// only mutate functions whose name, as shown by --list, such as `Parser::parse_header`, is this or matches this glob: can be repeated.
+    #[argh(option)]
+    function: Vec<String>,
+
+    /// only test mutants of lines added or changed by this unified diff, such as from `git diff`, or `-` to read it from stdin.
+    #[argh(option)]
+    in_diff: Option<PathBuf>,
+
+    /// return this value from functions returning integers, replacing the defaults of 0, 1, and -1: can be repeated, and `MAX` or `MIN` gives the type's limit.
+    #[argh(option)]
+    int_value: Vec<String>,
+
+    /// skip mutants that were caught in the previous run and whose function hasn't changed, testing only new or missed mutants.
+    #[argh(switch)]
+    iterate: bool,
+
+    /// test this many mutants at once, each in its own copy of the tree, which needs more memory and disk: default 1.
+    #[argh(option, short = 'j')]
+    jobs: Option<usize>,
+
+    /// keep a copy of the mutated tree of each mutant that's not caught, for debugging.
+    #[argh(switch)]
+    keep_missed_dirs: bool,
+
+    /// output json: with --list, a list of mutants; with --list-files, a list of files and their packages; with --count, the number of mutants in total and in each file; otherwise, a stream of events as mutants are tested.
     #[argh(switch)]
     json: bool,
 
+    /// keep the scratch directory, and a copy of the tree for every mutant, rather than deleting them.
+    #[argh(switch)]
+    leak_dirs: bool,
+
     /// just list possible mutants, don't run them.
     #[argh(switch)]
     list: bool,
 
+    /// just print the number of mutants, without building or copying the tree.
+    #[argh(switch)]
+    count: bool,
+
+    /// just list the source files that would be mutated, without building or copying the tree.
+    #[argh(switch)]
+    list_files: bool,
+
+    /// maximum number of literal mutants to generate in each function, with --mutate-literals: default 10.
+    #[argh(option)]
+    max_literal_mutants: Option<usize>,
+
+    /// maximum number of missed mutants to list in mutants.out/summary.md: default 20.
+    #[argh(option)]
+    max_missed_in_summary: Option<usize>,
+
+    /// path to the Cargo.toml of the crate to examine, as an alternative to --dir.
+    #[argh(option)]
+    manifest_path: Option<PathBuf>,
+
+    /// maximum number of mutants to generate for a function returning a tuple: default 4.
+    #[argh(option)]
+    max_tuple_mutants: Option<usize>,
+
+    /// also mutate code under #[cfg(test)], such as test helpers, which is skipped by default.
+    #[argh(switch)]
+    mutate_cfg_test: bool,
+
+    /// also change integer literals within functions by one: this can generate many mutants.
+    #[argh(switch)]
+    mutate_literals: bool,
+
+    /// also replace string literals within functions, other than format strings and patterns: non-empty strings with "", and empty strings with "xyzzy".
+    #[argh(switch)]
+    mutate_strings: bool,
+
+    /// don't use outcomes cached from the previous run, even with --iterate: test every mutant.
+    #[argh(switch)]
+    no_cache: bool,
+
+    /// exit successfully even if some mutants are not caught, so that CI only reports them.
+    #[argh(switch)]
+    no_fail_on_missed: bool,
+
+    /// don't enable the default cargo features.
+    #[argh(switch)]
+    no_default_features: bool,
+
     /// don't copy the /target directory, and don't build the source tree first.
     #[argh(switch)]
     no_copy_target: bool,
 
+    /// don't generate mutants inside functions, such as changing operators or deleting match arms: only replace whole function bodies, or the field values returned by Default::default.
+    #[argh(switch)]
+    no_operators: bool,
+
+    /// test mutants in the order they're found, even with --shuffle or --seed.
+    #[argh(switch)]
+    no_shuffle: bool,
+
+    /// don't read the config file, .cargo/mutants.toml in the source tree.
+    #[argh(switch)]
+    no_config: bool,
+
     /// don't print times or tree sizes, to make output deterministic.
     #[argh(switch)]
     no_times: bool,
 
+    /// only mutate `pub` functions, outside of private modules and traits.
+    #[argh(switch)]
+    only_pub: bool,
+
+    /// only mutate `pub` and `pub(crate)` functions, outside of private modules and traits.
+    #[argh(switch)]
+    only_pub_crate: bool,
+
+    /// only test mutants whose name, as shown by --list, matches this regexp: can be repeated.
+    #[argh(option)]
+    re: Vec<String>,
+
+    /// only mutate and test this package in the workspace, found by `cargo metadata`: can be repeated.
+    #[argh(option, short = 'p')]
+    package: Vec<String>,
+
+    /// build and test with this cargo profile, such as `release`, for every cargo command.
+    #[argh(option)]
+    profile: Option<String>,
+
+    /// build and test with the release profile, like --profile release.
+    #[argh(switch)]
+    release: bool,
+
+    /// shuffle the mutants with this random seed, as recorded in `outcomes.json` by an earlier shuffled run, to repeat its order.
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// test only one shard of the mutants, like `0/8` for the first of 8, to split a run across machines.
+    #[argh(option)]
+    shard: Option<Shard>,
+
     /// run mutants in random order.
     #[argh(switch)]
     shuffle: bool,
 
-    /// maximum run time for all cargo commands, in seconds.
+    /// number of slowest mutants to list at the end of the run, with their times: default 10, or 0 for none.
+    #[argh(option)]
+    slowest: Option<usize>,
+
+    /// don't delete statements that call a function or method with this name, as well as the defaults such as `drop` and `lock`: can be repeated.
+    #[argh(option)]
+    skip_calls: Vec<String>,
+
+    /// print results in TAP (Test Anything Protocol) format, without progress bars.
+    #[argh(switch)]
+    tap: bool,
+
+    /// pass this argument to the test binaries, after `--` in cargo test: can be repeated.
+    #[argh(option)]
+    test_arg: Vec<String>,
+
+    /// maximum run time for the tests of each mutant, in seconds: by default it's set from the baseline.
     #[argh(option, short = 't')]
     timeout: Option<f64>,
 
+    /// minimum test timeout, in seconds, when it's set from the baseline: default 20.
+    #[argh(option)]
+    minimum_test_timeout: Option<f64>,
+
+    /// multiply the baseline test time by this to set the test timeout: default 5.
+    #[argh(option)]
+    timeout_multiplier: Option<f64>,
+
+    /// maximum run time for each check or build of a mutant, in seconds.
+    #[argh(option)]
+    build_timeout: Option<f64>,
+
+    /// set the build timeout by multiplying the baseline build time by this, with a minimum of 20 seconds.
+    #[argh(option)]
+    build_timeout_multiplier: Option<f64>,
+
     /// print mutations that failed to check or build.
     #[argh(switch, short = 'V')]
     unviable: bool,
 
+    /// print more about what's being done, such as which files are skipped by --file and --exclude.
+    #[argh(switch)]
+    verbose: bool,
+
+    /// mutate every package in the workspace, and test each mutant with the tests of its own package.
+    #[argh(switch)]
+    workspace: bool,
+
     // The following option captures all the remaining non-option args, to
     // send to cargo.
-    /// pass remaining arguments to cargo test after all options and after `--`.
+    /// pass remaining arguments after `--` to every cargo check, build, and test, and those after a second `--` to the test binaries.
     #[argh(positional)]
-    cargo_test_args: Vec<String>,
+    cargo_args: Vec<String>,
 }
 
-fn main() -> Result<()> {
+fn main() {
     if let Some(subcommand) = env::args().nth(1) {
         if subcommand != "mutants" {
             eprintln!("unrecognized cargo subcommand {:?}", subcommand);
@@ -104,12 +376,98 @@ fn main() -> Result<()> {
         exit(exit_code::USAGE);
     }
     let args: Args = argh::cargo_from_env();
-    let source_tree = SourceTree::new(&args.dir)?;
-    let options = Options::from(&args);
-    let console = console::Console::new(&options);
+    if let Some(shell) = args.completions {
+        // The flags come from the help text that argh generates, so that the
+        // completions can't drift from the real arguments.
+        let help = match Args::from_args(&["mutants"], &["--help"]) {
+            Err(early_exit) => early_exit.output,
+            Ok(_) => unreachable!("--help always exits early"),
+        };
+        print!("{}", completions::completion_script(shell, &help));
+        exit(exit_code::SUCCESS);
+    }
+    let mut source_tree = match tree_dir(&args).and_then(|dir| SourceTree::new(&dir)) {
+        Ok(source_tree) => source_tree,
+        Err(err) => exit_with_usage_error(&args, &err),
+    };
+    let options = match load_options(&args, &mut source_tree) {
+        Ok(options) => options,
+        Err(err) => exit_with_usage_error(&args, &err),
+    };
+    match run(&args, &source_tree, &options) {
+        Ok(code) => exit(code),
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            if check_interrupted().is_err() {
+                exit(exit_code::INTERRUPTED);
+            }
+            exit(exit_code::SOFTWARE);
+        }
+    }
+}
+
+/// Print an error in the tree, config, or arguments, in the requested output format, and
+/// exit.
+fn exit_with_usage_error(args: &Args, err: &anyhow::Error) -> ! {
+    // The options couldn't be built, but the output mode and colors can still be taken
+    // from the arguments.
+    let mut options = Options::default();
+    options.output_mode = options::output_mode(args).unwrap_or_default();
+    options.colors = args.color.unwrap_or_default();
+    console::Console::new(&options).print_error(&format!("{:#}", err));
+    exit(exit_code::USAGE);
+}
+
+/// Return the directory of the source tree, from `--dir` or `--manifest-path`.
+fn tree_dir(args: &Args) -> Result<PathBuf> {
+    match (&args.dir, &args.manifest_path) {
+        (Some(_), Some(_)) => bail!("--dir and --manifest-path can't be used together"),
+        (Some(dir), None) => Ok(dir.clone()),
+        (None, Some(manifest_path)) => {
+            if manifest_path.file_name() != Some(OsStr::new("Cargo.toml")) {
+                bail!(
+                    "--manifest-path must be a path to a Cargo.toml file: {}",
+                    manifest_path.to_slash_lossy()
+                );
+            }
+            match manifest_path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => Ok(dir.to_owned()),
+                _ => Ok(PathBuf::from(".")),
+            }
+        }
+        (None, None) => Ok(PathBuf::from(".")),
+    }
+}
+
+/// Build options from the arguments and the tree's config file, and select the packages
+/// to mutate in the tree.
+///
+/// Errors here are usage errors.
+fn load_options(args: &Args, source_tree: &mut SourceTree) -> Result<Options> {
+    let config = if args.no_config {
+        Config::default()
+    } else {
+        Config::read_tree_config(source_tree)?
+    };
+    let mut options = Options::new(args, &config)?;
+    let packages = source_tree.select_packages(&options)?;
+    if !options.workspace {
+        // Name the selected packages to cargo, so that excluded default members aren't
+        // built or tested.
+        options.packages = packages;
+    }
+    Ok(options)
+}
+
+/// List or test mutants, and return the exit code.
+fn run(args: &Args, source_tree: &SourceTree, options: &Options) -> Result<i32> {
+    let console = console::Console::new(options);
     interrupt::install_handler();
     if args.list {
-        let mutations = source_tree.mutations()?;
+        let mutations = source_tree.discover(options)?.mutations;
+        if let Some(diff_dir) = &options.diff_dir {
+            output::write_diffs(diff_dir, &mutations)?;
+        }
         if args.json {
             if args.diff {
                 eprintln!("--list --diff --json is not (yet) supported");
@@ -119,11 +477,52 @@ fn main() -> Result<()> {
         } else {
             console::list_mutations(&mutations, args.diff);
         }
+    } else if args.count {
+        let mutations = source_tree.discover(options)?.mutations;
+        if args.json {
+            let mut files: BTreeMap<String, usize> = BTreeMap::new();
+            for mutation in &mutations {
+                *files
+                    .entry(mutation.source_file.tree_relative_slashes())
+                    .or_default() += 1;
+            }
+            let count = serde_json::json!({
+                "total": mutations.len(),
+                "files": files,
+            });
+            serde_json::to_writer_pretty(io::BufWriter::new(io::stdout()), &count)?;
+        } else {
+            println!("{}", mutations.len());
+        }
+        if mutations.is_empty() && options.fail_on_empty {
+            eprintln!("no mutants found");
+            return Ok(exit_code::EMPTY);
+        }
+    } else if args.list_files {
+        let files = source_tree.examined_files(options)?;
+        if args.json {
+            let files: Vec<serde_json::Value> = files
+                .iter()
+                .map(|file| {
+                    serde_json::json!({
+                        "path": file.tree_relative_slashes(),
+                        "package": file.package(),
+                    })
+                })
+                .collect();
+            serde_json::to_writer_pretty(io::BufWriter::new(io::stdout()), &files)?;
+        } else {
+            for file in &files {
+                println!("{}", file.tree_relative_slashes());
+            }
+        }
+        if files.is_empty() {
+            eprintln!("no source files found");
+            return Ok(exit_code::EMPTY);
+        }
     } else {
-        let lab_outcome = lab::test_unmutated_then_all_mutants(&source_tree, &options, &console)?;
-        // TODO: Perhaps print a text summary of how many were tested and whether they were all
-        // caught?
-        exit(lab_outcome.exit_code());
+        let lab_outcome = lab::test_unmutated_then_all_mutants(source_tree, options, &console)?;
+        return Ok(lab_outcome.exit_code(options));
     }
-    Ok(())
+    Ok(exit_code::SUCCESS)
 }
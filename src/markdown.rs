@@ -0,0 +1,200 @@
+// Copyright 2022 Martin Pool
+
+//! A short Markdown `summary.md`, built from `outcomes.json`, to paste into pull
+//! requests.
+
+use std::fmt::Write;
+
+use crate::outcome::MutantStatus;
+use crate::report::LabOutcomeRecord;
+
+/// The number of missed mutants listed in the summary, if no other limit is configured.
+pub const DEFAULT_MAX_MISSED_IN_SUMMARY: usize = 20;
+
+/// Return a Markdown summary of the run, listing at most `max_missed` missed mutants.
+pub fn markdown_summary(record: &LabOutcomeRecord, max_missed: usize) -> String {
+    let totals = record.totals();
+    let mut md = String::from("## cargo-mutants results\n\n");
+    if record.check_only {
+        // There are only viable and unviable mutants, and it'd be misleading to say that
+        // none were caught.
+        md.push_str("| Total | Viable | Unviable | Timeouts |\n");
+        md.push_str("| ----: | -----: | -------: | -------: |\n");
+        writeln!(
+            md,
+            "| {} | {} | {} | {} |",
+            totals.total, totals.untested, totals.unviable, totals.timeout
+        )
+        .unwrap();
+        md.push_str("\nThe mutants were only checked, with `--check`, and not tested.\n");
+    } else {
+        md.push_str("| Total | Caught | Missed | Unviable | Timeouts |\n");
+        md.push_str("| ----: | -----: | -----: | -------: | -------: |\n");
+        writeln!(
+            md,
+            "| {} | {} | {} | {} | {} |",
+            totals.total, totals.caught, totals.missed, totals.unviable, totals.timeout
+        )
+        .unwrap();
+    }
+    if record.cached_caught > 0 {
+        writeln!(
+            md,
+            "\n{} more mutants were caught (cached) in a previous run, and not retested.",
+            record.cached_caught
+        )
+        .unwrap();
+    }
+    if let Some(score) = totals.score() {
+        writeln!(
+            md,
+            "\nMutation score: {:.1}% ({} of {} tested mutants caught)",
+            score,
+            totals.caught,
+            totals.caught + totals.missed
+        )
+        .unwrap();
+    }
+    let missed: Vec<_> = record
+        .mutants()
+        .filter(|(_, status, _)| *status == MutantStatus::Missed)
+        .map(|(mutation, _, _)| mutation)
+        .collect();
+    if !missed.is_empty() {
+        md.push_str("\n### Missed mutants\n\n");
+        for mutation in missed.iter().take(max_missed) {
+            writeln!(
+                md,
+                "- {}:{} — {}",
+                mutation.file,
+                mutation.span.start.line,
+                code_span(&mutation.change)
+            )
+            .unwrap();
+        }
+        if missed.len() > max_missed {
+            writeln!(md, "- and {} more", missed.len() - max_missed).unwrap();
+        }
+    }
+    md
+}
+
+/// Format text as inline code, using a longer fence if it contains backticks.
+fn code_span(s: &str) -> String {
+    if s.contains('`') {
+        format!("`` {} ``", s)
+    } else {
+        format!("`{}`", s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn record_with_missed(n: usize) -> LabOutcomeRecord {
+        let mut outcomes = vec![r#"{ "scenario": { "Mutant": { "mutation": {
+                "file": "src/lib.rs",
+                "span": { "start": { "line": 2, "column": 1 }, "end": { "line": 4, "column": 2 } },
+                "function": "f", "replacement": "0",
+                "change": "replace f with 0",
+                "description": "replace f with 0 in src/lib.rs:2"
+            } } },
+            "phase_results": [
+                { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Failure" }
+            ] }"#
+            .to_owned()];
+        for i in 0..n {
+            outcomes.push(format!(
+                r#"{{ "scenario": {{ "Mutant": {{ "mutation": {{
+                    "file": "src/lib.rs",
+                    "span": {{ "start": {{ "line": {line}, "column": 5 }}, "end": {{ "line": {line}, "column": 6 }} }},
+                    "function": "g", "replacement": "true",
+                    "change": "swap branches of `if a` in g",
+                    "description": "swap branches of `if a` in g at src/lib.rs:{line}:5"
+                }} }} }},
+                "phase_results": [
+                    {{ "phase": "Test", "duration": {{ "secs": 1, "nanos": 0 }}, "cargo_result": "Success" }}
+                ] }}"#,
+                line = 10 + i
+            ));
+        }
+        serde_json::from_str(&format!(r#"{{ "outcomes": [{}] }}"#, outcomes.join(", "))).unwrap()
+    }
+
+    #[test]
+    fn summary_lists_missed_mutants() {
+        assert_eq!(
+            markdown_summary(&record_with_missed(2), 5),
+            "## cargo-mutants results
+
+| Total | Caught | Missed | Unviable | Timeouts |
+| ----: | -----: | -----: | -------: | -------: |
+| 3 | 1 | 2 | 0 | 0 |
+
+Mutation score: 33.3% (1 of 3 tested mutants caught)
+
+### Missed mutants
+
+- src/lib.rs:10 — `` swap branches of `if a` in g ``
+- src/lib.rs:11 — `` swap branches of `if a` in g ``
+"
+        );
+    }
+
+    #[test]
+    fn summary_separates_cached_mutants() {
+        let mut record = record_with_missed(0);
+        record.cached_caught = 4;
+        let md = markdown_summary(&record, 5);
+        assert!(md.contains(
+            "| 1 | 1 | 0 | 0 | 0 |\n\n4 more mutants were caught (cached) in a previous run, and not retested.\n\nMutation score: 100.0% (1 of 1 tested mutants caught)\n"
+        ));
+    }
+
+    #[test]
+    fn summary_is_limited() {
+        let md = markdown_summary(&record_with_missed(5), 2);
+        assert_eq!(md.matches("swap branches").count(), 2);
+        assert!(
+            md.ends_with("- src/lib.rs:11 — `` swap branches of `if a` in g ``\n- and 3 more\n")
+        );
+    }
+
+    #[test]
+    fn check_only_summary_counts_viable_mutants() {
+        let record: LabOutcomeRecord = serde_json::from_str(
+            r#"{ "check_only": true, "outcomes": [ { "scenario": { "Mutant": { "mutation": {
+                "file": "src/lib.rs",
+                "span": { "start": { "line": 2, "column": 1 }, "end": { "line": 4, "column": 2 } },
+                "function": "f", "replacement": "0",
+                "change": "replace f with 0",
+                "description": "replace f with 0 in src/lib.rs:2"
+            } } },
+            "phase_results": [
+                { "phase": "Check", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+            ] } ] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            markdown_summary(&record, 5),
+            "## cargo-mutants results
+
+| Total | Viable | Unviable | Timeouts |
+| ----: | -----: | -------: | -------: |
+| 1 | 1 | 0 | 0 |
+
+The mutants were only checked, with `--check`, and not tested.
+"
+        );
+    }
+
+    #[test]
+    fn no_missed_mutants() {
+        let md = markdown_summary(&record_with_missed(0), 2);
+        assert!(md.contains("Mutation score: 100.0% (1 of 1 tested mutants caught)"));
+        assert!(!md.contains("Missed mutants"));
+    }
+}
@@ -9,50 +9,40 @@ use std::path::Path;
 use anyhow::Context;
 use anyhow::Result;
 use serde::ser::{SerializeStruct, Serializer};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use similar::TextDiff;
 
 use crate::source::SourceFile;
-use crate::textedit::{replace_region, Span};
+use crate::textedit::{extract_region, replace_region, Span};
 
 /// A comment marker inserted next to changes, so they can be easily found.
 const MUTATION_MARKER_COMMENT: &str = "/* ~ changed by cargo-mutants ~ */";
 
-/// A type of mutation operation that could be applied to a source file.
-#[derive(Debug, Eq, Clone, PartialEq, Serialize)]
-pub enum MutationOp {
-    /// Return [Default::default].
-    Default,
-    /// Replace the function body with nothing (for functions that return `()`.
-    ///
-    /// We use `()` rather than just nothing because it's clearer in messages about the mutation.
-    Unit,
-    /// Return true.
-    True,
-    /// Return false.
-    False,
-    /// Return empty string.
-    EmptyString,
-    /// Return `"xyzzy"`.
-    Xyzzy,
-    /// Return `Ok(Default::default())`
-    OkDefault,
-}
-
-impl MutationOp {
-    /// Return the text that replaces the body of the mutated span, without the marker comment.
-    fn replacement(&self) -> &'static str {
-        use MutationOp::*;
-        match self {
-            Default => "Default::default()",
-            Unit => "()",
-            True => "true",
-            False => "false",
-            EmptyString => "\"\".into()",
-            Xyzzy => "\"xyzzy\".into()",
-            OkDefault => "Ok(Default::default())",
-        }
-    }
+/// The kind of change made by a mutation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Genre {
+    /// Replace the body of a function with a value of its return type.
+    FnValue,
+    /// Replace a binary operator with a different one, like `+` with `-`.
+    BinaryOperator,
+    /// Delete a unary operator, like `!` or `-`.
+    UnaryOperator,
+    /// Replace the condition of an `if` with `true` or `false`.
+    IfCondition,
+    /// Swap the `if` and `else` blocks of an `if` expression.
+    SwapBranches,
+    /// Delete one arm of a `match`.
+    MatchArm,
+    /// Change a range between half-open `a..b` and closed `a..=b`.
+    Range,
+    /// Call a method with the opposite meaning, like `max` instead of `min`.
+    MethodName,
+    /// Replace the body of a closure passed to an iterator adapter, like `filter`.
+    Closure,
+    /// Delete a statement that calls a function or method.
+    Statement,
+    /// Replace a literal with a different value, like a shift amount `8` with `9`.
+    Literal,
 }
 
 /// A mutation that could possibly be applied to source code.
@@ -60,11 +50,14 @@ impl MutationOp {
 /// The Mutation knows:
 /// * which file to modify,
 /// * which function and span in that file,
-/// * and what type of mutation to apply.
+/// * and what text to put in its place.
 #[derive(Clone, Eq, PartialEq)]
 pub struct Mutation {
     pub source_file: SourceFile,
 
+    /// What kind of change this is.
+    genre: Genre,
+
     /// The function that's being mutated.
     function_name: String,
 
@@ -74,41 +67,93 @@ pub struct Mutation {
     /// The mutated textual region.
     span: Span,
 
-    /// The type of change to apply.
-    pub op: MutationOp,
+    /// The body of the function containing the mutation.
+    function_span: Span,
+
+    /// The original text of the region, for mutations within a function body.
+    ///
+    /// For deleted match arms, this is just the arm's pattern, for swapped branches it's
+    /// the condition of the `if`, and for deleted statements it's the name of the function
+    /// or method called.
+    original: String,
+
+    /// The replacement text, as Rust source, without the marker comment.
+    replacement: String,
 }
 
 impl Mutation {
+    /// Make a mutation that replaces the whole body of a function.
     pub fn new(
         source_file: SourceFile,
-        op: MutationOp,
+        replacement: String,
         function_name: String,
         return_type: String,
         span: Span,
     ) -> Mutation {
         Mutation {
             source_file,
-            op,
+            genre: Genre::FnValue,
+            replacement,
             function_name,
             return_type,
             span,
+            function_span: span,
+            original: String::new(),
+        }
+    }
+
+    /// Make a mutation that replaces the exact text `original` at `span`, within the
+    /// body of a function at `function_span`.
+    ///
+    /// An empty `replacement` deletes the original text.
+    pub fn new_expression(
+        source_file: SourceFile,
+        genre: Genre,
+        function_name: String,
+        original: String,
+        replacement: String,
+        span: Span,
+        function_span: Span,
+    ) -> Mutation {
+        Mutation {
+            source_file,
+            genre,
+            replacement,
+            function_name,
+            return_type: String::new(),
+            span,
+            function_span,
+            original,
         }
     }
 
     /// Return text of the whole file with the mutation applied.
     pub fn mutated_code(&self) -> String {
+        let replacement = match self.genre {
+            Genre::FnValue => {
+                format!("{{\n{} {}\n}}\n", self.replacement, MUTATION_MARKER_COMMENT)
+            }
+            _ if self.replacement.is_empty() => format!("{} ", MUTATION_MARKER_COMMENT),
+            _ => format!("{} {}", self.replacement, MUTATION_MARKER_COMMENT),
+        };
         replace_region(
             &self.source_file.code,
             &self.span.start,
             &self.span.end,
-            &format!(
-                "{{\n{} {}\n}}\n",
-                self.op.replacement(),
-                MUTATION_MARKER_COMMENT
-            ),
+            &replacement,
         )
     }
 
+    pub fn genre(&self) -> Genre {
+        self.genre
+    }
+
+    /// Return the original text replaced by a mutation within a function body, or an empty
+    /// string for mutations of the whole function.
+    pub fn original_text(&self) -> &str {
+        &self.original
+    }
+
     /// Return the original code for the entire file affected by this mutation.
     pub fn original_code(&self) -> &str {
         &self.source_file.code
@@ -118,27 +163,99 @@ impl Mutation {
         &self.return_type
     }
 
-    /// Return a "file:line" description of the location of this mutation.
-    pub fn describe_location(&self) -> String {
-        format!(
-            "{}:{}",
-            self.source_file.tree_relative_slashes(),
-            self.span.start.line,
+    /// Return the span of the code that's replaced.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Return the span of the body of the function containing the mutation.
+    pub fn function_span(&self) -> Span {
+        self.function_span
+    }
+
+    /// Return the original text of the body of the function containing the mutation.
+    pub fn function_text(&self) -> String {
+        extract_region(
+            &self.source_file.code,
+            &self.function_span.start,
+            &self.function_span.end,
         )
     }
 
+    /// Return a "file:line" description of the location of this mutation, or
+    /// "file:line:column" for mutations within a function body.
+    pub fn describe_location(&self) -> String {
+        match self.genre {
+            Genre::FnValue => format!(
+                "{}:{}",
+                self.source_file.tree_relative_slashes(),
+                self.span.start.line,
+            ),
+            _ => format!(
+                "{}:{}:{}",
+                self.source_file.tree_relative_slashes(),
+                self.span.start.line,
+                self.span.start.column,
+            ),
+        }
+    }
+
+    /// Describe the mutation and its location as `--list` prints it, including the return
+    /// type of replaced functions. This is what `--re` is matched against.
+    pub fn name(&self) -> String {
+        match self.genre {
+            Genre::FnValue if !self.return_type.is_empty() => format!(
+                "{}: replace {} {} with {}",
+                self.describe_location(),
+                self.function_name(),
+                self.return_type,
+                self.replacement
+            ),
+            _ => format!("{}: {}", self.describe_location(), self.describe_change()),
+        }
+    }
+
     /// Describe the mutation briefly, not including the location.
     pub fn describe_change(&self) -> String {
-        format!(
-            "replace {} with {}",
-            self.function_name(),
-            self.op.replacement()
-        )
+        match self.genre {
+            Genre::FnValue => {
+                format!("replace {} with {}", self.function_name(), self.replacement)
+            }
+            Genre::SwapBranches => format!(
+                "swap branches of `if {}` in {}",
+                self.original,
+                self.function_name()
+            ),
+            Genre::MatchArm => format!(
+                "delete match arm `{} => ...` in {}",
+                self.original,
+                self.function_name()
+            ),
+            Genre::Closure => format!(
+                "replace closure in {} with {}",
+                self.function_name(),
+                self.replacement
+            ),
+            Genre::Statement => format!(
+                "delete call to `{}` in {}",
+                self.original,
+                self.function_name()
+            ),
+            _ if self.replacement.is_empty() => {
+                format!("delete {} in {}", self.original, self.function_name())
+            }
+            _ => format!(
+                "replace {} with {} in {}",
+                self.original,
+                self.replacement,
+                self.function_name()
+            ),
+        }
     }
 
     /// Return the text inserted for this mutation.
-    pub fn replacement_text(&self) -> &'static str {
-        self.op.replacement()
+    pub fn replacement_text(&self) -> &str {
+        &self.replacement
     }
 
     /// Return the name of the function to be mutated.
@@ -150,13 +267,15 @@ impl Mutation {
     }
 
     /// Return a unified diff for the mutation.
+    ///
+    /// The headers name the file as `a/...` and `b/...`, relative to the tree, so the
+    /// diff can be applied with `git apply` or `patch -p1`.
     pub fn diff(&self) -> String {
-        let old_label = self.source_file.tree_relative_slashes();
-        let new_label = self.describe_change();
+        let path = self.source_file.tree_relative_slashes();
         TextDiff::from_lines(self.original_code(), &self.mutated_code())
             .unified_diff()
             .context_radius(8)
-            .header(&old_label, &new_label)
+            .header(&format!("a/{}", path), &format!("b/{}", path))
             .to_string()
     }
 
@@ -194,7 +313,8 @@ impl Mutation {
 impl fmt::Debug for Mutation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Mutation")
-            .field("op", &self.op)
+            .field("genre", &self.genre)
+            .field("replacement", &self.replacement)
             .field("function_name", &self.function_name())
             .field("return_type", &self.return_type)
             // more concise display of spans
@@ -208,8 +328,12 @@ impl fmt::Display for Mutation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{} in {}",
+            "{} {} {}",
             self.describe_change(),
+            match self.genre {
+                Genre::FnValue => "in",
+                _ => "at",
+            },
             self.describe_location(),
         )
     }
@@ -221,12 +345,18 @@ impl Serialize for Mutation {
         S: Serializer,
     {
         // custom serialize to omit inessential info
-        let mut ss = serializer.serialize_struct("Mutation", 4)?;
+        let mut ss = serializer.serialize_struct("Mutation", 11)?;
+        ss.serialize_field("genre", &self.genre)?;
         ss.serialize_field("file", &self.source_file.tree_relative_slashes())?;
         ss.serialize_field("line", &self.span.start.line)?;
+        ss.serialize_field("column", &self.span.start.column)?;
+        ss.serialize_field("span", &self.span)?;
         ss.serialize_field("function", &self.function_name)?;
         ss.serialize_field("return_type", &self.return_type)?;
-        ss.serialize_field("replacement", self.op.replacement())?;
+        ss.serialize_field("original", &self.original)?;
+        ss.serialize_field("replacement", &self.replacement)?;
+        ss.serialize_field("change", &self.describe_change())?;
+        ss.serialize_field("description", &self.to_string())?;
         ss.end()
     }
 }
@@ -239,6 +369,7 @@ mod test {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::*;
 
     #[test]
     fn discover_mutations() {
@@ -247,18 +378,40 @@ mod test {
             Path::new("src/bin/main.rs"),
         )
         .unwrap();
-        let muts = source_file.mutations().unwrap();
-        assert_eq!(muts.len(), 2);
+        let muts = source_file.discover(&Options::default()).unwrap().mutations;
+        assert_eq!(muts.len(), 3);
         assert_eq!(
             format!("{:?}", muts[0]),
-            r#"Mutation { op: Unit, function_name: "main", return_type: "", start: (1, 11), end: (5, 2) }"#
+            r#"Mutation { genre: FnValue, replacement: "()", function_name: "main", return_type: "", start: (1, 11), end: (5, 2) }"#
         );
         assert_eq!(
             format!("{:?}", muts[1]),
-            r#"Mutation { op: Default, function_name: "factorial", return_type: "-> u32", start: (7, 29), end: (13, 2) }"#
+            r#"Mutation { genre: FnValue, replacement: "0", function_name: "factorial", return_type: "-> u32", start: (7, 29), end: (13, 2) }"#
+        );
+        assert_eq!(
+            format!("{:?}", muts[2]),
+            r#"Mutation { genre: FnValue, replacement: "1", function_name: "factorial", return_type: "-> u32", start: (7, 29), end: (13, 2) }"#
         );
     }
 
+    #[test]
+    fn diff_shows_float_replacement() {
+        let source_file = SourceFile::new(
+            Path::new("testdata/tree/well_tested"),
+            Path::new("src/simple_fns.rs"),
+        )
+        .unwrap();
+        let muts = source_file.discover(&Options::default()).unwrap().mutations;
+        let mutation = muts
+            .iter()
+            .find(|m| m.function_name() == "mean" && m.replacement_text() == "1.0")
+            .unwrap();
+        assert_eq!(mutation.describe_change(), "replace mean with 1.0");
+        let diff = mutation.diff();
+        assert!(diff.starts_with("--- a/src/simple_fns.rs\n+++ b/src/simple_fns.rs\n@@ -"));
+        assert!(diff.contains("\n-    (a + b) / 2.0\n+1.0 /* ~ changed by cargo-mutants ~ */\n"));
+    }
+
     #[test]
     fn filter_by_attributes() {
         let source_file = SourceFile::new(
@@ -266,7 +419,7 @@ mod test {
             Path::new("src/lib.rs"),
         )
         .unwrap();
-        let muts = source_file.mutations().unwrap();
+        let muts = source_file.discover(&Options::default()).unwrap().mutations;
         let descriptions = muts.iter().map(Mutation::describe_change).collect_vec();
         insta::assert_snapshot!(
             descriptions.join("\n"),
@@ -281,8 +434,8 @@ mod test {
             Path::new("src/bin/main.rs"),
         )
         .unwrap();
-        let muts = source_file.mutations().unwrap();
-        assert_eq!(muts.len(), 2);
+        let muts = source_file.discover(&Options::default()).unwrap().mutations;
+        assert_eq!(muts.len(), 3);
 
         let mut mutated_code = muts[0].mutated_code();
         assert_eq!(muts[0].function_name(), "main");
@@ -321,7 +474,7 @@ fn test_factorial() {
 }
 
 fn factorial(n: u32) -> u32 {
-Default::default() /* ~ changed by cargo-mutants ~ */
+0 /* ~ changed by cargo-mutants ~ */
 }
 
 #[test]
@@ -0,0 +1,355 @@
+// Copyright 2022 Martin Pool
+
+//! Mutations that replace one operator with another, within a function body.
+
+/// Pairs of methods with the same signature but opposite meanings, which are swapped.
+pub const METHOD_NAME_PAIRS: &[(&str, &str)] = &[
+    ("min", "max"),
+    ("min_by", "max_by"),
+    ("min_by_key", "max_by_key"),
+    ("first", "last"),
+    ("first_mut", "last_mut"),
+    ("starts_with", "ends_with"),
+    ("trim_start", "trim_end"),
+];
+
+/// Iterator and collection methods taking a closure that returns `bool`, whose body can
+/// be replaced by `true` or `false`.
+pub const BOOL_CLOSURE_METHODS: &[&str] = &[
+    "all",
+    "any",
+    "filter",
+    "find",
+    "position",
+    "retain",
+    "skip_while",
+    "take_while",
+];
+
+/// Return the operators that could replace a binary operator, or a compound assignment
+/// operator like `+=`, as Rust source.
+///
+/// An empty list means the operator is not mutated.
+pub fn binary_operator_replacements(op: &syn::BinOp) -> &'static [&'static str] {
+    use syn::BinOp::*;
+    match op {
+        Add(_) => &["-"],
+        Sub(_) => &["+"],
+        Mul(_) => &["/"],
+        Div(_) => &["*"],
+        Rem(_) => &["/"],
+        Lt(_) => &["<="],
+        Le(_) => &["<"],
+        Gt(_) => &[">="],
+        Ge(_) => &[">"],
+        Eq(_) => &["!="],
+        Ne(_) => &["=="],
+        BitAnd(_) => &["|"],
+        BitOr(_) => &["&"],
+        BitXor(_) => &["|"],
+        Shl(_) => &[">>"],
+        Shr(_) => &["<<"],
+        And(_) => &["||"],
+        Or(_) => &["&&"],
+        AddEq(_) => &["-="],
+        SubEq(_) => &["+="],
+        MulEq(_) => &["/="],
+        DivEq(_) => &["*="],
+        BitOrEq(_) => &["&="],
+        BitAndEq(_) => &["|="],
+        _ => &[],
+    }
+}
+
+/// Return the names of methods that could replace a call to a method with this name.
+pub fn method_name_replacements(name: &str) -> Vec<&'static str> {
+    METHOD_NAME_PAIRS
+        .iter()
+        .filter_map(|&(a, b)| {
+            if a == name {
+                Some(b)
+            } else if b == name {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Return replacements for the condition of an `if`, as Rust source.
+///
+/// `if let` isn't mutated, and there's no `false` mutant for an `if` with an empty body and
+/// no `else`, because it would do nothing.
+pub fn if_condition_replacements(expr_if: &syn::ExprIf) -> &'static [&'static str] {
+    if matches!(*expr_if.cond, syn::Expr::Let(_)) {
+        &[]
+    } else if expr_if.else_branch.is_none() && expr_if.then_branch.stmts.is_empty() {
+        &["true"]
+    } else {
+        &["true", "false"]
+    }
+}
+
+/// Return the replacement for the limits of a range like `a..b`, as Rust source.
+///
+/// Ranges with no end, like `a..`, aren't mutated.
+pub fn range_limits_replacements(range: &syn::ExprRange) -> &'static [&'static str] {
+    match (&range.limits, &range.to) {
+        (_, None) => &[],
+        (syn::RangeLimits::HalfOpen(_), Some(_)) => &["..="],
+        (syn::RangeLimits::Closed(_), Some(_)) => &[".."],
+    }
+}
+
+/// The maximum number of literal mutants generated in each function, if no other limit is
+/// configured.
+pub const DEFAULT_MAX_LITERAL_MUTANTS: usize = 10;
+
+/// Return replacements for an integer literal, like a shift amount: one less and one
+/// more than the original, keeping any type suffix.
+pub fn int_literal_replacements(lit: &syn::LitInt) -> Vec<String> {
+    let value: u128 = match lit.base10_parse() {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+    let mut values = Vec::new();
+    if value > 0 {
+        values.push(value - 1);
+    }
+    values.push(value + 1);
+    values
+        .into_iter()
+        .map(|v| format!("{}{}", v, lit.suffix()))
+        .collect()
+}
+
+/// Return replacements for a literal initializing a field in a hand-written `Default`
+/// impl: integers change by one, and strings are emptied.
+///
+/// Bools needn't be handled here because they're flipped everywhere by
+/// [bool_literal_replacement].
+pub fn field_literal_replacements(lit: &syn::Lit) -> Vec<String> {
+    match lit {
+        syn::Lit::Int(lit_int) => int_literal_replacements(lit_int),
+        syn::Lit::Str(lit_str) if !lit_str.value().is_empty() => vec![r#""""#.to_owned()],
+        _ => Vec::new(),
+    }
+}
+
+/// Return a replacement for a string literal: non-empty strings are emptied, and empty
+/// strings are replaced by `"xyzzy"`.
+pub fn string_literal_replacement(lit: &syn::LitStr) -> &'static str {
+    if lit.value().is_empty() {
+        r#""xyzzy""#
+    } else {
+        r#""""#
+    }
+}
+
+/// Return the opposite of a bool literal.
+pub fn bool_literal_replacement(lit: &syn::LitBool) -> &'static str {
+    if lit.value {
+        "false"
+    } else {
+        "true"
+    }
+}
+
+/// True if a `!` applied to this expression should be deleted.
+///
+/// `!` can also be bitwise negation of an integer, which can't be distinguished by syntax
+/// alone, so only negations of calls and paths in `if` and `while` conditions, which are
+/// almost certainly bools, are deleted.
+pub fn should_delete_not(operand: &syn::Expr) -> bool {
+    matches!(
+        operand,
+        syn::Expr::Call(_) | syn::Expr::MethodCall(_) | syn::Expr::Path(_)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic_operators_are_swapped() {
+        for (op, expected) in [
+            (syn::parse_quote! { + }, "-"),
+            (syn::parse_quote! { - }, "+"),
+            (syn::parse_quote! { * }, "/"),
+            (syn::parse_quote! { / }, "*"),
+            (syn::parse_quote! { % }, "/"),
+        ] {
+            assert_eq!(binary_operator_replacements(&op), &[expected]);
+        }
+    }
+
+    #[test]
+    fn comparison_operators_move_the_boundary() {
+        for (op, expected) in [
+            (syn::parse_quote! { < }, "<="),
+            (syn::parse_quote! { <= }, "<"),
+            (syn::parse_quote! { > }, ">="),
+            (syn::parse_quote! { >= }, ">"),
+            (syn::parse_quote! { == }, "!="),
+            (syn::parse_quote! { != }, "=="),
+        ] {
+            assert_eq!(binary_operator_replacements(&op), &[expected]);
+        }
+    }
+
+    #[test]
+    fn boolean_operators_are_swapped() {
+        let op: syn::BinOp = syn::parse_quote! { && };
+        assert_eq!(binary_operator_replacements(&op), &["||"]);
+        let op: syn::BinOp = syn::parse_quote! { || };
+        assert_eq!(binary_operator_replacements(&op), &["&&"]);
+    }
+
+    #[test]
+    fn bitwise_operators_are_swapped() {
+        for (op, expected) in [
+            (syn::parse_quote! { & }, "|"),
+            (syn::parse_quote! { | }, "&"),
+            (syn::parse_quote! { ^ }, "|"),
+        ] {
+            assert_eq!(binary_operator_replacements(&op), &[expected]);
+        }
+    }
+
+    #[test]
+    fn shift_operators_are_swapped() {
+        let op: syn::BinOp = syn::parse_quote! { << };
+        assert_eq!(binary_operator_replacements(&op), &[">>"]);
+        let op: syn::BinOp = syn::parse_quote! { >> };
+        assert_eq!(binary_operator_replacements(&op), &["<<"]);
+    }
+
+    #[test]
+    fn shift_amounts_change_by_one() {
+        let lit: syn::LitInt = syn::parse_quote! { 8 };
+        assert_eq!(int_literal_replacements(&lit), ["7", "9"]);
+        let lit: syn::LitInt = syn::parse_quote! { 0x10u32 };
+        assert_eq!(int_literal_replacements(&lit), ["15u32", "17u32"]);
+        let lit: syn::LitInt = syn::parse_quote! { 0 };
+        assert_eq!(int_literal_replacements(&lit), ["1"]);
+    }
+
+    #[test]
+    fn field_literals_are_bumped_or_emptied() {
+        let lit: syn::Lit = syn::parse_quote! { 3 };
+        assert_eq!(field_literal_replacements(&lit), ["2", "4"]);
+        let lit: syn::Lit = syn::parse_quote! { true };
+        assert!(field_literal_replacements(&lit).is_empty());
+        let lit: syn::Lit = syn::parse_quote! { "main" };
+        assert_eq!(field_literal_replacements(&lit), [r#""""#]);
+        let lit: syn::Lit = syn::parse_quote! { "" };
+        assert!(field_literal_replacements(&lit).is_empty());
+        let lit: syn::Lit = syn::parse_quote! { 1.5 };
+        assert!(field_literal_replacements(&lit).is_empty());
+    }
+
+    #[test]
+    fn string_literals_are_emptied_or_filled() {
+        assert_eq!(
+            string_literal_replacement(&syn::parse_quote! { "host" }),
+            r#""""#
+        );
+        assert_eq!(
+            string_literal_replacement(&syn::parse_quote! { r"" }),
+            r#""xyzzy""#
+        );
+    }
+
+    #[test]
+    fn bool_literals_are_flipped() {
+        assert_eq!(
+            bool_literal_replacement(&syn::parse_quote! { true }),
+            "false"
+        );
+        assert_eq!(
+            bool_literal_replacement(&syn::parse_quote! { false }),
+            "true"
+        );
+    }
+
+    #[test]
+    fn method_names_are_swapped_in_either_direction() {
+        assert_eq!(method_name_replacements("max"), ["min"]);
+        assert_eq!(method_name_replacements("first"), ["last"]);
+        assert_eq!(method_name_replacements("min_by_key"), ["max_by_key"]);
+        assert!(method_name_replacements("push").is_empty());
+    }
+
+    #[test]
+    fn if_conditions_are_replaced() {
+        for (expr_if, expected) in [
+            (
+                syn::parse_quote! { if a < b { a } else { b } },
+                &["true", "false"][..],
+            ),
+            (
+                syn::parse_quote! { if done() { stop() } },
+                &["true", "false"],
+            ),
+            (syn::parse_quote! { if done() {} }, &["true"]),
+            (
+                syn::parse_quote! { if let Some(a) = b { a } else { 0 } },
+                &[],
+            ),
+        ] {
+            assert_eq!(if_condition_replacements(&expr_if), expected);
+        }
+    }
+
+    #[test]
+    fn range_limits_are_swapped() {
+        for (range, expected) in [
+            (syn::parse_quote! { 0..n }, &["..="][..]),
+            (syn::parse_quote! { 1..=6 }, &[".."][..]),
+            (syn::parse_quote! { ..len }, &["..="][..]),
+            (syn::parse_quote! { start.. }, &[][..]),
+        ] {
+            assert_eq!(range_limits_replacements(&range), expected);
+        }
+    }
+
+    #[test]
+    fn compound_assignment_operators_are_swapped() {
+        for (op, expected) in [
+            (syn::parse_quote! { += }, "-="),
+            (syn::parse_quote! { -= }, "+="),
+            (syn::parse_quote! { *= }, "/="),
+            (syn::parse_quote! { /= }, "*="),
+            (syn::parse_quote! { |= }, "&="),
+            (syn::parse_quote! { &= }, "|="),
+        ] {
+            assert_eq!(binary_operator_replacements(&op), &[expected]);
+        }
+    }
+
+    #[test]
+    fn other_operators_are_not_mutated() {
+        let op: syn::BinOp = syn::parse_quote! { <<= };
+        assert!(binary_operator_replacements(&op).is_empty());
+    }
+
+    #[test]
+    fn delete_not_of_calls_and_paths() {
+        for expr in [
+            syn::parse_quote! { done },
+            syn::parse_quote! { is_done() },
+            syn::parse_quote! { self.queue.is_empty() },
+        ] {
+            assert!(should_delete_not(&expr));
+        }
+        for expr in [
+            syn::parse_quote! { (a & b) },
+            syn::parse_quote! { 0u8 },
+            syn::parse_quote! { x.flags },
+        ] {
+            assert!(!should_delete_not(&expr));
+        }
+    }
+}
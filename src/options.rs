@@ -2,8 +2,20 @@
 
 //! Global in-process options for experimenting on mutants.
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use anyhow::{bail, Context};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+
+use crate::config::CONFIG_PATH;
+use crate::console::{Colors, OutputMode, DEFAULT_SLOWEST};
+use crate::features::Features;
+use crate::in_diff::ChangedLines;
+use crate::lab::{DEFAULT_MINIMUM_TEST_TIMEOUT, DEFAULT_TIMEOUT_MULTIPLIER};
+use crate::visit::{OnlyPub, DEFAULT_SKIP_CALLS};
 use crate::*;
 
 /// Options for running experiments.
@@ -14,6 +26,18 @@ pub struct Options {
 
     test_timeout: Duration,
 
+    /// The shortest test timeout that's set automatically from the baseline.
+    pub minimum_test_timeout: Duration,
+
+    /// The baseline test time is multiplied by this to set the test timeout.
+    pub timeout_multiplier: f64,
+
+    build_timeout: Duration,
+
+    /// If set, the baseline check or build time is multiplied by this to set the build
+    /// timeout.
+    pub build_timeout_multiplier: Option<f64>,
+
     pub print_caught: bool,
     pub print_unviable: bool,
 
@@ -22,24 +46,171 @@ pub struct Options {
     /// Show logs even from mutants that were caught, or source/unmutated builds.
     pub show_all_logs: bool,
 
-    /// Test mutants in random order.
-    ///
-    /// (Mostly for development so that we don't always exercise the first few mutants.)
+    /// How results are printed on stdout.
+    pub output_mode: OutputMode,
+
+    /// Whether to color output.
+    pub colors: Colors,
+
+    /// The number of slowest mutants to list at the end of the run.
+    pub slowest: usize,
+
+    /// The number of mutants to test at once, each in its own scratch tree.
+    pub jobs: usize,
+
+    /// Test only the mutants in this shard.
+    pub shard: Option<Shard>,
+
+    /// Test mutants in random order, so that a run that's stopped early isn't biased
+    /// towards the first files.
     pub shuffle: bool,
 
-    /// Additional arguments to `cargo test`.
+    /// The seed for shuffling, or None to choose one at random.
+    pub seed: Option<u64>,
+
+    /// Print annotations on missed mutants in this format.
+    pub annotations: Annotations,
+
+    /// Whether missed mutants are annotated as warnings or errors.
+    pub annotation_level: AnnotationLevel,
+
+    /// Colors for `badge.json`, with the minimum score for each, if not the defaults.
+    pub badge_colors: BTreeMap<String, f64>,
+
+    /// Additional arguments to every `cargo check`, `cargo build`, and `cargo test`.
+    pub additional_cargo_args: Vec<String>,
+
+    /// Additional arguments to `cargo test`, after [Options::additional_cargo_args].
     pub additional_cargo_test_args: Vec<String>,
 
+    /// Arguments passed through `cargo test` to the test binaries, after `--`.
+    pub test_args: Vec<String>,
+
     /// Copy the `/target/` directory from the source tree.
     pub copy_target: bool,
 
+    /// Whether to test the unmutated tree, or reuse or skip its baseline.
+    pub baseline: BaselineStrategy,
+
     /// Build the source directory before copying it.
     pub build_source: bool,
+
+    /// Print the diff of each missed mutant.
+    pub diff_missed: bool,
+
+    /// Write a `.patch` file for each mutant into this directory.
+    pub diff_dir: Option<PathBuf>,
+
+    /// Expressions to return inside `Err(...)` from functions returning `Result`.
+    pub error_values: Vec<String>,
+
+    /// The cargo features enabled for every cargo command.
+    pub features: Features,
+
+    /// The cargo profile for every cargo command, if not the default.
+    pub profile: Option<String>,
+
+    /// Exit with an error if no mutants are found or the baseline runs no tests.
+    pub fail_on_empty: bool,
+
+    /// Exit with an error if any mutants are missed.
+    pub fail_on_missed: bool,
+
+    /// Functions whose names match any of these regexps are not mutated.
+    pub exclude_functions: Vec<Regex>,
+
+    /// If not empty, only files whose tree-relative paths match one of these globs are
+    /// mutated.
+    pub examine_globs: Vec<GlobMatcher>,
+
+    /// Files whose tree-relative paths match any of these globs are not mutated.
+    pub exclude_globs: Vec<GlobMatcher>,
+
+    /// Like `examine_globs`, but from the config file: files must match both, if both
+    /// are given.
+    pub config_examine_globs: Vec<GlobMatcher>,
+
+    /// Like `exclude_globs`, but from the config file.
+    pub config_exclude_globs: Vec<GlobMatcher>,
+
+    /// If not empty, only functions whose names match one of these globs are mutated.
+    pub examine_functions: Vec<GlobMatcher>,
+
+    /// If not empty, only mutants whose names match one of these regexps are generated.
+    pub examine_names: Vec<Regex>,
+
+    /// Mutants whose names match any of these regexps are not generated.
+    pub exclude_names: Vec<Regex>,
+
+    /// If set, only functions that are public by this standard are mutated.
+    pub only_pub: Option<OnlyPub>,
+
+    /// If set, only mutants of lines added or changed by this diff are generated.
+    pub in_diff: Option<ChangedLines>,
+
+    /// Skip mutants that were caught in the previous run, according to its cache.
+    pub iterate: bool,
+
+    /// Keep a copy of the tree of each missed mutant.
+    pub keep_missed_dirs: bool,
+
+    /// Keep the scratch directory, and a copy of the tree of every mutant.
+    pub leak_dirs: bool,
+
+    /// Values to return from functions returning integers.
+    ///
+    /// `MAX` and `MIN` stand for the constants of the return type.
+    pub int_values: Vec<String>,
+
+    /// The maximum number of literal mutants to generate within each function, if not the
+    /// default.
+    pub max_literal_mutants: Option<usize>,
+
+    /// The maximum number of missed mutants to list in `summary.md`, if not the default.
+    pub max_missed_in_summary: Option<usize>,
+
+    /// The maximum number of mutants to generate for a function returning a tuple, if not
+    /// the default.
+    pub max_tuple_mutants: Option<usize>,
+
+    /// Mutate code that's only built in tests, under `#[cfg(test)]`.
+    pub mutate_cfg_test: bool,
+
+    /// Change integer literals within function bodies.
+    pub mutate_literals: bool,
+
+    /// Replace string literals within function bodies.
+    pub mutate_strings: bool,
+
+    /// Generate mutants within function bodies, such as changing operators or deleting
+    /// match arms.
+    pub mutate_operators: bool,
+
+    /// Packages in the workspace to mutate and test, passed to cargo as `-p`, or empty for
+    /// the default package.
+    pub packages: Vec<String>,
+
+    /// Mutate every package in the workspace.
+    pub workspace: bool,
+
+    /// Packages in the workspace that are not mutated or tested.
+    pub exclude_packages: Vec<String>,
+
+    /// Names of functions and methods whose calls should not be deleted, including
+    /// [crate::visit::DEFAULT_SKIP_CALLS] unless the config turns them off.
+    pub skip_calls: Vec<String>,
+
+    /// Expressions to return from functions returning the named types, used instead of
+    /// the built-in replacements.
+    pub type_replacements: BTreeMap<String, Vec<String>>,
+
+    /// Print more detail about what's done, such as which files are skipped.
+    pub verbose: bool,
 }
 
 impl Options {
     /// Return the maximum run time for `cargo test` commands.
-    ///commands
+    ///
     /// Build and check are not affected.
     pub fn test_timeout(&self) -> Duration {
         self.test_timeout
@@ -52,24 +223,290 @@ impl Options {
     pub fn set_test_timeout(&mut self, test_timeout: Duration) {
         self.test_timeout = test_timeout;
     }
+
+    /// Return the maximum run time for each `cargo check` or `cargo build` command.
+    pub fn build_timeout(&self) -> Duration {
+        self.build_timeout
+    }
+
+    pub fn has_build_timeout(&self) -> bool {
+        self.build_timeout < Duration::MAX
+    }
+
+    pub fn set_build_timeout(&mut self, build_timeout: Duration) {
+        self.build_timeout = build_timeout;
+    }
 }
 
-impl From<&Args> for Options {
-    fn from(args: &Args) -> Options {
-        Options {
+impl Options {
+    /// Build options by merging command-line args and the config file.
+    pub(crate) fn new(args: &Args, config: &Config) -> Result<Options> {
+        let exclude_functions = compile_regexes(&config.exclude_functions, "exclude_functions")?;
+        let examine_globs = compile_globs(&args.file, "--file")?;
+        let examine_functions = compile_globs(&args.function, "--function")?;
+        let exclude_globs = compile_globs(&args.exclude, "--exclude")?;
+        let config_examine_globs = compile_globs(&config.examine_globs, "examine_globs")?;
+        let config_exclude_globs = compile_globs(&config.exclude_globs, "exclude_globs")?;
+        let mut examine_names = compile_regexes(&args.re, "--re")?;
+        examine_names.extend(compile_regexes(&config.examine_re, "examine_re")?);
+        let mut error_values = check_expressions(&args.error, "--error")?;
+        error_values.extend(check_expressions(
+            &config.error_values,
+            &format!("error_values in {}", CONFIG_PATH),
+        )?);
+        let mut skip_calls: Vec<String> = if config.skip_calls_defaults == Some(false) {
+            Vec::new()
+        } else {
+            DEFAULT_SKIP_CALLS
+                .iter()
+                .map(|&name| name.to_owned())
+                .collect()
+        };
+        skip_calls.extend(config.skip_calls.iter().cloned());
+        skip_calls.extend(args.skip_calls.iter().cloned());
+        let mut exclude_names = compile_regexes(&args.exclude_re, "--exclude-re")?;
+        exclude_names.extend(compile_regexes(&config.exclude_re, "exclude_re")?);
+        if args.jobs == Some(0) {
+            bail!("--jobs must be at least 1");
+        }
+        let profile = match (&args.profile, args.release) {
+            (Some(_), true) => bail!("--release and --profile can't be used together"),
+            (Some(profile), false) => Some(profile.clone()),
+            (None, true) => Some("release".to_owned()),
+            (None, false) => None,
+        };
+        if args.workspace && !args.package.is_empty() {
+            bail!("--workspace and --package can't be used together");
+        }
+        if args.count && (args.list || args.list_files) {
+            bail!("--count can't be used with --list or --list-files");
+        }
+        // A timeout or multiplier from the command line replaces both from the config.
+        if config.timeout.is_some() && config.timeout_multiplier.is_some() {
+            bail!(
+                "timeout and timeout_multiplier can't be used together in {}",
+                CONFIG_PATH
+            );
+        }
+        if config.build_timeout.is_some() && config.build_timeout_multiplier.is_some() {
+            bail!(
+                "build_timeout and build_timeout_multiplier can't be used together in {}",
+                CONFIG_PATH
+            );
+        }
+        let test_timeout = match (args.timeout, args.timeout_multiplier) {
+            (Some(timeout), _) => Some(seconds(timeout, "--timeout")?),
+            (None, Some(_)) => None,
+            (None, None) => config
+                .timeout
+                .map(|timeout| seconds(timeout, "timeout"))
+                .transpose()?,
+        };
+        let minimum_test_timeout = match args.minimum_test_timeout {
+            Some(minimum) => seconds(minimum, "--minimum-test-timeout")?,
+            None => match config.minimum_test_timeout {
+                Some(minimum) => seconds(minimum, "minimum_test_timeout")?,
+                None => DEFAULT_MINIMUM_TEST_TIMEOUT,
+            },
+        };
+        if args.timeout.is_some() && args.timeout_multiplier.is_some() {
+            bail!("--timeout and --timeout-multiplier can't be used together");
+        }
+        let timeout_multiplier = match args.timeout_multiplier {
+            Some(multiplier) => multiplier_value(multiplier, "--timeout-multiplier")?,
+            None => match config.timeout_multiplier {
+                Some(multiplier) => multiplier_value(multiplier, "timeout_multiplier")?,
+                None => DEFAULT_TIMEOUT_MULTIPLIER,
+            },
+        };
+        if args.build_timeout.is_some() && args.build_timeout_multiplier.is_some() {
+            bail!("--build-timeout and --build-timeout-multiplier can't be used together");
+        }
+        let (build_timeout, build_timeout_multiplier) =
+            match (args.build_timeout, args.build_timeout_multiplier) {
+                (Some(timeout), _) => (Some(seconds(timeout, "--build-timeout")?), None),
+                (None, Some(multiplier)) => (
+                    None,
+                    Some(multiplier_value(multiplier, "--build-timeout-multiplier")?),
+                ),
+                (None, None) => (
+                    config
+                        .build_timeout
+                        .map(|timeout| seconds(timeout, "build_timeout"))
+                        .transpose()?,
+                    config
+                        .build_timeout_multiplier
+                        .map(|multiplier| multiplier_value(multiplier, "build_timeout_multiplier"))
+                        .transpose()?,
+                ),
+            };
+        let output_mode = output_mode(args)?;
+        // Arguments after `--` go to every cargo command, except those from a second `--`
+        // on, which `cargo test` passes through to the test binaries.
+        let (trailing_cargo_args, trailing_test_args) =
+            match args.cargo_args.iter().position(|arg| arg == "--") {
+                Some(i) => (&args.cargo_args[..i], &args.cargo_args[i + 1..]),
+                None => (args.cargo_args.as_slice(), &[][..]),
+            };
+        // Arguments from the config come first, so that the command line can add to them;
+        // likewise, config test args after `--` go to the test binaries.
+        let (config_cargo_test_args, config_test_args) = match config
+            .additional_cargo_test_args
+            .iter()
+            .position(|arg| arg == "--")
+        {
+            Some(i) => (
+                &config.additional_cargo_test_args[..i],
+                &config.additional_cargo_test_args[i + 1..],
+            ),
+            None => (config.additional_cargo_test_args.as_slice(), &[][..]),
+        };
+        let mut additional_cargo_args = config.additional_cargo_args.clone();
+        additional_cargo_args.extend_from_slice(&args.cargo_arg);
+        additional_cargo_args.extend_from_slice(trailing_cargo_args);
+        let mut test_args = config_test_args.to_vec();
+        test_args.extend_from_slice(&args.test_arg);
+        test_args.extend_from_slice(trailing_test_args);
+        Ok(Options {
+            baseline: args.baseline.unwrap_or_default(),
             build_source: !args.no_copy_target,
             check_only: args.check,
             copy_target: !args.no_copy_target,
-            print_caught: args.caught,
-            print_unviable: args.unviable,
-            shuffle: args.shuffle,
-            show_times: !args.no_times,
-            show_all_logs: args.all_logs,
-            test_timeout: args
-                .timeout
-                .map(Duration::from_secs_f64)
-                .unwrap_or(Duration::MAX),
-            additional_cargo_test_args: args.cargo_test_args.clone(),
-        }
+            print_caught: args.caught || config.print_caught == Some(true),
+            print_unviable: args.unviable || config.print_unviable == Some(true),
+            jobs: args.jobs.unwrap_or(1),
+            shard: args.shard,
+            shuffle: (args.shuffle || args.seed.is_some()) && !args.no_shuffle,
+            seed: args.seed,
+            slowest: args.slowest.unwrap_or(DEFAULT_SLOWEST),
+            show_times: !args.no_times && config.show_times != Some(false),
+            show_all_logs: args.all_logs || config.show_all_logs == Some(true),
+            output_mode,
+            colors: args.color.unwrap_or_default(),
+            test_timeout: test_timeout.unwrap_or(Duration::MAX),
+            minimum_test_timeout,
+            timeout_multiplier,
+            build_timeout: build_timeout.unwrap_or(Duration::MAX),
+            build_timeout_multiplier,
+            additional_cargo_args,
+            additional_cargo_test_args: config_cargo_test_args.to_vec(),
+            test_args,
+            // Annotations would mix with TAP or JSON, so they're only printed in those modes
+            // if asked for.
+            annotations: args.annotations.unwrap_or_else(|| {
+                if output_mode == OutputMode::Human {
+                    Annotations::from_env()
+                } else {
+                    Annotations::None
+                }
+            }),
+            annotation_level: args.annotation_level.unwrap_or_default(),
+            badge_colors: config.badge_colors.clone(),
+            diff_dir: args.diff_dir.clone(),
+            diff_missed: args.diff_missed,
+            error_values,
+            examine_globs,
+            profile,
+            features: Features::new(&args.features, args.all_features, args.no_default_features),
+            examine_functions,
+            examine_names,
+            exclude_functions,
+            exclude_globs,
+            config_examine_globs,
+            config_exclude_globs,
+            exclude_names,
+            exclude_packages: args
+                .exclude_package
+                .iter()
+                .chain(&config.exclude_packages)
+                .cloned()
+                .collect(),
+            fail_on_empty: args.fail_on_empty,
+            fail_on_missed: !args.no_fail_on_missed,
+            int_values: args.int_value.clone(),
+            in_diff: args
+                .in_diff
+                .as_deref()
+                .map(ChangedLines::read)
+                .transpose()?,
+            iterate: args.iterate && !args.no_cache,
+            keep_missed_dirs: args.keep_missed_dirs,
+            leak_dirs: args.leak_dirs,
+            max_literal_mutants: args.max_literal_mutants,
+            max_missed_in_summary: args.max_missed_in_summary,
+            max_tuple_mutants: args.max_tuple_mutants,
+            mutate_cfg_test: args.mutate_cfg_test,
+            mutate_literals: args.mutate_literals,
+            mutate_strings: args.mutate_strings,
+            only_pub: if args.only_pub_crate {
+                Some(OnlyPub::Crate)
+            } else if args.only_pub {
+                Some(OnlyPub::Pub)
+            } else {
+                None
+            },
+            mutate_operators: !args.no_operators,
+            packages: args.package.clone(),
+            skip_calls,
+            type_replacements: config.type_replacements.clone(),
+            verbose: args.verbose,
+            workspace: args.workspace,
+        })
+    }
+}
+
+/// Choose how results are printed from the command-line args.
+pub(crate) fn output_mode(args: &Args) -> Result<OutputMode> {
+    match (args.tap, args.json && !args.list && !args.list_files) {
+        (true, true) => bail!("--tap and --json can't be used together"),
+        (true, false) => Ok(OutputMode::Tap),
+        (false, true) => Ok(OutputMode::Json),
+        (false, false) => Ok(OutputMode::Human),
+    }
+}
+
+/// Check that expressions given to an option parse as Rust, so that mistakes are
+/// reported now rather than as unviable mutants.
+fn check_expressions(exprs: &[String], name: &str) -> Result<Vec<String>> {
+    for expr in exprs {
+        syn::parse_str::<syn::Expr>(expr)
+            .with_context(|| format!("parse {} expression {:?}", name, expr))?;
     }
+    Ok(exprs.to_vec())
+}
+
+/// Convert a number of seconds from an option into a duration.
+fn seconds(seconds: f64, name: &str) -> Result<Duration> {
+    if !(seconds.is_finite() && seconds >= 0.0) {
+        bail!("{} must be a non-negative number of seconds", name);
+    }
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Check that a timeout multiplier from an option is a positive number.
+fn multiplier_value(multiplier: f64, name: &str) -> Result<f64> {
+    if !(multiplier.is_finite() && multiplier > 0.0) {
+        bail!("{} must be a positive number", name);
+    }
+    Ok(multiplier)
+}
+
+/// Compile regexps given to an option or config key.
+fn compile_regexes(regexes: &[String], name: &str) -> Result<Vec<Regex>> {
+    regexes
+        .iter()
+        .map(|re| Regex::new(re).with_context(|| format!("parse {} regex {:?}", name, re)))
+        .collect()
+}
+
+/// Compile globs given to an option.
+fn compile_globs(globs: &[String], option_name: &str) -> Result<Vec<GlobMatcher>> {
+    globs
+        .iter()
+        .map(|glob| {
+            Glob::new(glob)
+                .map(|glob| glob.compile_matcher())
+                .with_context(|| format!("parse {} glob {:?}", option_name, glob))
+        })
+        .collect()
 }
@@ -4,18 +4,21 @@
 
 use std::fmt;
 use std::fs;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use anyhow::Context;
-use serde::Serialize;
+use path_slash::PathExt;
+use serde::{Deserialize, Serialize};
 
 use crate::exit_code;
+use crate::features::Features;
 use crate::log_file::LogFile;
+use crate::report::Totals;
 use crate::*;
 
 /// What phase of evaluating a tree?
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum Phase {
     Check,
     Build,
@@ -40,10 +43,100 @@ impl fmt::Display for Phase {
     }
 }
 
+/// A condition that makes the results of a run meaningless, although nothing failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Warning {
+    /// No mutants were generated from the source tree.
+    NoMutants,
+    /// The tests in the unmutated tree passed, but there were none.
+    NoTests,
+    /// The baseline was skipped, and a mutant failed to build because of errors in
+    /// files it didn't change, so the unmutated tree may not build either.
+    BaselineMayBeBroken,
+}
+
+impl Warning {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Warning::NoMutants => "no mutants were found, so nothing was tested",
+            Warning::NoTests => {
+                "no tests were run in the unmutated tree, so no mutants can be caught"
+            }
+            Warning::BaselineMayBeBroken => {
+                "a mutant failed to build because of errors in other files, so the unmutated tree may be broken: try again without --baseline skip"
+            }
+        }
+    }
+}
+
 /// The outcome from a whole lab run containing multiple mutants.
-#[derive(Debug, Default, Serialize)]
+///
+/// This is written to `outcomes.json`, with the scenario, log path, and results of each
+/// phase for every outcome.
+#[derive(Debug, Serialize)]
 pub struct LabOutcome {
     outcomes: Vec<Outcome>,
+    /// The number of mutants skipped by `--iterate` because they were caught in a
+    /// previous run.
+    cached_caught: usize,
+    /// Conditions that make the results doubtful.
+    warnings: Vec<Warning>,
+    /// The seed used to shuffle the mutants, if they were shuffled, so that the order
+    /// can be repeated with `--seed`.
+    seed: Option<u64>,
+    /// True if the mutants were only checked or built, with `--check`, so none can have
+    /// been caught or missed.
+    check_only: bool,
+    /// The additional arguments given to every cargo command, so that the run can be
+    /// repeated.
+    cargo_args: Vec<String>,
+    /// The additional arguments given to `cargo test`.
+    cargo_test_args: Vec<String>,
+    /// The arguments passed through `cargo test` to the test binaries.
+    test_args: Vec<String>,
+    /// The cargo features selected for every cargo command.
+    features: Features,
+    /// The cargo profile for every cargo command, if not the default.
+    profile: Option<String>,
+    /// The timeout for each `cargo test`, if there was one.
+    test_timeout: Option<Duration>,
+    /// The multiplier applied to the baseline test time, if the test timeout was set
+    /// from it.
+    timeout_multiplier: Option<f64>,
+    /// The shortest test timeout allowed, if the test timeout was set from the baseline.
+    minimum_test_timeout: Option<Duration>,
+    /// The timeout for each `cargo check` or `cargo build`, if there was one.
+    build_timeout: Option<Duration>,
+    /// The multiplier applied to the baseline build time, if the build timeout was set
+    /// from it.
+    build_timeout_multiplier: Option<f64>,
+    /// When the lab started, for the elapsed time in the summary.
+    #[serde(skip)]
+    start_time: Instant,
+}
+
+impl Default for LabOutcome {
+    fn default() -> Self {
+        LabOutcome {
+            outcomes: Vec::new(),
+            cached_caught: 0,
+            warnings: Vec::new(),
+            seed: None,
+            check_only: false,
+            cargo_args: Vec::new(),
+            cargo_test_args: Vec::new(),
+            test_args: Vec::new(),
+            features: Features::default(),
+            profile: None,
+            test_timeout: None,
+            timeout_multiplier: None,
+            minimum_test_timeout: None,
+            build_timeout: None,
+            build_timeout_multiplier: None,
+            start_time: Instant::now(),
+        }
+    }
 }
 
 impl LabOutcome {
@@ -52,8 +145,84 @@ impl LabOutcome {
         self.outcomes.push(outcome.clone());
     }
 
+    /// Record that a mutant was skipped because it was caught in a previous run.
+    pub fn add_cached_caught(&mut self) {
+        self.cached_caught += 1;
+    }
+
+    /// The number of mutants that weren't tested because they were caught in a previous
+    /// run.
+    pub fn cached_caught(&self) -> usize {
+        self.cached_caught
+    }
+
+    /// Record the seed used to shuffle the mutants.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Record that the mutants are only checked, not tested.
+    pub fn set_check_only(&mut self) {
+        self.check_only = true;
+    }
+
+    /// Record the additional arguments passed to cargo.
+    pub fn set_cargo_args(
+        &mut self,
+        cargo_args: &[String],
+        cargo_test_args: &[String],
+        test_args: &[String],
+    ) {
+        self.cargo_args = cargo_args.to_vec();
+        self.cargo_test_args = cargo_test_args.to_vec();
+        self.test_args = test_args.to_vec();
+    }
+
+    /// Record the features and profile selected for cargo.
+    pub fn set_features(&mut self, features: &Features, profile: Option<&str>) {
+        self.features = features.clone();
+        self.profile = profile.map(str::to_owned);
+    }
+
+    /// Record the test timeout, and the multiplier if it was set from the baseline.
+    pub fn set_test_timeout(&mut self, test_timeout: Duration, timeout_multiplier: Option<f64>) {
+        self.test_timeout = Some(test_timeout);
+        self.timeout_multiplier = timeout_multiplier;
+    }
+
+    /// Record the minimum test timeout, if the test timeout was set from the baseline.
+    pub fn set_minimum_test_timeout(&mut self, minimum_test_timeout: Duration) {
+        self.minimum_test_timeout = Some(minimum_test_timeout);
+    }
+
+    /// Record the build timeout, and the multiplier if it was set from the baseline.
+    pub fn set_build_timeout(
+        &mut self,
+        build_timeout: Duration,
+        build_timeout_multiplier: Option<f64>,
+    ) {
+        self.build_timeout = Some(build_timeout);
+        self.build_timeout_multiplier = build_timeout_multiplier;
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Record a warning about the run.
+    pub fn add_warning(&mut self, warning: Warning) {
+        self.warnings.push(warning);
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     /// Return the overall program exit code reflecting this outcome.
-    pub fn exit_code(&self) -> i32 {
+    ///
+    /// Missed mutants don't cause a failure if [Options::fail_on_missed] is false, and
+    /// warnings that nothing was tested only do if [Options::fail_on_empty] is true.
+    pub fn exit_code(&self, options: &Options) -> i32 {
         // TODO: Maybe move this into an error returned from experiment()?
         if self
             .outcomes
@@ -61,41 +230,199 @@ impl LabOutcome {
             .any(|o| !o.scenario.is_mutant() && !o.success())
         {
             exit_code::CLEAN_TESTS_FAILED
+        } else if options.fail_on_empty
+            && self
+                .warnings
+                .iter()
+                .any(|w| matches!(w, Warning::NoMutants | Warning::NoTests))
+        {
+            exit_code::EMPTY
         } else if self.outcomes.iter().any(|o| o.has_timeout()) {
             exit_code::TIMEOUT
-        } else if self.outcomes.iter().any(|o| o.mutant_missed()) {
+        } else if options.fail_on_missed && self.outcomes.iter().any(|o| o.mutant_missed()) {
             exit_code::FOUND_PROBLEMS
         } else {
             exit_code::SUCCESS
         }
     }
+
+    /// Count the mutants tested so far with each status.
+    pub fn totals(&self) -> Totals {
+        let mut totals = Totals::default();
+        for status in self.outcomes.iter().filter_map(Outcome::mutant_status) {
+            totals.add(status);
+        }
+        totals
+    }
+
+    /// The total time spent building and testing mutants, not including the source tree
+    /// and baseline.
+    pub fn mutant_duration(&self) -> Duration {
+        self.outcomes
+            .iter()
+            .filter(|o| o.scenario.is_mutant())
+            .map(Outcome::duration)
+            .sum()
+    }
+
+    /// The wall-clock time since the lab started.
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Return up to `n` mutant outcomes that took the longest, slowest first.
+    pub fn slowest_mutants(&self, n: usize) -> Vec<&Outcome> {
+        let mut mutants: Vec<&Outcome> = self
+            .outcomes
+            .iter()
+            .filter(|o| o.scenario.is_mutant())
+            .collect();
+        mutants.sort_by_key(|o| std::cmp::Reverse(o.duration()));
+        mutants.truncate(n);
+        mutants
+    }
+
+    /// The total time spent in each phase by all the mutants, for phases that were run.
+    pub fn mutant_phase_durations(&self) -> Vec<(Phase, Duration)> {
+        Phase::ALL
+            .iter()
+            .filter_map(|&phase| {
+                let mut durations = self
+                    .outcomes
+                    .iter()
+                    .filter(|o| o.scenario.is_mutant())
+                    .flat_map(|o| &o.phase_results)
+                    .filter(|pr| pr.phase == phase)
+                    .map(|pr| pr.duration)
+                    .peekable();
+                durations.peek()?;
+                Some((phase, durations.sum()))
+            })
+            .collect()
+    }
 }
 
 /// The result of running one mutation scenario.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 #[must_use]
 pub struct Outcome {
-    /// A file holding the text output from running this test.
-    log_path: PathBuf,
+    /// A file holding the text output from running this test, to read it back.
+    #[serde(skip)]
+    log_full_path: PathBuf,
+    /// The log file, relative to the output directory, like `log/baseline.log`.
+    log_path: String,
     /// What kind of scenario was being built?
     pub scenario: Scenario,
     /// For each phase, the duration and the cargo result.
     phase_results: Vec<PhaseResult>,
+    /// For mutants, how the mutant fared, so that readers of `outcomes.json` don't have
+    /// to work it out from the phase results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<MutantStatus>,
+    /// For mutants, the diff of the mutation, so that reports can show it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+    /// A copy of the tree that was kept, with `--keep-missed-dirs` or `--leak-dirs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kept_dir: Option<String>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
-struct PhaseResult {
-    phase: Phase,
-    duration: Duration,
-    cargo_result: CargoResult,
+/// Count the tests run, from the output of `cargo test`, or return None if no test
+/// binaries were run.
+///
+/// Each test binary, including doctests, prints a line like `running 3 tests`.
+pub fn count_tests_run(cargo_test_output: &str) -> Option<usize> {
+    let counts: Vec<usize> = cargo_test_output
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("running ")?
+                .strip_suffix(" tests")
+                .or_else(|| line.strip_prefix("running ")?.strip_suffix(" test"))?
+                .parse()
+                .ok()
+        })
+        .collect();
+    if counts.is_empty() {
+        None
+    } else {
+        Some(counts.iter().sum())
+    }
+}
+
+/// The result of running one phase of a scenario.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PhaseResult {
+    pub phase: Phase,
+    pub duration: Duration,
+    pub cargo_result: CargoResult,
+}
+
+/// How a mutant fared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MutantStatus {
+    /// The tests failed, so the mutant was caught.
+    Caught,
+    /// The tests passed, so the mutant was not caught.
+    Missed,
+    /// The mutant failed to check or build.
+    Unviable,
+    /// The tests timed out.
+    Timeout,
+    /// The check or build timed out.
+    #[serde(rename = "build_timeout")]
+    BuildTimeout,
+    /// The mutant built, but was not tested, as with `--check`.
+    Untested,
+}
+
+impl MutantStatus {
+    /// Judge a mutant from the results of the phases it ran.
+    pub fn from_phase_results(phase_results: &[PhaseResult]) -> MutantStatus {
+        let last = phase_results.last().expect("at least one phase was run");
+        if last.cargo_result == CargoResult::Timeout {
+            if last.phase == Phase::Test {
+                MutantStatus::Timeout
+            } else {
+                MutantStatus::BuildTimeout
+            }
+        } else if last.cargo_result == CargoResult::Failure {
+            if last.phase == Phase::Test {
+                MutantStatus::Caught
+            } else {
+                MutantStatus::Unviable
+            }
+        } else if last.phase == Phase::Test {
+            MutantStatus::Missed
+        } else {
+            MutantStatus::Untested
+        }
+    }
 }
 
 impl Outcome {
     pub fn new(log_file: &LogFile, scenario: Scenario) -> Outcome {
+        let diff = match &scenario {
+            Scenario::Mutant { mutation, .. } => Some(mutation.diff()),
+            _ => None,
+        };
         Outcome {
-            log_path: log_file.path().to_owned(),
+            log_full_path: log_file.path().to_owned(),
+            log_path: log_file.relative_path(),
             scenario,
             phase_results: Vec::new(),
+            status: None,
+            diff,
+            kept_dir: None,
+        }
+    }
+
+    /// For mutants, return how the mutant fared; otherwise None.
+    pub fn mutant_status(&self) -> Option<MutantStatus> {
+        if self.scenario.is_mutant() {
+            Some(MutantStatus::from_phase_results(&self.phase_results))
+        } else {
+            None
         }
     }
 
@@ -110,10 +437,16 @@ impl Outcome {
             duration,
             cargo_result,
         });
+        self.status = self.mutant_status();
     }
 
     pub fn get_log_content(&self) -> Result<String> {
-        fs::read_to_string(&self.log_path).context("read log file")
+        fs::read_to_string(&self.log_full_path).context("read log file")
+    }
+
+    /// The log file, relative to the output directory.
+    pub fn log_path(&self) -> &str {
+        &self.log_path
     }
 
     pub fn last_phase(&self) -> Phase {
@@ -160,6 +493,26 @@ impl Outcome {
             && self.last_phase_result().success()
     }
 
+    /// The total time taken by all the phases.
+    pub fn duration(&self) -> Duration {
+        self.phase_results.iter().map(|pr| pr.duration).sum()
+    }
+
+    /// The directory where a copy of the tree was kept, if any.
+    pub fn kept_dir(&self) -> Option<&str> {
+        self.kept_dir.as_deref()
+    }
+
+    /// Record that a copy of the tree was kept in this directory.
+    pub fn set_kept_dir(&mut self, dir: &Path) {
+        self.kept_dir = Some(dir.to_slash_lossy());
+    }
+
+    /// The results of each phase that was run, in order.
+    pub fn phase_results(&self) -> &[PhaseResult] {
+        &self.phase_results
+    }
+
     /// Duration of the test phase, if tests were run.
     pub fn test_duration(&self) -> Option<Duration> {
         if let Some(phase_result) = self.phase_results.last() {
@@ -169,4 +522,262 @@ impl Outcome {
         }
         None
     }
+
+    /// Duration of the longest check or build phase, if one was run.
+    pub fn build_duration(&self) -> Option<Duration> {
+        self.phase_results
+            .iter()
+            .filter(|pr| pr.phase != Phase::Test)
+            .map(|pr| pr.duration)
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::source::SourceFile;
+    use crate::textedit::Span;
+
+    /// What's read back from `outcomes.json`: [Outcome] can't be deserialized because the
+    /// mutation doesn't include the source code, but everything written can be read.
+    #[derive(Debug, Deserialize)]
+    struct OutcomeRecord {
+        log_path: String,
+        scenario: ScenarioRecord,
+        phase_results: Vec<PhaseResult>,
+        diff: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    enum ScenarioRecord {
+        SourceTree,
+        Baseline,
+        Mutant {
+            mutation: Box<MutationRecord>,
+            i_mutation: usize,
+            n_mutations: usize,
+        },
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct MutationRecord {
+        genre: crate::mutate::Genre,
+        file: String,
+        line: usize,
+        column: usize,
+        span: Span,
+        function: String,
+        return_type: String,
+        original: String,
+        replacement: String,
+        change: String,
+        description: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct LabOutcomeRecord {
+        outcomes: Vec<OutcomeRecord>,
+    }
+
+    #[test]
+    fn outcomes_json_round_trips() {
+        let temp = tempfile::tempdir().unwrap();
+        let source_file = SourceFile::new(
+            Path::new("testdata/tree/factorial"),
+            Path::new("src/bin/main.rs"),
+        )
+        .unwrap();
+        let mutation = source_file.discover(&Options::default()).unwrap().mutations[1].clone();
+        let mut lab_outcome = LabOutcome::default();
+        let log_dir = temp.path().join("log");
+        fs::create_dir(&log_dir).unwrap();
+        let log_file = LogFile::create_in(&log_dir, "baseline").unwrap();
+        let mut baseline = Outcome::new(&log_file, Scenario::Baseline);
+        for phase in Phase::ALL {
+            baseline.add_phase_result(*phase, Duration::from_millis(1500), CargoResult::Success);
+        }
+        lab_outcome.add(&baseline);
+        let log_file = LogFile::create_in(&log_dir, "mutant").unwrap();
+        let mut mutant = Outcome::new(
+            &log_file,
+            Scenario::Mutant {
+                mutation: mutation.clone(),
+                package: None,
+                i_mutation: 1,
+                n_mutations: 3,
+            },
+        );
+        mutant.add_phase_result(Phase::Build, Duration::from_secs(2), CargoResult::Success);
+        mutant.add_phase_result(Phase::Test, Duration::from_secs(3), CargoResult::Failure);
+        lab_outcome.add(&mutant);
+        assert_eq!(
+            lab_outcome.totals(),
+            Totals {
+                total: 1,
+                caught: 1,
+                ..Totals::default()
+            }
+        );
+        assert_eq!(lab_outcome.mutant_duration(), Duration::from_secs(5));
+
+        let json = serde_json::to_string_pretty(&lab_outcome).unwrap();
+        let record: LabOutcomeRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(record.outcomes.len(), 2);
+
+        assert_eq!(record.outcomes[0].log_path, "log/baseline.log");
+        assert!(matches!(
+            record.outcomes[0].scenario,
+            ScenarioRecord::Baseline
+        ));
+        assert_eq!(record.outcomes[0].phase_results, baseline.phase_results);
+
+        assert_eq!(record.outcomes[1].log_path, "log/mutant.log");
+        assert_eq!(record.outcomes[1].phase_results, mutant.phase_results);
+        assert_eq!(record.outcomes[1].diff, Some(mutation.diff()));
+        assert_eq!(record.outcomes[0].diff, None);
+        match &record.outcomes[1].scenario {
+            ScenarioRecord::Mutant {
+                mutation: mutation_record,
+                i_mutation,
+                n_mutations,
+            } => {
+                assert_eq!((*i_mutation, *n_mutations), (1, 3));
+                assert_eq!(mutation_record.genre, mutation.genre());
+                assert_eq!(mutation_record.file, "src/bin/main.rs");
+                assert_eq!((mutation_record.line, mutation_record.column), (7, 29));
+                assert_eq!(mutation_record.span.end.line, 13);
+                assert_eq!(mutation_record.function, "factorial");
+                assert_eq!(mutation_record.return_type, "-> u32");
+                assert_eq!(mutation_record.original, "");
+                assert_eq!(mutation_record.replacement, "0");
+                assert_eq!(mutation_record.change, "replace factorial with 0");
+                assert_eq!(mutation_record.description, mutation.to_string());
+            }
+            other => panic!("unexpected scenario {:?}", other),
+        }
+
+        // Durations are written as seconds and nanoseconds.
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["outcomes"][0]["phase_results"][0],
+            json!({
+                "phase": "Check",
+                "duration": { "secs": 1, "nanos": 500_000_000 },
+                "cargo_result": "Success",
+            })
+        );
+    }
+
+    #[test]
+    fn slowest_mutants_and_phase_durations() {
+        let temp = tempfile::tempdir().unwrap();
+        let source_file = SourceFile::new(
+            Path::new("testdata/tree/factorial"),
+            Path::new("src/bin/main.rs"),
+        )
+        .unwrap();
+        let mutations = source_file.discover(&Options::default()).unwrap().mutations;
+        let log_file = LogFile::create_in(temp.path(), "log").unwrap();
+        let mut lab_outcome = LabOutcome::default();
+        let mut baseline = Outcome::new(&log_file, Scenario::Baseline);
+        baseline.add_phase_result(Phase::Test, Duration::from_secs(100), CargoResult::Success);
+        lab_outcome.add(&baseline);
+        for (i, (build_secs, test_secs)) in [(2, 1), (1, 5), (3, 0)].iter().enumerate() {
+            let mut outcome = Outcome::new(
+                &log_file,
+                Scenario::Mutant {
+                    mutation: mutations[i].clone(),
+                    package: None,
+                    i_mutation: i,
+                    n_mutations: 3,
+                },
+            );
+            outcome.add_phase_result(
+                Phase::Build,
+                Duration::from_secs(*build_secs),
+                CargoResult::Success,
+            );
+            if *test_secs > 0 {
+                outcome.add_phase_result(
+                    Phase::Test,
+                    Duration::from_secs(*test_secs),
+                    CargoResult::Failure,
+                );
+            }
+            lab_outcome.add(&outcome);
+        }
+
+        let slowest: Vec<Duration> = lab_outcome
+            .slowest_mutants(2)
+            .iter()
+            .map(|o| o.duration())
+            .collect();
+        assert_eq!(slowest, [Duration::from_secs(6), Duration::from_secs(3)]);
+        assert_eq!(lab_outcome.slowest_mutants(10).len(), 3);
+        // The baseline isn't counted, and the check phase wasn't run.
+        assert_eq!(
+            lab_outcome.mutant_phase_durations(),
+            [
+                (Phase::Build, Duration::from_secs(6)),
+                (Phase::Test, Duration::from_secs(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_tests_from_cargo_test_output() {
+        let output = "\
+   Compiling foo v0.1.0
+     Running unittests src/lib.rs (target/debug/deps/foo-1234)
+
+running 1 test
+test test_factorial ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out
+
+running 12 tests
+test result: ok. 12 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out
+
+   Doc-tests foo
+
+running 0 tests
+";
+        assert_eq!(count_tests_run(output), Some(13));
+        assert_eq!(
+            count_tests_run("running 0 tests\n\nrunning 0 tests\n"),
+            Some(0)
+        );
+        assert_eq!(count_tests_run("error: could not compile `foo`\n"), None);
+    }
+
+    #[test]
+    fn test_and_build_timeouts_are_distinct() {
+        let phase_result = |phase, cargo_result| PhaseResult {
+            phase,
+            duration: Duration::from_secs(1),
+            cargo_result,
+        };
+        let built = phase_result(Phase::Build, CargoResult::Success);
+        assert_eq!(
+            MutantStatus::from_phase_results(&[
+                built.clone(),
+                phase_result(Phase::Test, CargoResult::Timeout)
+            ]),
+            MutantStatus::Timeout
+        );
+        assert_eq!(
+            MutantStatus::from_phase_results(&[phase_result(Phase::Build, CargoResult::Timeout)]),
+            MutantStatus::BuildTimeout
+        );
+        assert_eq!(
+            serde_json::to_value(MutantStatus::BuildTimeout).unwrap(),
+            json!("build_timeout")
+        );
+    }
 }
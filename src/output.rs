@@ -2,21 +2,41 @@
 
 //! A `mutants.out` directory holding logs and other output.
 
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
-use crate::log_file::LogFile;
+use crate::badge::{badge, DEFAULT_BADGE_COLORS};
+use crate::console;
+use crate::file_table::file_table;
+use crate::html::html_report;
+use crate::junit::junit_report;
+use crate::lab::Scenario;
+use crate::lcov::lcov_report;
+use crate::log_file::{clean_filename, LogFile};
+use crate::markdown::{markdown_summary, DEFAULT_MAX_MISSED_IN_SUMMARY};
+use crate::mutate::Mutation;
+use crate::outcome::{LabOutcome, MutantStatus, Outcome};
+use crate::report::LabOutcomeRecord;
+use crate::sarif::sarif_report;
+use crate::Options;
 
-const OUTDIR_NAME: &str = "mutants.out";
-const ROTATED_NAME: &str = "mutants.out.old";
+pub const OUTDIR_NAME: &str = "mutants.out";
+pub const ROTATED_NAME: &str = "mutants.out.old";
 
 /// A `mutants.out` directory holding logs and other output information.
 #[derive(Debug)]
 pub struct OutputDir {
     path: PathBuf,
     log_dir: PathBuf,
+
+    /// Lists of mutants with each outcome, one per line, appended as they're tested.
+    caught_list: File,
+    missed_list: File,
+    unviable_list: File,
+    timeout_list: File,
 }
 
 impl OutputDir {
@@ -37,7 +57,41 @@ impl OutputDir {
         fs::create_dir(&path).with_context(|| format!("create output directory {:?}", &path))?;
         let log_dir = path.join("log");
         fs::create_dir(&log_dir).with_context(|| format!("create log directory {:?}", &log_dir))?;
-        Ok(OutputDir { path, log_dir })
+        let open_list = |name: &str| {
+            let list_path = path.join(name);
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&list_path)
+                .with_context(|| format!("create {:?}", &list_path))
+        };
+        Ok(OutputDir {
+            caught_list: open_list("caught.txt")?,
+            missed_list: open_list("missed.txt")?,
+            unviable_list: open_list("unviable.txt")?,
+            timeout_list: open_list("timeout.txt")?,
+            path,
+            log_dir,
+        })
+    }
+
+    /// Add the mutant tested in this outcome to the list for its outcome, if any.
+    ///
+    /// Each line is written straight through to the file, so the lists are complete up to
+    /// this mutant even if cargo-mutants is interrupted.
+    pub fn add_outcome(&self, outcome: &Outcome) -> Result<()> {
+        let mutation = match &outcome.scenario {
+            Scenario::Mutant { mutation, .. } => mutation,
+            _ => return Ok(()),
+        };
+        let mut list = match outcome.mutant_status() {
+            Some(MutantStatus::Caught) => &self.caught_list,
+            Some(MutantStatus::Missed) => &self.missed_list,
+            Some(MutantStatus::Unviable) => &self.unviable_list,
+            Some(MutantStatus::Timeout | MutantStatus::BuildTimeout) => &self.timeout_list,
+            Some(MutantStatus::Untested) | None => return Ok(()),
+        };
+        writeln!(list, "{}", console::plain_mutation(mutation)).context("write mutant list")
     }
 
     /// Create a new log for a given scenario.
@@ -48,6 +102,53 @@ impl OutputDir {
         LogFile::create_in(&self.log_dir, scenario_name)
     }
 
+    /// Write all the outcomes so far to `outcomes.json`.
+    pub fn write_outcomes(&self, lab_outcome: &LabOutcome) -> Result<()> {
+        let outcomes_path = self.path.join("outcomes.json");
+        let file =
+            File::create(&outcomes_path).with_context(|| format!("create {:?}", &outcomes_path))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), lab_outcome)
+            .with_context(|| format!("write {:?}", &outcomes_path))
+    }
+
+    /// Write reports such as `mutants.html` and `junit.xml`, from the `outcomes.json`
+    /// already written in this directory.
+    pub fn write_reports(&self, options: &Options) -> Result<()> {
+        let max_missed = options
+            .max_missed_in_summary
+            .unwrap_or(DEFAULT_MAX_MISSED_IN_SUMMARY);
+        let badge_colors: Vec<(&str, f64)> = if options.badge_colors.is_empty() {
+            DEFAULT_BADGE_COLORS.to_vec()
+        } else {
+            options
+                .badge_colors
+                .iter()
+                .map(|(color, &min)| (color.as_str(), min))
+                .collect()
+        };
+        let record = LabOutcomeRecord::read_file(&self.path.join("outcomes.json"))?;
+        for (name, content) in [
+            ("mutants.html", html_report(&record)),
+            ("junit.xml", junit_report(&record)),
+            ("summary.md", markdown_summary(&record, max_missed)),
+            ("files.txt", file_table(&record)),
+            ("lcov.info", lcov_report(&record)),
+            (
+                "badge.json",
+                serde_json::to_string(&badge(&record, &badge_colors))?,
+            ),
+            (
+                "mutants.sarif",
+                serde_json::to_string_pretty(&sarif_report(&record))?,
+            ),
+        ] {
+            let report_path = self.path.join(name);
+            fs::write(&report_path, content)
+                .with_context(|| format!("write {:?}", &report_path))?;
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     /// Return the path of the `mutants.out` directory.
     pub fn path(&self) -> &Path {
@@ -55,6 +156,24 @@ impl OutputDir {
     }
 }
 
+/// Write the diff of each mutation into its own `.patch` file in a directory, which is
+/// created if necessary.
+///
+/// Files are numbered in the order the mutations are given, and named by their description,
+/// shortened by [clean_filename] if it's long.
+pub fn write_diffs(diff_dir: &Path, mutations: &[Mutation]) -> Result<()> {
+    fs::create_dir_all(diff_dir).with_context(|| format!("create {:?}", diff_dir))?;
+    for (i, mutation) in mutations.iter().enumerate() {
+        let path = diff_dir.join(format!(
+            "{:04}-{}.patch",
+            i + 1,
+            clean_filename(&mutation.to_string())
+        ));
+        fs::write(&path, mutation.diff()).with_context(|| format!("write {:?}", path))?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use itertools::Itertools;
@@ -93,7 +212,16 @@ mod test {
         let output_dir = OutputDir::new(src_tree.root()).unwrap();
         assert_eq!(
             list_recursive(tmp.path()),
-            &["", "Cargo.toml", "mutants.out", "mutants.out/log",]
+            &[
+                "",
+                "Cargo.toml",
+                "mutants.out",
+                "mutants.out/caught.txt",
+                "mutants.out/log",
+                "mutants.out/missed.txt",
+                "mutants.out/timeout.txt",
+                "mutants.out/unviable.txt",
+            ]
         );
         assert_eq!(output_dir.path(), tmp.path().join("mutants.out"));
         assert_eq!(output_dir.log_dir, tmp.path().join("mutants.out/log"));
@@ -133,4 +261,24 @@ mod test {
             .join("mutants.out.old/log/one.log")
             .is_file());
     }
+
+    #[test]
+    fn diffs_of_mutants_with_long_names_are_written() {
+        let tmp = tempfile::tempdir().unwrap();
+        let name = "a_very_long_name".repeat(20);
+        fs::write(
+            tmp.path().join("lib.rs"),
+            format!("fn {}() -> bool {{ true }}\n", name),
+        )
+        .unwrap();
+        let source_file = crate::source::SourceFile::new(tmp.path(), Path::new("lib.rs")).unwrap();
+        let mutations = source_file.discover(&Options::default()).unwrap().mutations;
+        assert_eq!(mutations.len(), 2);
+        let diff_dir = tmp.path().join("diffs");
+        write_diffs(&diff_dir, &mutations).unwrap();
+        let names = list_recursive(&diff_dir);
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert!(names.iter().all(|name| name.len() <= 120), "{:?}", names);
+        assert!(names[1].starts_with("0001-replace_a_very_long_name"));
+    }
 }
@@ -0,0 +1,191 @@
+// Copyright 2022 Martin Pool
+
+//! Find the packages in a Cargo workspace, and the directories containing their source,
+//! from `cargo metadata`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context};
+use path_slash::PathExt;
+use serde::Deserialize;
+
+use crate::run::cargo_bin;
+use crate::*;
+
+/// Kinds of target whose code isn't mutated, because it's not part of the package's
+/// library or binaries.
+const UNMUTATED_TARGET_KINDS: &[&str] = &["test", "bench", "example", "custom-build"];
+
+/// A package within a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The name of the package, as given to `cargo -p`.
+    pub name: String,
+
+    /// Directories containing the top source files of the package's library and
+    /// binaries, relative to the source tree.
+    pub source_dirs: Vec<PathBuf>,
+
+    /// True if the package is one of the workspace's `default-members`, which cargo
+    /// builds when run in the root of a virtual workspace with no package options.
+    pub default_member: bool,
+
+    /// The package's `[features]` table: each feature, and the features and dependencies
+    /// it enables.
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+
+    /// The ids of the default members, from cargo 1.71 on; if it's missing, all packages
+    /// are taken to be default members, as they are when none are configured.
+    #[serde(default)]
+    workspace_default_members: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    targets: Vec<MetadataTarget>,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataTarget {
+    kind: Vec<String>,
+    src_path: PathBuf,
+}
+
+/// Return the packages in the workspace containing a source tree, from `cargo metadata`.
+pub fn workspace_packages(root: &Path) -> Result<Vec<Package>> {
+    let cargo_bin = cargo_bin();
+    let output = Command::new(cargo_bin.as_ref())
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(root)
+        .output()
+        .with_context(|| format!("failed to run {} metadata", cargo_bin))?;
+    if !output.status.success() {
+        bail!(
+            "cargo metadata failed in {}: {}",
+            root.to_slash_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let metadata = String::from_utf8(output.stdout).context("cargo metadata output")?;
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("canonicalize {}", root.to_slash_lossy()))?;
+    packages_from_metadata(&metadata, &root)
+}
+
+/// Parse the JSON output from `cargo metadata` into the packages within the tree at
+/// `root`, which must be an absolute, canonical path.
+fn packages_from_metadata(metadata: &str, root: &Path) -> Result<Vec<Package>> {
+    let metadata: Metadata = serde_json::from_str(metadata).context("parse cargo metadata")?;
+    let mut packages = Vec::new();
+    for package in metadata.packages {
+        let mut source_dirs: Vec<PathBuf> = Vec::new();
+        for target in &package.targets {
+            if target
+                .kind
+                .iter()
+                .all(|kind| UNMUTATED_TARGET_KINDS.contains(&kind.as_str()))
+            {
+                continue;
+            }
+            let dir = target.src_path.parent().ok_or_else(|| {
+                anyhow!(
+                    "source path {} of package {:?} has no parent",
+                    target.src_path.to_slash_lossy(),
+                    package.name
+                )
+            })?;
+            match dir.strip_prefix(root) {
+                Ok(dir) => source_dirs.push(dir.to_owned()),
+                Err(_) => eprintln!(
+                    "warning: source directory {} of package {:?} is outside the source tree, and won't be mutated",
+                    dir.to_slash_lossy(),
+                    package.name
+                ),
+            }
+        }
+        // Directories within others, like `src/bin` within `src`, are walked as part of
+        // the outer directory.
+        source_dirs.sort();
+        source_dirs.dedup();
+        let outer_dirs: Vec<PathBuf> = source_dirs
+            .iter()
+            .filter(|dir| {
+                !source_dirs
+                    .iter()
+                    .any(|outer| outer != *dir && dir.starts_with(outer))
+            })
+            .cloned()
+            .collect();
+        let default_member = metadata
+            .workspace_default_members
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&package.id));
+        packages.push(Package {
+            name: package.name,
+            source_dirs: outer_dirs,
+            default_member,
+            features: package.features,
+        });
+    }
+    Ok(packages)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn find_source_dirs_of_packages_in_testdata_workspace() {
+        let packages = workspace_packages(Path::new("testdata/tree/workspace")).unwrap();
+        assert_eq!(
+            packages,
+            [
+                Package {
+                    name: "cargo_mutants_testdata_workspace_main".to_owned(),
+                    source_dirs: vec![PathBuf::from("main/src")],
+                    default_member: true,
+                    features: BTreeMap::new(),
+                },
+                Package {
+                    name: "cargo_mutants_testdata_workspace_utils".to_owned(),
+                    source_dirs: vec![PathBuf::from("utils/src")],
+                    default_member: false,
+                    features: BTreeMap::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_and_test_target_dirs_are_skipped() {
+        let metadata = r#"{ "packages": [ { "id": "p 0.1.0", "name": "p", "targets": [
+            { "kind": ["lib"], "src_path": "/ws/p/src/lib.rs" },
+            { "kind": ["bin"], "src_path": "/ws/p/src/bin/tool.rs" },
+            { "kind": ["bin"], "src_path": "/ws/p/tools/other.rs" },
+            { "kind": ["test"], "src_path": "/ws/p/tests/t.rs" },
+            { "kind": ["custom-build"], "src_path": "/ws/p/build.rs" }
+        ] } ] }"#;
+        let packages = packages_from_metadata(metadata, Path::new("/ws")).unwrap();
+        assert_eq!(
+            packages[0].source_dirs,
+            [PathBuf::from("p/src"), PathBuf::from("p/tools")]
+        );
+        // Without `workspace_default_members`, from older versions of cargo, every
+        // package is a default member.
+        assert!(packages[0].default_member);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2022 Martin Pool
+
+//! Read back `outcomes.json`, to generate reports from it.
+//!
+//! Reports are built from what's written to `outcomes.json`, rather than from the
+//! in-memory outcomes, so that they can also be regenerated later from the file alone.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use path_slash::PathExt;
+use serde::Deserialize;
+
+use crate::outcome::{MutantStatus, PhaseResult};
+use crate::textedit::Span;
+use crate::*;
+
+/// The contents of `outcomes.json`.
+#[derive(Debug, Deserialize)]
+pub struct LabOutcomeRecord {
+    pub outcomes: Vec<OutcomeRecord>,
+    /// The number of mutants not retested because they were caught in a previous run.
+    #[serde(default)]
+    pub cached_caught: usize,
+    /// True if the mutants were only checked, with `--check`, and not tested.
+    #[serde(default)]
+    pub check_only: bool,
+}
+
+/// One outcome read from `outcomes.json`.
+#[derive(Debug, Deserialize)]
+pub struct OutcomeRecord {
+    /// The log file, relative to the output directory.
+    #[serde(default)]
+    pub log_path: String,
+    pub scenario: ScenarioRecord,
+    pub phase_results: Vec<PhaseResult>,
+    #[serde(default)]
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum ScenarioRecord {
+    SourceTree,
+    Baseline,
+    Mutant { mutation: Box<MutationRecord> },
+}
+
+/// The parts of a mutation that are used in reports.
+#[derive(Debug, Deserialize)]
+pub struct MutationRecord {
+    pub file: String,
+    pub span: Span,
+    pub function: String,
+    pub replacement: String,
+    /// A description of the change, without the location.
+    pub change: String,
+    pub description: String,
+}
+
+impl MutationRecord {
+    /// The line where the mutated code starts.
+    pub fn line(&self) -> usize {
+        self.span.start.line
+    }
+}
+
+/// Counts of mutants with each status.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Totals {
+    pub total: usize,
+    pub caught: usize,
+    pub missed: usize,
+    pub unviable: usize,
+    pub timeout: usize,
+    pub untested: usize,
+}
+
+impl Totals {
+    /// The percentage of mutants that were caught, out of those that were tested to
+    /// completion, or None if there were none.
+    ///
+    /// Unviable mutants and those that timed out aren't counted either way.
+    pub fn score(&self) -> Option<f64> {
+        let tested = self.caught + self.missed;
+        if tested == 0 {
+            None
+        } else {
+            Some(self.caught as f64 * 100.0 / tested as f64)
+        }
+    }
+
+    /// Count one more mutant with this status.
+    pub fn add(&mut self, status: MutantStatus) {
+        self.total += 1;
+        *match status {
+            MutantStatus::Caught => &mut self.caught,
+            MutantStatus::Missed => &mut self.missed,
+            MutantStatus::Unviable => &mut self.unviable,
+            MutantStatus::Timeout | MutantStatus::BuildTimeout => &mut self.timeout,
+            MutantStatus::Untested => &mut self.untested,
+        } += 1;
+    }
+}
+
+impl OutcomeRecord {
+    /// The total time taken by all the phases.
+    pub fn duration(&self) -> Duration {
+        self.phase_results.iter().map(|pr| pr.duration).sum()
+    }
+}
+
+impl LabOutcomeRecord {
+    /// Read `outcomes.json`.
+    pub fn read_file(path: &Path) -> Result<LabOutcomeRecord> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("read outcomes from {}", path.to_slash_lossy()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("parse outcomes from {}", path.to_slash_lossy()))
+    }
+
+    /// Return each mutant that was tested, with its status.
+    pub fn mutants(&self) -> impl Iterator<Item = (&MutationRecord, MutantStatus, &OutcomeRecord)> {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.scenario {
+                ScenarioRecord::Mutant { mutation } => Some((
+                    mutation.as_ref(),
+                    MutantStatus::from_phase_results(&outcome.phase_results),
+                    outcome,
+                )),
+                _ => None,
+            })
+    }
+
+    /// Count the mutants with each status.
+    pub fn totals(&self) -> Totals {
+        let mut totals = Totals::default();
+        for (_, status, _) in self.mutants() {
+            totals.add(status);
+        }
+        totals
+    }
+
+    /// Count the mutants with each status in each source file.
+    pub fn totals_by_file(&self) -> BTreeMap<&str, Totals> {
+        let mut by_file: BTreeMap<&str, Totals> = BTreeMap::new();
+        for (mutation, status, _) in self.mutants() {
+            by_file
+                .entry(mutation.file.as_str())
+                .or_default()
+                .add(status);
+        }
+        by_file
+    }
+
+    /// Count the mutants with each status on each line of each source file.
+    pub fn totals_by_line(&self) -> BTreeMap<(&str, usize), Totals> {
+        let mut by_line: BTreeMap<(&str, usize), Totals> = BTreeMap::new();
+        for (mutation, status, _) in self.mutants() {
+            by_line
+                .entry((mutation.file.as_str(), mutation.line()))
+                .or_default()
+                .add(status);
+        }
+        by_line
+    }
+}
+
+/// Escape text to be included in HTML or XML.
+pub fn escape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => r.push_str("&amp;"),
+            '<' => r.push_str("&lt;"),
+            '>' => r.push_str("&gt;"),
+            '"' => r.push_str("&quot;"),
+            '\'' => r.push_str("&apos;"),
+            _ => r.push(c),
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_markup() {
+        assert_eq!(
+            escape(r#"replace a < b && "c" with 'd'"#),
+            "replace a &lt; b &amp;&amp; &quot;c&quot; with &apos;d&apos;"
+        );
+    }
+}
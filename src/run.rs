@@ -8,7 +8,7 @@ use std::path::Path;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use subprocess::{Popen, PopenConfig, Redirection};
 
 use crate::console::Activity;
@@ -19,7 +19,7 @@ use crate::*;
 const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// The result of running a single Cargo command.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum CargoResult {
     // Note: This is not, for now, a Result, because it seems like there is
     // no clear "normal" success: sometimes a non-zero exit is what we want, etc.
@@ -41,6 +41,16 @@ impl CargoResult {
     }
 }
 
+/// Return the command to run cargo.
+pub fn cargo_bin() -> Cow<'static, str> {
+    // When run as a Cargo subcommand, which is the usual/intended case,
+    // $CARGO tells us the right way to call back into it, so that we get
+    // the matching toolchain etc.
+    env::var("CARGO")
+        .map(Cow::from)
+        .unwrap_or(Cow::Borrowed("cargo"))
+}
+
 /// Run one `cargo` subprocess, with a timeout, and with appropriate handling of interrupts.
 pub fn run_cargo(
     cargo_args: &[&str],
@@ -50,12 +60,7 @@ pub fn run_cargo(
     timeout: Duration,
 ) -> Result<CargoResult> {
     let start = Instant::now();
-    // When run as a Cargo subcommand, which is the usual/intended case,
-    // $CARGO tells us the right way to call back into it, so that we get
-    // the matching toolchain etc.
-    let cargo_bin: Cow<str> = env::var("CARGO")
-        .map(Cow::from)
-        .unwrap_or(Cow::Borrowed("cargo"));
+    let cargo_bin = cargo_bin();
     log_file.message(&format!("run {} {}", cargo_bin, cargo_args.join(" "),));
 
     let mut argv: Vec<&str> = vec![&cargo_bin];
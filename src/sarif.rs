@@ -0,0 +1,175 @@
+// Copyright 2022 Martin Pool
+
+//! A SARIF 2.1.0 report of missed mutants, built from `outcomes.json`, so that they can
+//! be shown by code scanning tools.
+//!
+//! See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>.
+
+use serde_json::{json, Value};
+
+use crate::outcome::MutantStatus;
+use crate::report::LabOutcomeRecord;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The id of the one rule that results are reported against.
+const MISSED_MUTANT_RULE: &str = "missed-mutant";
+
+/// Return a SARIF log with a result for each missed mutant.
+pub fn sarif_report(record: &LabOutcomeRecord) -> Value {
+    let results: Vec<Value> = record
+        .mutants()
+        .filter(|(_, status, _)| *status == MutantStatus::Missed)
+        .map(|(mutation, _, _)| {
+            json!({
+                "ruleId": MISSED_MUTANT_RULE,
+                "level": "warning",
+                "message": {
+                    "text": format!(
+                        "Mutant was not caught by tests: {}. Replacing this code with `{}` \
+                        did not cause any tests to fail.",
+                        mutation.description,
+                        mutation.replacement,
+                    ),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": mutation.file,
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        // Columns are 1-based, and the end is just after the last
+                        // character, as in SARIF.
+                        "region": {
+                            "startLine": mutation.span.start.line,
+                            "startColumn": mutation.span.start.column,
+                            "endLine": mutation.span.end.line,
+                            "endColumn": mutation.span.end.column,
+                        },
+                    },
+                    "logicalLocations": [{
+                        "fullyQualifiedName": mutation.function,
+                        "kind": "function",
+                    }],
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cargo-mutants",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": [{
+                        "id": MISSED_MUTANT_RULE,
+                        "name": "MissedMutant",
+                        "shortDescription": {
+                            "text": "Code was changed without any tests failing",
+                        },
+                        "helpUri": env!("CARGO_PKG_REPOSITORY"),
+                    }],
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn example_record() -> LabOutcomeRecord {
+        serde_json::from_str(
+            r#"{ "outcomes": [
+                { "scenario": "Baseline", "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs",
+                    "span": { "start": { "line": 3, "column": 7 }, "end": { "line": 3, "column": 8 } },
+                    "function": "Foo::less",
+                    "replacement": "<=",
+                    "change": "replace < with <= in Foo::less",
+                    "description": "replace < with <= in Foo::less"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Success" }
+                ] },
+                { "scenario": { "Mutant": { "mutation": {
+                    "file": "src/lib.rs",
+                    "span": { "start": { "line": 9, "column": 1 }, "end": { "line": 11, "column": 2 } },
+                    "function": "f",
+                    "replacement": "0",
+                    "change": "replace f with 0",
+                    "description": "replace f with 0"
+                  } } },
+                  "phase_results": [
+                    { "phase": "Test", "duration": { "secs": 1, "nanos": 0 }, "cargo_result": "Failure" }
+                ] }
+            ] }"#,
+        )
+        .unwrap()
+    }
+
+    /// Check the properties that are required by the SARIF 2.1.0 schema, or that code
+    /// scanning tools need, and their types.
+    #[test]
+    fn sarif_conforms_to_required_schema() {
+        let sarif = sarif_report(&example_record());
+        assert_eq!(sarif["version"], "2.1.0");
+        assert!(sarif["$schema"].as_str().unwrap().contains("sarif-2.1.0"));
+        let runs = sarif["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+        let driver = &runs[0]["tool"]["driver"];
+        assert_eq!(driver["name"], "cargo-mutants");
+        let rule_ids: Vec<&str> = driver["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        for result in runs[0]["results"].as_array().unwrap() {
+            assert!(result["message"]["text"].is_string());
+            assert!(rule_ids.contains(&result["ruleId"].as_str().unwrap()));
+            assert!(
+                ["none", "note", "warning", "error"].contains(&result["level"].as_str().unwrap())
+            );
+            for location in result["locations"].as_array().unwrap() {
+                let physical = &location["physicalLocation"];
+                assert!(physical["artifactLocation"]["uri"].is_string());
+                let region = physical["region"].as_object().unwrap();
+                for key in ["startLine", "startColumn", "endLine", "endColumn"] {
+                    assert!(region[key].as_u64().unwrap() >= 1, "{} is positive", key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_missed_mutants_are_reported() {
+        let sarif = sarif_report(&example_record());
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0]["locations"][0],
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "src/lib.rs", "uriBaseId": "%SRCROOT%" },
+                    "region": { "startLine": 3, "startColumn": 7, "endLine": 3, "endColumn": 8 },
+                },
+                "logicalLocations": [{ "fullyQualifiedName": "Foo::less", "kind": "function" }],
+            })
+        );
+        assert!(results[0]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("replace < with <= in Foo::less"));
+    }
+}
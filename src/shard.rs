@@ -0,0 +1,113 @@
+// Copyright 2022 Martin Pool
+
+//! Split the mutants into shards, for `--shard`, so that a run can be spread across
+//! several machines.
+//!
+//! Each mutant is assigned to a shard by a hash of its name, so that the assignment
+//! doesn't depend on the other mutants: adding a source file or a function doesn't move
+//! existing mutants to other shards. The shards are of nearly, but not exactly, equal
+//! size.
+
+use std::convert::TryInto;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+
+use crate::mutate::Mutation;
+use crate::*;
+
+/// One of `n` shards, numbered from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub k: usize,
+    pub n: usize,
+}
+
+impl Shard {
+    /// True if this mutant belongs in this shard.
+    pub fn contains(&self, mutation: &Mutation) -> bool {
+        let hash = Sha256::digest(mutation.name().as_bytes());
+        let prefix = u64::from_be_bytes(hash[..8].try_into().unwrap());
+        (prefix % self.n as u64) as usize == self.k
+    }
+}
+
+impl FromStr for Shard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let err = || anyhow!("invalid shard {:?}: expected k/n, with k from 0 to n-1", s);
+        let (k, n) = s.split_once('/').ok_or_else(err)?;
+        let k: usize = k.parse().map_err(|_| err())?;
+        let n: usize = n.parse().map_err(|_| err())?;
+        if k >= n {
+            return Err(err());
+        }
+        Ok(Shard { k, n })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.k, self.n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::source::SourceTree;
+
+    #[test]
+    fn parse_shards() {
+        assert_eq!("0/1".parse::<Shard>().unwrap(), Shard { k: 0, n: 1 });
+        assert_eq!("7/8".parse::<Shard>().unwrap(), Shard { k: 7, n: 8 });
+        for bad in ["8/8", "1", "-1/2", "a/b", "0/0", ""] {
+            assert_eq!(
+                bad.parse::<Shard>().unwrap_err().to_string(),
+                format!(
+                    "invalid shard {:?}: expected k/n, with k from 0 to n-1",
+                    bad
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn shards_partition_the_mutants() {
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = SourceTree::new(Path::new("testdata/tree/well_tested"))
+            .unwrap()
+            .discover(&options)
+            .unwrap()
+            .mutations;
+        let n = 4;
+        let sizes: Vec<usize> = (0..n)
+            .map(|k| {
+                mutations
+                    .iter()
+                    .filter(|mutation| Shard { k, n }.contains(mutation))
+                    .count()
+            })
+            .collect();
+        assert_eq!(sizes.iter().sum::<usize>(), mutations.len());
+        for mutation in &mutations {
+            assert_eq!(
+                (0..n)
+                    .filter(|&k| Shard { k, n }.contains(mutation))
+                    .count(),
+                1
+            );
+        }
+        for size in sizes {
+            assert!(size > mutations.len() / n / 2, "uneven shard of {}", size);
+        }
+    }
+}
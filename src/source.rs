@@ -2,16 +2,22 @@
 
 //! Access to a Rust source tree and files.
 
-use std::path::{Path, PathBuf};
-use std::rc::Rc;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
 use path_slash::PathExt;
 use syn::visit::Visit;
 
+use crate::features::{manifest_features, KnownFeatures};
+use crate::in_diff;
 use crate::interrupt::check_interrupted;
-use crate::mutate::Mutation;
-use crate::visit::DiscoveryVisitor;
+use crate::package::{self, Package};
+use crate::visit::{mod_path_attrs, Discovery, DiscoveryVisitor, SkipReason};
+use crate::*;
 
 /// A Rust source file within a source tree.
 ///
@@ -26,7 +32,14 @@ pub struct SourceFile {
     tree_relative: PathBuf,
 
     /// Full copy of the source.
-    pub code: Rc<String>,
+    pub code: Arc<String>,
+
+    /// The workspace package containing this file, if packages were selected.
+    package: Option<String>,
+
+    /// Which features of the package are known to be on and off, if the packages were
+    /// selected and not all features are enabled.
+    features: Option<Arc<KnownFeatures>>,
 }
 
 impl SourceFile {
@@ -40,21 +53,34 @@ impl SourceFile {
             .replace("\r\n", "\n");
         Ok(SourceFile {
             tree_relative: tree_relative.to_owned(),
-            code: Rc::new(code),
+            code: Arc::new(code),
+            package: None,
+            features: None,
         })
     }
 
+    /// Return the name of the workspace package containing this file, if packages were
+    /// selected by `--package`, `--workspace`, or the workspace's default members.
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    /// Return which of the package's features are known to be off, if any.
+    pub fn features(&self) -> Option<&KnownFeatures> {
+        self.features.as_deref()
+    }
+
     /// Return the path of this file relative to the tree root, with forward slashes.
     pub fn tree_relative_slashes(&self) -> String {
         self.tree_relative.to_slash_lossy()
     }
 
     /// Generate a list of all mutation possibilities within this file.
-    pub fn mutations(&self) -> Result<Vec<Mutation>> {
+    pub fn discover(&self, options: &Options) -> Result<Discovery> {
         let syn_file = syn::parse_str::<syn::File>(&self.code)?;
-        let mut v = DiscoveryVisitor::new(self);
+        let mut v = DiscoveryVisitor::new(self, options);
         v.visit_file(&syn_file);
-        Ok(v.mutations)
+        Ok(v.discovery)
     }
 
     /// Return the path of this file relative to a given directory.
@@ -67,11 +93,29 @@ impl SourceFile {
 #[derive(Debug)]
 pub struct SourceTree {
     root: PathBuf,
+
+    /// Directories, relative to the root, that are searched for source files, and the
+    /// package containing each, if packages were selected.
+    source_dirs: Vec<(PathBuf, Option<String>)>,
+
+    /// The known features of each package, or of the package in the root of the tree
+    /// under None, once packages are selected.
+    features: BTreeMap<Option<String>, Arc<KnownFeatures>>,
 }
 
 impl SourceTree {
     pub fn new(root: &Path) -> Result<SourceTree> {
-        if !root.join("Cargo.toml").is_file() {
+        if !root.exists() {
+            return Err(anyhow!(
+                "{} does not exist: specify a crate directory",
+                root.to_slash_lossy()
+            ));
+        } else if !root.is_dir() {
+            return Err(anyhow!(
+                "{} is not a directory: specify a crate directory",
+                root.to_slash_lossy()
+            ));
+        } else if !root.join("Cargo.toml").is_file() {
             return Err(anyhow!(
                 "{} does not contain a Cargo.toml: specify a crate directory",
                 root.to_slash_lossy()
@@ -79,46 +123,386 @@ impl SourceTree {
         }
         Ok(SourceTree {
             root: root.to_owned(),
+            source_dirs: vec![(PathBuf::from("src"), None)],
+            features: BTreeMap::new(),
         })
     }
 
-    /// Return all the mutations that could possibly be applied to this tree.
-    pub fn mutations(&self) -> Result<Vec<Mutation>> {
-        let mut r = Vec::new();
-        for sf in self.source_files() {
+    /// Choose which packages in the workspace to mutate, as cargo chooses which to build:
+    /// all of them with `--workspace`, those named by `--package`, or otherwise, in the
+    /// root of a virtual workspace, its default members.
+    ///
+    /// Packages named by `--exclude-package`, or `exclude_packages` in the config, are
+    /// then left out.
+    ///
+    /// The packages and their source are found from `cargo metadata`. If none of these
+    /// apply, the `src` directory of the package in the root of the tree is mutated, and
+    /// `cargo metadata` isn't run.
+    ///
+    /// Return the names of the selected packages, or an empty list if the package in the
+    /// root is mutated.
+    ///
+    /// The features of the mutated packages that are selected by `--features` and related
+    /// options, or by default, are also worked out from their manifests.
+    pub fn select_packages(&mut self, options: &Options) -> Result<Vec<String>> {
+        let names = &options.packages;
+        let manifest = self.read_manifest()?;
+        let is_virtual_workspace =
+            manifest.get("package").is_none() && manifest.get("workspace").is_some();
+        if !options.workspace && names.is_empty() && !is_virtual_workspace {
+            self.features = options
+                .features
+                .known_features(&manifest_features(&manifest))
+                .map(|known| (None, Arc::new(known)))
+                .into_iter()
+                .collect();
+            return Ok(Vec::new());
+        }
+        let packages = package::workspace_packages(&self.root)?;
+        for excluded in &options.exclude_packages {
+            if !packages.iter().any(|package| &package.name == excluded) {
+                eprintln!(
+                    "warning: excluded package {:?} is not in the workspace",
+                    excluded
+                );
+            }
+        }
+        let mut selected: Vec<&Package> = if options.workspace {
+            packages.iter().collect()
+        } else if !names.is_empty() {
+            names
+                .iter()
+                .map(|name| {
+                    packages
+                        .iter()
+                        .find(|package| &package.name == name)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "package {:?} is not in the workspace: the packages are {}",
+                                name,
+                                packages.iter().map(|package| &package.name).join(", ")
+                            )
+                        })
+                })
+                .collect::<Result<_>>()?
+        } else {
+            packages
+                .iter()
+                .filter(|package| package.default_member)
+                .collect()
+        };
+        selected.retain(|package| !options.exclude_packages.contains(&package.name));
+        self.features = selected
+            .iter()
+            .filter_map(|package| {
+                options
+                    .features
+                    .known_features(&package.features)
+                    .map(|known| (Some(package.name.clone()), Arc::new(known)))
+            })
+            .collect();
+        self.source_dirs = selected
+            .iter()
+            .flat_map(|package| {
+                package
+                    .source_dirs
+                    .iter()
+                    .map(move |dir| (dir.clone(), Some(package.name.clone())))
+            })
+            .collect();
+        Ok(selected
+            .into_iter()
+            .map(|package| package.name.clone())
+            .collect())
+    }
+
+    /// Read and parse the tree's `Cargo.toml`.
+    fn read_manifest(&self) -> Result<toml::Value> {
+        let path = self.root.join("Cargo.toml");
+        fs::read_to_string(&path)
+            .with_context(|| format!("read {}", path.to_slash_lossy()))?
+            .parse()
+            .with_context(|| format!("parse {}", path.to_slash_lossy()))
+    }
+
+    /// Return the source files that will be mutated, as listed by `--list-files`.
+    ///
+    /// If `--file` globs were given, only files matching them are included, and globs that
+    /// match no files are warned about. Files matching `--exclude` globs are not included,
+    /// and with `--in-diff`, neither are files that the diff doesn't change.
+    ///
+    /// `examine_globs` and `exclude_globs` from the config file work the same way, and
+    /// `--file` narrows `examine_globs`: files must match both, if both are given.
+    pub fn examined_files(&self, options: &Options) -> Result<Vec<SourceFile>> {
+        let examine_globs = [
+            ("--file", &options.examine_globs),
+            ("examine_globs", &options.config_examine_globs),
+        ];
+        let exclude_globs = [
+            ("--exclude", &options.exclude_globs),
+            ("exclude_globs", &options.config_exclude_globs),
+        ];
+        if options.verbose {
+            for (option_name, globs) in examine_globs.iter().chain(&exclude_globs) {
+                for glob in globs.iter() {
+                    eprintln!("{} glob {:?}", option_name, glob.glob().glob());
+                }
+            }
+        }
+        let tree_prefix = if options.in_diff.is_some() {
+            in_diff::tree_prefix(&self.root)
+        } else {
+            String::new()
+        };
+        let mut files = Vec::new();
+        let mut glob_matched = examine_globs.map(|(_, globs)| vec![false; globs.len()]);
+        'files: for sf in self.source_files() {
             check_interrupted()?;
-            r.extend(Rc::new(sf).mutations()?);
+            let path = sf.tree_relative_slashes();
+            let mut unmatched = None;
+            for ((option_name, globs), glob_matched) in examine_globs.iter().zip(&mut glob_matched)
+            {
+                let mut matched = globs.is_empty();
+                for (glob, glob_matched) in globs.iter().zip(glob_matched) {
+                    if glob.is_match(&path) {
+                        *glob_matched = true;
+                        matched = true;
+                    }
+                }
+                if !matched && unmatched.is_none() {
+                    unmatched = Some(option_name);
+                }
+            }
+            if let Some(option_name) = unmatched {
+                if options.verbose {
+                    eprintln!("skip {}: doesn't match any {} glob", path, option_name);
+                }
+                continue;
+            }
+            for (option_name, globs) in &exclude_globs {
+                if let Some(glob) = globs.iter().find(|g| g.is_match(&path)) {
+                    if options.verbose {
+                        eprintln!(
+                            "skip {}: matches {} {:?}",
+                            path,
+                            option_name,
+                            glob.glob().glob()
+                        );
+                    }
+                    continue 'files;
+                }
+            }
+            if let Some(in_diff) = &options.in_diff {
+                if !in_diff.touches_file(&path, &tree_prefix) {
+                    continue;
+                }
+            }
+            files.push(sf);
+        }
+        for ((option_name, globs), glob_matched) in examine_globs.iter().zip(glob_matched) {
+            for (glob, matched) in globs.iter().zip(glob_matched) {
+                if !matched {
+                    eprintln!(
+                        "warning: {} {:?} did not match any source files",
+                        option_name,
+                        glob.glob().glob()
+                    );
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Return all the mutations that could possibly be applied to the files returned by
+    /// [SourceTree::examined_files].
+    ///
+    /// With `--function`, only mutants of the named functions are kept, and a warning is
+    /// printed for each name that matches no function, since it's probably a typo.
+    ///
+    /// If `--re` regexps were given, only mutants whose names match one of them are kept,
+    /// and then those matching `--exclude-re` are dropped: both are counted as skipped.
+    /// With `--in-diff`, only mutants of the changed lines are kept.
+    ///
+    /// Finally, with `--shard`, only the mutants in that shard are kept.
+    pub fn discover(&self, options: &Options) -> Result<Discovery> {
+        let mut r = Discovery::default();
+        for sf in self.examined_files(options)? {
+            check_interrupted()?;
+            r.extend(Arc::new(sf).discover(options)?);
+        }
+        if !options.examine_functions.is_empty() {
+            for glob in &options.examine_functions {
+                if !r
+                    .mutations
+                    .iter()
+                    .any(|mutation| glob.is_match(mutation.function_name()))
+                {
+                    eprintln!(
+                        "warning: --function {:?} matched no functions",
+                        glob.glob().glob()
+                    );
+                }
+            }
+            let before = r.mutations.len();
+            r.mutations.retain(|mutation| {
+                options
+                    .examine_functions
+                    .iter()
+                    .any(|glob| glob.is_match(mutation.function_name()))
+            });
+            let not_matching = before - r.mutations.len();
+            if not_matching > 0 {
+                *r.skipped
+                    .entry(SkipReason::NotMatchingFunction)
+                    .or_default() += not_matching;
+            }
+        }
+        if let Some(in_diff) = &options.in_diff {
+            let tree_prefix = in_diff::tree_prefix(&self.root);
+            let before = r.mutations.len();
+            r.mutations
+                .retain(|mutation| in_diff.touches_mutation(mutation, &tree_prefix));
+            let not_in_diff = before - r.mutations.len();
+            if not_in_diff > 0 {
+                *r.skipped.entry(SkipReason::NotInDiff).or_default() += not_in_diff;
+            }
+        }
+        if !options.examine_names.is_empty() || !options.exclude_names.is_empty() {
+            let mut not_matching = 0;
+            let mut excluded = 0;
+            r.mutations.retain(|mutation| {
+                let name = mutation.name();
+                if !options.examine_names.is_empty()
+                    && !options.examine_names.iter().any(|re| re.is_match(&name))
+                {
+                    not_matching += 1;
+                    false
+                } else if options.exclude_names.iter().any(|re| re.is_match(&name)) {
+                    excluded += 1;
+                    false
+                } else {
+                    true
+                }
+            });
+            for (reason, count) in [
+                (SkipReason::NotMatchingRe, not_matching),
+                (SkipReason::MatchingExcludeRe, excluded),
+            ] {
+                if count > 0 {
+                    *r.skipped.entry(reason).or_default() += count;
+                }
+            }
+        }
+        if let Some(shard) = &options.shard {
+            let before = r.mutations.len();
+            r.mutations.retain(|mutation| shard.contains(mutation));
+            let other_shards = before - r.mutations.len();
+            if other_shards > 0 {
+                *r.skipped.entry(SkipReason::OtherShard).or_default() += other_shards;
+            }
         }
         Ok(r)
     }
 
-    /// Return an iterator of `src/**/*.rs` paths relative to the root.
+    /// Return an iterator of `src/**/*.rs` paths relative to the root, or of the source
+    /// directories of the selected packages, and of files named by `#[path]` attributes
+    /// on `mod` declarations in them.
+    ///
+    /// Module paths that don't exist, or that are outside of the tree and so can't be
+    /// mutated in a copy of it, are warned about and skipped.
     pub fn source_files(&self) -> impl Iterator<Item = SourceFile> + '_ {
-        walkdir::WalkDir::new(self.root.join("src"))
-            .sort_by_file_name()
-            .into_iter()
-            .filter_map(|r| {
-                r.map_err(|err| eprintln!("error walking source tree: {:?}", err))
-                    .ok()
-            })
-            .filter(|entry| entry.file_type().is_file())
-            .map(|entry| entry.into_path())
-            .filter(|path| {
-                path.extension()
-                    .map_or(false, |p| p.eq_ignore_ascii_case("rs"))
-            })
-            .filter_map(move |full_path| {
-                let tree_relative = full_path.strip_prefix(&self.root).unwrap();
-                SourceFile::new(&self.root, tree_relative)
-                    .map_err(|err| {
+        let mut seen: BTreeSet<PathBuf> = BTreeSet::new();
+        let mut files: Vec<SourceFile> = self
+            .src_dir_files()
+            .filter(|f| seen.insert(f.tree_relative.clone()))
+            .collect();
+        let mut i = 0;
+        while i < files.len() {
+            let mod_paths = match syn::parse_str::<syn::File>(&files[i].code) {
+                Ok(syn_file) => mod_path_attrs(&syn_file),
+                Err(_) => Vec::new(), // reported when the file is discovered
+            };
+            let dir = files[i]
+                .tree_relative
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .to_owned();
+            for mod_path in mod_paths {
+                let tree_relative = match normalize_relative_path(&dir.join(&mod_path)) {
+                    Some(tree_relative) => tree_relative,
+                    None => {
                         eprintln!(
-                            "error reading source {}: {}",
-                            full_path.to_slash_lossy(),
-                            err
+                            "warning: module path {} in {} is outside the source tree, and won't be mutated",
+                            mod_path.to_slash_lossy(),
+                            files[i].tree_relative_slashes()
                         );
-                    })
-                    .ok()
-            })
+                        continue;
+                    }
+                };
+                if !seen.insert(tree_relative.clone()) {
+                    continue;
+                }
+                if !self.root.join(&tree_relative).is_file() {
+                    eprintln!(
+                        "warning: module path {} in {} does not exist",
+                        mod_path.to_slash_lossy(),
+                        files[i].tree_relative_slashes()
+                    );
+                    continue;
+                }
+                match SourceFile::new(&self.root, &tree_relative) {
+                    Ok(mut source_file) => {
+                        source_file.package = files[i].package.clone();
+                        source_file.features = files[i].features.clone();
+                        files.push(source_file)
+                    }
+                    Err(err) => eprintln!(
+                        "error reading source {}: {}",
+                        tree_relative.to_slash_lossy(),
+                        err
+                    ),
+                }
+            }
+            i += 1;
+        }
+        files.into_iter()
+    }
+
+    /// Return an iterator of `**/*.rs` paths in the source directories, relative to the
+    /// root.
+    fn src_dir_files(&self) -> impl Iterator<Item = SourceFile> + '_ {
+        self.source_dirs.iter().flat_map(move |(dir, package)| {
+            walkdir::WalkDir::new(self.root.join(dir))
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(|r| {
+                    r.map_err(|err| eprintln!("error walking source tree: {:?}", err))
+                        .ok()
+                })
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|p| p.eq_ignore_ascii_case("rs"))
+                })
+                .filter_map(move |full_path| {
+                    let tree_relative = full_path.strip_prefix(&self.root).unwrap();
+                    SourceFile::new(&self.root, tree_relative)
+                        .map(|mut source_file| {
+                            source_file.package = package.clone();
+                            source_file.features = self.features.get(package).cloned();
+                            source_file
+                        })
+                        .map_err(|err| {
+                            eprintln!(
+                                "error reading source {}: {}",
+                                full_path.to_slash_lossy(),
+                                err
+                            );
+                        })
+                        .ok()
+                })
+        })
     }
 
     /// Return the path (possibly relative) to the root of the source tree.
@@ -127,6 +511,25 @@ impl SourceTree {
     }
 }
 
+/// Remove `.` and `..` components from a relative path, or return None if it goes above
+/// the directory it's relative to.
+fn normalize_relative_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::CurDir => (),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
 #[cfg(test)]
 mod test {
     use std::fs::File;
@@ -149,6 +552,41 @@ mod test {
         );
     }
 
+    #[test]
+    fn source_files_include_mod_path_attributes() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/net")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"paths\"\n").unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "#[path = \"../generated/foo.rs\"]\nmod foo;\n\
+            #[cfg_attr(unix, path = \"../generated/unix.rs\")]\nmod os;\n\
+            #[path = \"missing.rs\"]\nmod missing;\n\
+            #[path = \"../../outside.rs\"]\nmod outside;\n\
+            mod net {\n    #[path = \"tcp_impl.rs\"]\n    mod tcp;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("generated/foo.rs"), "fn foo() {}\n").unwrap();
+        std::fs::write(root.join("generated/unix.rs"), "fn unix() {}\n").unwrap();
+        std::fs::write(root.join("src/net/tcp_impl.rs"), "fn tcp() {}\n").unwrap();
+        let paths: Vec<String> = SourceTree::new(root)
+            .unwrap()
+            .source_files()
+            .map(|sf| sf.tree_relative_slashes())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "src/lib.rs",
+                "src/net/tcp_impl.rs",
+                "generated/foo.rs",
+                "generated/unix.rs"
+            ]
+        );
+    }
+
     #[test]
     fn error_opening_subdirectory_of_crate() {
         let result = SourceTree::new(Path::new("testdata/tree/factorial/src"));
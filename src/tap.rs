@@ -0,0 +1,64 @@
+// Copyright 2022 Martin Pool
+
+//! Results in [TAP](https://testanything.org/tap-version-13-specification.html), the
+//! Test Anything Protocol, with each mutant as a test point.
+
+use crate::mutate::Mutation;
+use crate::outcome::MutantStatus;
+
+/// Return the TAP plan line, announcing how many test points will follow.
+pub fn plan(n_mutations: usize) -> String {
+    format!("1..{}", n_mutations)
+}
+
+/// Return a TAP test point for a mutant, numbered from 1.
+pub fn test_point(number: usize, mutation: &Mutation, status: MutantStatus) -> String {
+    let (ok, directive) = match status {
+        MutantStatus::Caught => ("ok", "caught"),
+        MutantStatus::Missed => ("not ok", "NOT CAUGHT"),
+        MutantStatus::Unviable => ("ok", "SKIP unviable"),
+        MutantStatus::Timeout => ("not ok", "TIMEOUT"),
+        MutantStatus::BuildTimeout => ("not ok", "BUILD TIMEOUT"),
+        MutantStatus::Untested => ("ok", "SKIP not tested"),
+    };
+    format!(
+        "{} {} - {} {} # {}",
+        ok,
+        number,
+        mutation.describe_location(),
+        escape_description(&mutation.describe_change()),
+        directive
+    )
+}
+
+/// Return text as TAP comments, with each line starting with `#`, and without colors.
+pub fn comment(text: &str) -> String {
+    ::console::strip_ansi_codes(text)
+        .lines()
+        .map(|line| format!("# {}\n", line))
+        .collect()
+}
+
+/// Escape `#` in a test point description, so it's not taken as a directive.
+fn escape_description(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn comments() {
+        assert_eq!(comment("one\ntwo\n"), "# one\n# two\n");
+        assert_eq!(comment("\u{1b}[31mred\u{1b}[0m"), "# red\n");
+    }
+
+    #[test]
+    fn descriptions_are_escaped() {
+        assert_eq!(
+            escape_description("replace f with #[foo] \\"),
+            "replace f with \\#[foo] \\\\"
+        );
+    }
+}
@@ -2,10 +2,10 @@
 
 //! Edit source code.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// A (line, column) position in a source file.
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct LineColumn {
     /// 1-based line number.
     pub line: usize,
@@ -26,7 +26,7 @@ impl From<proc_macro2::LineColumn> for LineColumn {
 /// A contiguous text span in a file.
 ///
 /// TODO: Perhaps a semi-open range that can represent an empty span would be more general?
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Span {
     /// The inclusive position where the span starts.
     pub start: LineColumn,
@@ -88,6 +88,29 @@ pub(crate) fn replace_region(
     r
 }
 
+/// Return the text of a subregion, between `start` and `end` inclusive.
+pub(crate) fn extract_region(s: &str, start: &LineColumn, end: &LineColumn) -> String {
+    let mut r = String::new();
+    let mut line_no = 1;
+    let mut col_no = 1;
+    for c in s.chars() {
+        let before_start = line_no < start.line || (line_no == start.line && col_no < start.column);
+        let after_end = line_no > end.line || (line_no == end.line && col_no > end.column);
+        if !before_start && !after_end {
+            r.push(c);
+        }
+        if c == '\n' {
+            line_no += 1;
+            col_no = 1;
+        } else if c == '\r' {
+            // counts as part of the last column, not a separate column
+        } else {
+            col_no += 1;
+        }
+    }
+    r
+}
+
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
@@ -163,4 +186,20 @@ const BAR: u32 = 69;
 "
         );
     }
+
+    #[test]
+    fn extract_multi_line_region() {
+        let source = "fn foo() {\n    some();\n}\n";
+        assert_eq!(
+            extract_region(
+                source,
+                &LineColumn {
+                    line: 1,
+                    column: 10
+                },
+                &LineColumn { line: 3, column: 1 },
+            ),
+            "{\n    some();\n}"
+        );
+    }
 }
@@ -4,48 +4,287 @@
 //!
 //! Knowledge of the syn API is localized here.
 
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
 use quote::ToTokens;
+use serde::Serialize;
 use syn::visit::Visit;
 use syn::Attribute;
 use syn::ItemFn;
 
-use crate::mutate::{Mutation, MutationOp};
+use crate::features::KnownFeatures;
+use crate::fnvalue::{
+    path_ends_with, replacement_is_const, return_type_replacements, type_replacements,
+};
+use crate::mutate::{Genre, Mutation};
+use crate::operator::{
+    binary_operator_replacements, bool_literal_replacement, field_literal_replacements,
+    if_condition_replacements, int_literal_replacements, method_name_replacements,
+    range_limits_replacements, should_delete_not, string_literal_replacement, BOOL_CLOSURE_METHODS,
+    DEFAULT_MAX_LITERAL_MUTANTS,
+};
 use crate::source::SourceFile;
+use crate::textedit::{extract_region, LineColumn, Span};
+use crate::*;
 
-/// `syn` visitor that recursively traverses the syntax tree, accumulating places that could be mutated.
-pub struct DiscoveryVisitor<'sf> {
-    /// All the mutations generated by visiting the file.
+/// Functions and methods whose calls aren't deleted unless the config says otherwise,
+/// because deleting them is rarely interesting: for example, deleting `drop` just delays
+/// it, and deleting `with_capacity` changes nothing observable.
+pub const DEFAULT_SKIP_CALLS: &[&str] = &["drop", "lock", "with_capacity"];
+
+/// The most characters of an `if` condition that are shown in the description of a
+/// mutant swapping its branches; longer conditions are cut short with `…`.
+const MAX_DESCRIBED_CONDITION: usize = 50;
+
+/// Why a function, or some other item, was not mutated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum SkipReason {
+    /// The item is only built in tests, because of a `#[cfg(test)]` attribute.
+    CfgTest,
+    /// The item is only built with a feature that's off, because of a
+    /// `#[cfg(feature = "...")]` attribute.
+    FeatureOff,
+    /// The item has a `#[mutants::skip]` attribute.
+    MutantsSkip,
+    /// The function's name matches an `exclude_functions` regexp in the config.
+    ExcludedByConfig,
+
+    /// There's no replacement value known to be of the function's return type.
+    NoViableReplacement,
+
+    /// The function returns `impl Trait` for a trait with no known implementation.
+    UnknownImplTrait,
+
+    /// The function returns a type parameter, or `Self`, that's not known to implement
+    /// `Default`.
+    GenericWithoutDefault,
+
+    /// The mutant's lines aren't added or changed by the `--in-diff` diff.
+    NotInDiff,
+
+    /// The mutant's function doesn't match any `--function` glob.
+    NotMatchingFunction,
+
+    /// The mutant's name doesn't match any `--re` or `examine_re` regexp.
+    NotMatchingRe,
+
+    /// The mutant's name matches an `--exclude-re` or `exclude_re` regexp.
+    MatchingExcludeRe,
+
+    /// The mutant is in a different `--shard`.
+    OtherShard,
+
+    /// The function isn't public, and `--only-pub` was given.
+    NotPub,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::CfgTest => "only built in tests",
+            SkipReason::FeatureOff => "only built with features that are off",
+            SkipReason::MutantsSkip => "marked #[mutants::skip]",
+            SkipReason::ExcludedByConfig => "excluded by config",
+            SkipReason::NoViableReplacement => "no viable replacement",
+            SkipReason::UnknownImplTrait => "returns impl of unknown trait",
+            SkipReason::GenericWithoutDefault => "returns generic type without Default",
+            SkipReason::NotInDiff => "not changed in --in-diff",
+            SkipReason::NotMatchingFunction => "not in --function",
+            SkipReason::NotMatchingRe => "don't match --re",
+            SkipReason::MatchingExcludeRe => "match --exclude-re",
+            SkipReason::OtherShard => "in other shards",
+            SkipReason::NotPub => "not pub",
+        })
+    }
+}
+
+impl SkipReason {
+    /// Name the kind of things skipped for this reason.
+    pub fn noun(&self, count: usize) -> &'static str {
+        let (one, many) = match self {
+            SkipReason::CfgTest | SkipReason::FeatureOff | SkipReason::MutantsSkip => {
+                ("item", "items")
+            }
+            SkipReason::NotInDiff
+            | SkipReason::NotMatchingFunction
+            | SkipReason::NotMatchingRe
+            | SkipReason::MatchingExcludeRe
+            | SkipReason::OtherShard => ("mutant", "mutants"),
+            _ => ("function", "functions"),
+        };
+        if count == 1 {
+            one
+        } else {
+            many
+        }
+    }
+}
+
+/// Which functions are mutated, with `--only-pub` or `--only-pub-crate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnlyPub {
+    /// Only `pub` functions.
+    Pub,
+    /// `pub` and `pub(crate)` functions.
+    Crate,
+}
+
+impl OnlyPub {
+    /// True if an item with this visibility is public enough to be mutated.
+    fn admits(self, vis: &syn::Visibility) -> bool {
+        match vis {
+            syn::Visibility::Public(_) => true,
+            syn::Visibility::Crate(_) => self == OnlyPub::Crate,
+            syn::Visibility::Restricted(restricted) => {
+                self == OnlyPub::Crate
+                    && restricted.in_token.is_none()
+                    && restricted.path.is_ident("crate")
+            }
+            syn::Visibility::Inherited => false,
+        }
+    }
+}
+
+/// The mutations found by visiting some source, and counts of functions that were skipped.
+#[derive(Debug, Default)]
+pub struct Discovery {
     pub mutations: Vec<Mutation>,
+    pub skipped: BTreeMap<SkipReason, usize>,
+}
+
+impl Discovery {
+    /// Add in the results of visiting more source.
+    pub fn extend(&mut self, other: Discovery) {
+        self.mutations.extend(other.mutations);
+        for (reason, count) in other.skipped {
+            *self.skipped.entry(reason).or_default() += count;
+        }
+    }
+}
+
+/// `syn` visitor that recursively traverses the syntax tree, accumulating places that could be mutated.
+pub struct DiscoveryVisitor<'sf, 'o> {
+    /// All the mutations generated by visiting the file, and the functions skipped.
+    pub discovery: Discovery,
 
     /// The file being visited.
     source_file: &'sf SourceFile,
 
+    /// Options controlling which mutations are generated.
+    options: &'o Options,
+
     /// The stack of namespaces we're currently inside.
     namespace_stack: Vec<String>,
+
+    /// The name of the function whose body is being visited, if any.
+    function_name: Option<String>,
+
+    /// The span of the body of the function being visited, if any.
+    function_span: Option<Span>,
+
+    /// True while visiting the condition of an `if` or `while`.
+    in_condition: bool,
+
+    /// True while visiting a statement with a `#[cfg]` attribute, which might not be built.
+    in_cfg_statement: bool,
+
+    /// The number of literal mutants generated so far in the current function.
+    literal_mutant_count: usize,
+
+    /// Names of the types in this file that derive or implement `Default`.
+    default_types: BTreeSet<String>,
+
+    /// The fields of structs defined in this file, by name.
+    struct_fields: BTreeMap<String, syn::Fields>,
+
+    /// The `impl` block whose items are being visited, if any.
+    impl_context: Option<ImplContext>,
+
+    /// False within a module or trait that's not public, with `--only-pub`.
+    in_pub_scope: bool,
+}
+
+/// What's known about the `impl` block being visited, so that its methods can return
+/// `Self` or its type parameters.
+struct ImplContext {
+    generics: syn::Generics,
+
+    /// True if the type of `Self` is known to implement `Default`.
+    self_is_default: bool,
+
+    /// True if this is `impl Default for ...`.
+    is_default_impl: bool,
+
+    /// True if this implements a trait, so that its methods have no visibility of their
+    /// own.
+    is_trait_impl: bool,
+
+    /// An expression to return from methods returning `Self`, when `Self` isn't known to
+    /// implement `Default`.
+    self_constructor: Option<String>,
 }
 
-impl<'sf> DiscoveryVisitor<'sf> {
-    pub fn new(source_file: &'sf SourceFile) -> DiscoveryVisitor<'sf> {
+impl<'sf, 'o> DiscoveryVisitor<'sf, 'o> {
+    pub fn new(source_file: &'sf SourceFile, options: &'o Options) -> DiscoveryVisitor<'sf, 'o> {
         DiscoveryVisitor {
             source_file,
-            mutations: Vec::new(),
+            options,
+            discovery: Discovery::default(),
             namespace_stack: Vec::new(),
+            function_name: None,
+            function_span: None,
+            in_condition: false,
+            in_cfg_statement: false,
+            literal_mutant_count: 0,
+            default_types: BTreeSet::new(),
+            struct_fields: BTreeMap::new(),
+            impl_context: None,
+            in_pub_scope: true,
         }
     }
 
-    fn collect_fn_mutations(
-        &mut self,
-        ident: &syn::Ident,
-        return_type: &syn::ReturnType,
-        span: &proc_macro2::Span,
-    ) {
-        self.in_namespace(&ident.to_string(), |v| {
+    fn collect_fn_mutations(&mut self, sig: &syn::Signature, span: &proc_macro2::Span) {
+        let return_type = &sig.output;
+        let generics = &sig.generics;
+        self.in_namespace(&sig.ident.to_string(), |v| {
+            // Replacing the body of a function called through an unsafe foreign ABI is
+            // unlikely to mean anything that tests could catch.
+            if sig.unsafety.is_some() && sig.abi.is_some() {
+                v.skip(SkipReason::NoViableReplacement);
+                return;
+            }
             let function_name = v.namespace_stack.join("::");
             let return_type_str = format!("{}", return_type.to_token_stream());
-            for op in ops_for_return_type(return_type) {
-                v.mutations.push(Mutation::new(
+            let mut replacements = if let Some(rep) = v.self_constructor(return_type) {
+                vec![rep]
+            } else {
+                match v.generic_return_type_is_default(return_type, generics) {
+                    Some(true) => vec!["Default::default()".to_owned()],
+                    Some(false) => {
+                        v.skip(SkipReason::GenericWithoutDefault);
+                        return;
+                    }
+                    None => return_type_replacements(return_type, v.options),
+                }
+            };
+            // Other replacements would never build inside a `const fn`.
+            if sig.constness.is_some() {
+                replacements.retain(|rep| replacement_is_const(rep));
+            }
+            if replacements.is_empty() {
+                v.skip(match return_type {
+                    syn::ReturnType::Type(_, ty) if matches!(**ty, syn::Type::ImplTrait(_)) => {
+                        SkipReason::UnknownImplTrait
+                    }
+                    _ => SkipReason::NoViableReplacement,
+                });
+            }
+            for replacement in replacements {
+                v.discovery.mutations.push(Mutation::new(
                     v.source_file.clone(),
-                    op,
+                    replacement,
                     function_name.clone(),
                     return_type_str.clone(),
                     span.into(),
@@ -54,6 +293,275 @@ impl<'sf> DiscoveryVisitor<'sf> {
         });
     }
 
+    /// If the function returns one of its own or its impl's type parameters, or `Self`,
+    /// return whether that type is known to implement `Default`.
+    ///
+    /// Type parameters implement `Default` if it's in their inline or where-clause bounds.
+    /// `Self` does if the type derives or implements `Default` in the same file.
+    fn generic_return_type_is_default(
+        &self,
+        return_type: &syn::ReturnType,
+        generics: &syn::Generics,
+    ) -> Option<bool> {
+        let ident = match return_type {
+            syn::ReturnType::Type(_, ty) => match &**ty {
+                syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident()?,
+                _ => return None,
+            },
+            syn::ReturnType::Default => return None,
+        };
+        let impl_generics = self.impl_context.as_ref().map(|c| &c.generics);
+        if ident == "Self" {
+            return self.impl_context.as_ref().map(|c| c.self_is_default);
+        }
+        std::iter::once(generics)
+            .chain(impl_generics)
+            .find(|g| g.type_params().any(|tp| tp.ident == *ident))
+            .map(|g| type_param_has_default_bound(g, ident))
+    }
+
+    /// If the function returns `Self`, within an `impl` where `Self` isn't known to be
+    /// `Default`, return an expression to construct it.
+    fn self_constructor(&self, return_type: &syn::ReturnType) -> Option<String> {
+        match return_type {
+            syn::ReturnType::Type(_, ty) if type_last_ident(ty).as_deref() == Some("Self") => {
+                self.impl_context.as_ref()?.self_constructor.clone()
+            }
+            _ => None,
+        }
+    }
+
+    /// Return a struct expression building `Self` from the first replacement for each of
+    /// its fields, like `Self { count: 0, name: "".into() }`, if the struct is defined in
+    /// this file and all its fields have replacements.
+    fn self_struct_literal(&self, self_ty: &syn::Type) -> Option<String> {
+        let fields = self.struct_fields.get(&type_last_ident(self_ty)?)?;
+        let values = fields
+            .iter()
+            .map(|field| {
+                type_replacements(&field.ty, self.options)
+                    .into_iter()
+                    .next()
+            })
+            .collect::<Option<Vec<String>>>()?;
+        Some(match fields {
+            syn::Fields::Named(_) => format!(
+                "Self {{ {} }}",
+                fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| format!("{}: {}", field.ident.as_ref().unwrap(), value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            syn::Fields::Unnamed(_) => format!("Self({})", values.join(", ")),
+            syn::Fields::Unit => "Self".to_owned(),
+        })
+    }
+
+    /// Add mutations of the literals in the struct expression at the end of a block, like
+    /// `Config { retries: 3, name: "main".into() }`.
+    ///
+    /// These stand in for replacing the body of `Default::default`, so like function
+    /// value mutants they're generated even with `--no-operators`.
+    fn collect_field_literal_mutations(&mut self, block: &syn::Block) {
+        if let Some(syn::Stmt::Expr(syn::Expr::Struct(expr_struct))) = block.stmts.last() {
+            for field in &expr_struct.fields {
+                if let Some(lit) = field_literal(&field.expr) {
+                    if (self.options.mutate_literals && matches!(lit, syn::Lit::Int(_)))
+                        || (self.options.mutate_strings && matches!(lit, syn::Lit::Str(_)))
+                    {
+                        continue; // It'll be mutated like any other literal.
+                    }
+                    self.collect_expression_mutations(
+                        Genre::Literal,
+                        lit,
+                        &field_literal_replacements(lit),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Add mutations replacing the tokens of `original` with each of the replacements,
+    /// if we're inside a function.
+    fn collect_expression_mutations<T: ToTokens>(
+        &mut self,
+        genre: Genre,
+        original: &T,
+        replacements: &[impl AsRef<str>],
+    ) {
+        let original_str = original.to_token_stream().to_string();
+        self.collect_span_mutations(genre, tokens_span(original), original_str, replacements);
+    }
+
+    /// Add mutations replacing the given span, described as `original`, if we're inside
+    /// a function.
+    fn collect_span_mutations(
+        &mut self,
+        genre: Genre,
+        span: Span,
+        original: String,
+        replacements: &[impl AsRef<str>],
+    ) {
+        let (function_name, function_span) = match (&self.function_name, self.function_span) {
+            (Some(function_name), Some(function_span)) => (function_name.clone(), function_span),
+            _ => return,
+        };
+        for replacement in replacements {
+            self.discovery.mutations.push(Mutation::new_expression(
+                self.source_file.clone(),
+                genre,
+                function_name.clone(),
+                original.clone(),
+                replacement.as_ref().to_owned(),
+                span,
+                function_span,
+            ));
+        }
+    }
+
+    /// Return the source text between two positions, inclusive, on one line, to describe
+    /// a mutation as it's written rather than as syn would print it.
+    fn source_text(&self, start: LineColumn, end: LineColumn) -> String {
+        extract_region(&self.source_file.code, &start, &end)
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Add a mutation that swaps the `if` and `else` blocks, keeping their original text.
+    fn collect_swap_branches(&mut self, expr_if: &syn::ExprIf, else_block: &syn::ExprBlock) {
+        let then_span = tokens_span(&expr_if.then_branch);
+        let else_span = tokens_span(else_block);
+        let cond_span = tokens_span(&expr_if.cond);
+        let code = &self.source_file.code;
+        let replacement = format!(
+            "{} else {}",
+            extract_region(code, &else_span.start, &else_span.end),
+            extract_region(code, &then_span.start, &then_span.end),
+        );
+        self.collect_span_mutations(
+            Genre::SwapBranches,
+            Span {
+                start: then_span.start,
+                end: else_span.end,
+            },
+            shorten(
+                self.source_text(cond_span.start, cond_span.end),
+                MAX_DESCRIBED_CONDITION,
+            ),
+            &[replacement],
+        );
+    }
+
+    /// Visit the body of a function, after its name has been pushed onto the namespace stack.
+    fn in_function<F>(&mut self, body: &syn::Block, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let outer = self.function_name.replace(self.namespace_stack.join("::"));
+        let outer_span = self.function_span.replace(body.brace_token.span.into());
+        let outer_literal_count = std::mem::take(&mut self.literal_mutant_count);
+        f(self);
+        self.function_name = outer;
+        self.function_span = outer_span;
+        self.literal_mutant_count = outer_literal_count;
+    }
+
+    /// Visit the condition of an `if` or `while`, unless it's an `if let` or `while let`.
+    ///
+    /// A literal condition, like `while true`, is not visited, because it's either already
+    /// mutated by [if_condition_replacements], or would make a loop run forever or never.
+    fn visit_condition(&mut self, cond: &syn::Expr) {
+        if matches!(cond, syn::Expr::Lit(_)) {
+            return;
+        }
+        let outer = self.in_condition;
+        self.in_condition = !matches!(cond, syn::Expr::Let(_));
+        self.visit_expr(cond);
+        self.in_condition = outer;
+    }
+
+    /// Count a function that was not mutated.
+    fn skip(&mut self, reason: SkipReason) {
+        *self.discovery.skipped.entry(reason).or_default() += 1;
+    }
+
+    /// True if any of the attrs indicate that we should skip this node and everything
+    /// inside it. Items skipped because they're only built in tests, or because they're
+    /// marked `#[mutants::skip]`, are counted.
+    fn attrs_excluded(&mut self, attrs: &[Attribute]) -> bool {
+        if attrs.iter().any(attr_is_test) {
+            true
+        } else if attrs.iter().any(attr_is_mutants_skip) {
+            self.skip(SkipReason::MutantsSkip);
+            true
+        } else if !self.options.mutate_cfg_test && attrs.iter().any(attr_is_cfg_test) {
+            self.skip(SkipReason::CfgTest);
+            true
+        } else if self.source_file.features().is_some_and(|features| {
+            attrs
+                .iter()
+                .any(|attr| attr_requires_off_feature(attr, features))
+        }) {
+            self.skip(SkipReason::FeatureOff);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True if the function with this name, within the current namespace, matches any
+    /// `exclude_functions` regexp from the config, and so shouldn't be mutated.
+    fn name_excluded(&mut self, ident: &syn::Ident) -> bool {
+        if self.options.exclude_functions.is_empty() {
+            return false;
+        }
+        let name = self.in_namespace(&ident.to_string(), |v| v.namespace_stack.join("::"));
+        if self
+            .options
+            .exclude_functions
+            .iter()
+            .any(|re| re.is_match(&name))
+        {
+            self.skip(SkipReason::ExcludedByConfig);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True if an item with this visibility is public enough to be mutated with
+    /// `--only-pub`, regardless of the scope it's in.
+    fn vis_is_pub(&self, vis: &syn::Visibility) -> bool {
+        self.options
+            .only_pub
+            .is_none_or(|only_pub| only_pub.admits(vis))
+    }
+
+    /// True if a function should be mutated with `--only-pub`, given its visibility, or
+    /// None for methods of traits and trait impls, which are as public as their trait.
+    /// Functions that aren't are skipped and counted.
+    ///
+    /// Functions within other functions, or within private modules or traits, are never
+    /// public. The visibility of the trait of an impl isn't known, so its methods are
+    /// taken to be public.
+    fn fn_is_pub(&mut self, vis: Option<&syn::Visibility>) -> bool {
+        if self.options.only_pub.is_none() {
+            return true;
+        }
+        if self.in_pub_scope
+            && self.function_name.is_none()
+            && vis.is_none_or(|vis| self.vis_is_pub(vis))
+        {
+            true
+        } else {
+            self.skip(SkipReason::NotPub);
+            false
+        }
+    }
+
     /// Call a function with a namespace pushed onto the stack.
     ///
     /// This is used when recursively descending into a namespace.
@@ -68,74 +576,700 @@ impl<'sf> DiscoveryVisitor<'sf> {
     }
 }
 
-impl<'ast, 'sf> Visit<'ast> for DiscoveryVisitor<'sf> {
+impl<'ast, 'sf, 'o> Visit<'ast> for DiscoveryVisitor<'sf, 'o> {
     fn visit_item_fn(&mut self, i: &'ast ItemFn) {
         // TODO: Filter out more inapplicable fns.
-        if attrs_excluded(&i.attrs) {
+        if self.attrs_excluded(&i.attrs)
+            || self.name_excluded(&i.sig.ident)
+            || !self.fn_is_pub(Some(&i.vis))
+        {
             return; // don't look inside it either
         }
-        self.collect_fn_mutations(&i.sig.ident, &i.sig.output, &i.block.brace_token.span);
+        self.collect_fn_mutations(&i.sig, &i.block.brace_token.span);
+        if block_is_replaced_literal(&i.block) {
+            return; // it's already mutated as a whole
+        }
         self.in_namespace(&i.sig.ident.to_string(), |v| {
-            syn::visit::visit_item_fn(v, i);
+            v.in_function(&i.block, |v| syn::visit::visit_item_fn(v, i));
         });
     }
 
     /// Visit `impl Foo { ...}` or `impl Debug for Foo { ... }`.
     fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
-        if attrs_excluded(&i.attrs) {
+        if self.attrs_excluded(&i.attrs) {
             return;
         }
         // Make an approximately-right namespace.
         let name = type_name_string(&i.self_ty);
+        let self_is_default =
+            type_last_ident(&i.self_ty).is_some_and(|ident| self.default_types.contains(&ident));
+        // If `Self` can't be built from its fields, `Default::default()` is still tried,
+        // although it may be unviable.
+        let self_constructor = if self_is_default {
+            None
+        } else {
+            Some(
+                self.self_struct_literal(&i.self_ty)
+                    .unwrap_or_else(|| "Default::default()".to_owned()),
+            )
+        };
+        let context = ImplContext {
+            generics: i.generics.clone(),
+            self_is_default,
+            is_default_impl: matches!(&i.trait_, Some((None, path, _))
+                if path_ends_with(path, "Default")),
+            is_trait_impl: i.trait_.is_some(),
+            self_constructor,
+        };
+        let outer_context = self.impl_context.replace(context);
         self.in_namespace(&name, |v| syn::visit::visit_item_impl(v, i));
+        self.impl_context = outer_context;
+    }
+
+    /// Visit `trait Foo { ... }`, to find default method bodies.
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        // Within the trait, `Self` is whatever type implements it.
+        let context = ImplContext {
+            generics: i.generics.clone(),
+            self_is_default: i.supertraits.iter().any(|bound| {
+                matches!(bound, syn::TypeParamBound::Trait(tb)
+                    if path_ends_with(&tb.path, "Default"))
+            }),
+            is_default_impl: false,
+            is_trait_impl: false,
+            self_constructor: None,
+        };
+        let outer_context = self.impl_context.replace(context);
+        let outer_pub_scope = self.in_pub_scope;
+        self.in_pub_scope = outer_pub_scope && self.vis_is_pub(&i.vis);
+        self.in_namespace(&i.ident.to_string(), |v| syn::visit::visit_item_trait(v, i));
+        self.in_pub_scope = outer_pub_scope;
+        self.impl_context = outer_context;
+    }
+
+    /// Visit a method with a default body within a `trait`.
+    ///
+    /// Methods without a body are just declarations, and there's nothing to mutate.
+    fn visit_trait_item_method(&mut self, i: &'ast syn::TraitItemMethod) {
+        if self.attrs_excluded(&i.attrs)
+            || self.name_excluded(&i.sig.ident)
+            || (i.default.is_some() && !self.fn_is_pub(None))
+        {
+            return;
+        }
+        if let Some(block) = &i.default {
+            self.collect_fn_mutations(&i.sig, &block.brace_token.span);
+            if block_is_replaced_literal(block) {
+                return;
+            }
+            self.in_namespace(&i.sig.ident.to_string(), |v| {
+                v.in_function(block, |v| syn::visit::visit_trait_item_method(v, i))
+            });
+        }
     }
 
     /// Visit `fn foo()` within an `impl`.
     fn visit_impl_item_method(&mut self, i: &'ast syn::ImplItemMethod) {
-        if attrs_excluded(&i.attrs) {
+        let in_trait_impl = self.impl_context.as_ref().is_some_and(|c| c.is_trait_impl);
+        if self.attrs_excluded(&i.attrs)
+            || self.name_excluded(&i.sig.ident)
+            || !self.fn_is_pub((!in_trait_impl).then_some(&i.vis))
+        {
+            return;
+        }
+        // Replacing `Default::default` with `Default::default()` would just recurse
+        // forever, so instead its field initializers are mutated.
+        let is_default_fn = i.sig.ident == "default"
+            && self
+                .impl_context
+                .as_ref()
+                .is_some_and(|c| c.is_default_impl);
+        if !is_default_fn {
+            self.collect_fn_mutations(&i.sig, &i.block.brace_token.span);
+        }
+        if block_is_replaced_literal(&i.block) {
             return;
         }
-        self.collect_fn_mutations(&i.sig.ident, &i.sig.output, &i.block.brace_token.span);
         self.in_namespace(&i.sig.ident.to_string(), |v| {
-            syn::visit::visit_impl_item_method(v, i)
+            v.in_function(&i.block, |v| {
+                if is_default_fn {
+                    v.collect_field_literal_mutations(&i.block);
+                }
+                syn::visit::visit_impl_item_method(v, i)
+            })
         });
     }
 
+    /// Visit a macro invocation, which syn leaves as unparsed tokens, and with `--verbose`,
+    /// say that any `&&` or `||` in its arguments isn't mutated.
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        if self.options.verbose
+            && self.options.mutate_operators
+            && self.function_name.is_some()
+            && tokens_have_boolean_operator(i.tokens.clone())
+        {
+            eprintln!(
+                "skip {}:{}: operators in {}! arguments aren't mutated",
+                self.source_file.tree_relative_slashes(),
+                tokens_span(i).start.line,
+                i.path
+                    .segments
+                    .last()
+                    .map_or(String::new(), |s| s.ident.to_string()),
+            );
+        }
+        syn::visit::visit_macro(self, i);
+    }
+
+    /// Visit `a + b` and other binary operators.
+    ///
+    /// Operators inside macro arguments, like `assert!(a && b)` or `cfg!(a || b)`, are never
+    /// reached because syn leaves macro arguments as unparsed tokens, and mutating them might
+    /// not apply cleanly or mean the same thing after expansion.
+    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
+        if self.options.mutate_operators {
+            self.collect_expression_mutations(
+                Genre::BinaryOperator,
+                &i.op,
+                binary_operator_replacements(&i.op),
+            );
+            if let (
+                syn::BinOp::Shl(_) | syn::BinOp::Shr(_),
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }),
+            ) = (&i.op, &*i.right)
+            {
+                // With `--mutate-literals` this is mutated like any other literal.
+                if !self.options.mutate_literals {
+                    self.collect_expression_mutations(
+                        Genre::Literal,
+                        lit,
+                        &int_literal_replacements(lit),
+                    );
+                }
+            }
+        }
+        syn::visit::visit_expr_binary(self, i);
+    }
+
+    /// Visit `a += b` and other compound assignments.
+    fn visit_expr_assign_op(&mut self, i: &'ast syn::ExprAssignOp) {
+        if self.options.mutate_operators {
+            self.collect_expression_mutations(
+                Genre::BinaryOperator,
+                &i.op,
+                binary_operator_replacements(&i.op),
+            );
+        }
+        syn::visit::visit_expr_assign_op(self, i);
+    }
+
+    /// Visit a range like `a..b`, in a `for` loop, slice index, or elsewhere.
+    ///
+    /// Ranges in patterns are not expressions, so they're not visited here and not mutated.
+    fn visit_expr_range(&mut self, i: &'ast syn::ExprRange) {
+        if self.options.mutate_operators {
+            let limits = match &i.limits {
+                syn::RangeLimits::HalfOpen(dots) => dots.to_token_stream(),
+                syn::RangeLimits::Closed(dots) => dots.to_token_stream(),
+            };
+            self.collect_expression_mutations(Genre::Range, &limits, range_limits_replacements(i));
+        }
+        syn::visit::visit_expr_range(self, i);
+    }
+
+    /// Visit a `match`, and generate mutants that delete each arm that's followed by a
+    /// wildcard arm, so that the match is still exhaustive.
+    fn visit_expr_match(&mut self, i: &'ast syn::ExprMatch) {
+        if self.options.mutate_operators {
+            for (n, arm) in i.arms.iter().enumerate() {
+                if !arm_is_wildcard(arm) && i.arms[n + 1..].iter().any(arm_is_wildcard) {
+                    let pattern_end = match &arm.guard {
+                        Some((_if, guard)) => tokens_span(guard).end,
+                        None => tokens_span(&arm.pat).end,
+                    };
+                    let pattern = self.source_text(tokens_span(&arm.pat).start, pattern_end);
+                    self.collect_span_mutations(Genre::MatchArm, tokens_span(arm), pattern, &[""]);
+                }
+            }
+        }
+        syn::visit::visit_expr_match(self, i);
+    }
+
+    /// Visit a statement, and generate a mutant deleting it if it's a call like `log(x);`
+    /// or `self.flush()?;`.
+    fn visit_stmt(&mut self, i: &'ast syn::Stmt) {
+        let outer_in_cfg = self.in_cfg_statement;
+        self.in_cfg_statement |= stmt_attrs(i).iter().any(|attr| attr.path.is_ident("cfg"));
+        if let syn::Stmt::Semi(expr, _semi) = i {
+            if self.options.mutate_operators {
+                if let Some(name) = callee_name(expr) {
+                    if !self.options.skip_calls.contains(&name) {
+                        self.collect_span_mutations(Genre::Statement, tokens_span(i), name, &[""]);
+                    }
+                }
+            }
+        }
+        syn::visit::visit_stmt(self, i);
+        self.in_cfg_statement = outer_in_cfg;
+    }
+
+    /// Visit a literal: flip bools, with `--mutate-literals` change integers by one, up
+    /// to the limit of literal mutants in each function, and with `--mutate-strings` replace
+    /// strings.
+    ///
+    /// Literals within attributes or macro arguments, like format strings, are never
+    /// reached, because syn leaves them as tokens, and literals in patterns are not visited.
+    fn visit_expr_lit(&mut self, i: &'ast syn::ExprLit) {
+        if let syn::Lit::Bool(lit_bool) = &i.lit {
+            if self.options.mutate_operators && !self.in_cfg_statement {
+                self.collect_expression_mutations(
+                    Genre::Literal,
+                    lit_bool,
+                    &[bool_literal_replacement(lit_bool)],
+                );
+            }
+        } else if let syn::Lit::Str(lit_str) = &i.lit {
+            if self.options.mutate_strings && !self.in_cfg_statement {
+                self.collect_expression_mutations(
+                    Genre::Literal,
+                    lit_str,
+                    &[string_literal_replacement(lit_str)],
+                );
+            }
+        } else if let syn::Lit::Int(lit_int) = &i.lit {
+            let max = self
+                .options
+                .max_literal_mutants
+                .unwrap_or(DEFAULT_MAX_LITERAL_MUTANTS);
+            if self.options.mutate_literals
+                && !self.in_cfg_statement
+                && self.function_name.is_some()
+                && self.literal_mutant_count < max
+            {
+                let mut replacements = int_literal_replacements(lit_int);
+                replacements.truncate(max - self.literal_mutant_count);
+                self.literal_mutant_count += replacements.len();
+                self.collect_expression_mutations(Genre::Literal, lit_int, &replacements);
+            }
+        }
+        syn::visit::visit_expr_lit(self, i);
+    }
+
+    /// Don't look inside patterns: there are no operators in them, and changing a literal
+    /// pattern, like `0 => ...`, could make a `match` fail to build.
+    fn visit_pat(&mut self, _i: &'ast syn::Pat) {}
+
+    /// Don't look inside types either: the only expressions in them are array lengths, which
+    /// must match the values they describe.
+    fn visit_type(&mut self, _i: &'ast syn::Type) {}
+
+    /// Visit `a.min(b)` and other method calls, and swap methods with opposite meanings.
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        if self.options.mutate_operators {
+            self.collect_expression_mutations(
+                Genre::MethodName,
+                &i.method,
+                &method_name_replacements(&i.method.to_string()),
+            );
+            if BOOL_CLOSURE_METHODS.contains(&i.method.to_string().as_str()) {
+                for arg in &i.args {
+                    if let syn::Expr::Closure(closure) = arg {
+                        self.collect_expression_mutations(
+                            Genre::Closure,
+                            &closure.body,
+                            &["true", "false"],
+                        );
+                    }
+                }
+            }
+            if let Some((closure, ty)) = map_closure_with_known_type(i) {
+                self.collect_expression_mutations(
+                    Genre::Closure,
+                    &closure.body,
+                    &type_replacements(ty, self.options),
+                );
+            }
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    /// Visit `!a` within a condition, or `-a` anywhere.
+    fn visit_expr_unary(&mut self, i: &'ast syn::ExprUnary) {
+        if self.options.mutate_operators {
+            let delete = match i.op {
+                syn::UnOp::Not(_) => self.in_condition && should_delete_not(&i.expr),
+                syn::UnOp::Neg(_) => true,
+                syn::UnOp::Deref(_) => false,
+            };
+            if delete {
+                self.collect_expression_mutations(Genre::UnaryOperator, &i.op, &[""]);
+            }
+        }
+        syn::visit::visit_expr_unary(self, i);
+    }
+
+    /// Visit `if`, and generate mutants replacing its condition with `true` and `false`.
+    fn visit_expr_if(&mut self, i: &'ast syn::ExprIf) {
+        if self.options.mutate_operators {
+            self.collect_expression_mutations(
+                Genre::IfCondition,
+                &i.cond,
+                if_condition_replacements(i),
+            );
+        }
+        if self.options.mutate_operators {
+            // `else if` isn't swapped here, but the inner `if` can have its own branches
+            // swapped.
+            if let Some((_else, else_branch)) = &i.else_branch {
+                if let syn::Expr::Block(else_block) = &**else_branch {
+                    self.collect_swap_branches(i, else_block);
+                }
+            }
+        }
+        for attr in &i.attrs {
+            self.visit_attribute(attr);
+        }
+        self.visit_condition(&i.cond);
+        let outer = std::mem::replace(&mut self.in_condition, false);
+        self.visit_block(&i.then_branch);
+        if let Some((_else, else_branch)) = &i.else_branch {
+            self.visit_expr(else_branch);
+        }
+        self.in_condition = outer;
+    }
+
+    fn visit_expr_while(&mut self, i: &'ast syn::ExprWhile) {
+        for attr in &i.attrs {
+            self.visit_attribute(attr);
+        }
+        self.visit_condition(&i.cond);
+        let outer = std::mem::replace(&mut self.in_condition, false);
+        self.visit_block(&i.body);
+        self.in_condition = outer;
+    }
+
+    fn visit_file(&mut self, i: &'ast syn::File) {
+        collect_default_types(&i.items, &mut self.default_types);
+        collect_struct_fields(&i.items, &mut self.struct_fields);
+        syn::visit::visit_file(self, i);
+    }
+
     fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
-        if !attrs_excluded(&node.attrs) {
+        if !self.attrs_excluded(&node.attrs) {
+            let outer_pub_scope = self.in_pub_scope;
+            self.in_pub_scope = outer_pub_scope && self.vis_is_pub(&node.vis);
             self.in_namespace(&node.ident.to_string(), |v| {
                 syn::visit::visit_item_mod(v, node)
             });
+            self.in_pub_scope = outer_pub_scope;
         }
     }
 }
 
-fn ops_for_return_type(return_type: &syn::ReturnType) -> Vec<MutationOp> {
-    let mut ops: Vec<MutationOp> = Vec::new();
-    match return_type {
-        syn::ReturnType::Default => ops.push(MutationOp::Unit),
-        syn::ReturnType::Type(_rarrow, box_typ) => match &**box_typ {
-            syn::Type::Path(syn::TypePath { path, .. }) => {
-                // dbg!(&path);
-                if path.is_ident("bool") {
-                    ops.push(MutationOp::True);
-                    ops.push(MutationOp::False);
-                } else if path.is_ident("String") {
-                    // TODO: Detect &str etc.
-                    ops.push(MutationOp::EmptyString);
-                    ops.push(MutationOp::Xyzzy);
-                } else if path_is_result(path) {
-                    // TODO: Try this for any path ending in "Result".
-                    // TODO: Recursively generate for types inside the Ok side of the Result.
-                    ops.push(MutationOp::OkDefault);
-                } else {
-                    ops.push(MutationOp::Default)
+/// True if the block is just a bool or string literal, like the body of
+/// `fn enabled() -> bool { true }`, whose mutants would be the same as replacing the
+/// whole function body.
+fn block_is_replaced_literal(block: &syn::Block) -> bool {
+    matches!(
+        block.stmts.as_slice(),
+        [syn::Stmt::Expr(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(_) | syn::Lit::Str(_),
+            ..
+        }))]
+    )
+}
+
+/// If this is a call like `.map(|x| ...).sum::<T>()`, return the closure passed to `map`
+/// and the type `T` that it must return.
+fn map_closure_with_known_type(
+    method_call: &syn::ExprMethodCall,
+) -> Option<(&syn::ExprClosure, &syn::Type)> {
+    if method_call.method != "sum" && method_call.method != "product" {
+        return None;
+    }
+    let ty = match method_call.turbofish.as_ref()?.args.first()? {
+        syn::GenericMethodArgument::Type(ty) => ty,
+        syn::GenericMethodArgument::Const(_) => return None,
+    };
+    match &*method_call.receiver {
+        syn::Expr::MethodCall(map_call) if map_call.method == "map" => {
+            match map_call.args.first()? {
+                syn::Expr::Closure(closure) => Some((closure, ty)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Return the paths given by `#[path = "..."]` attributes on `mod` declarations in a file,
+/// including within `#[cfg_attr(..., path = "...")]`.
+///
+/// Paths on declarations within inline modules are prefixed by the names of the modules.
+pub fn mod_path_attrs(file: &syn::File) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    collect_mod_path_attrs(&file.items, Path::new(""), &mut paths);
+    paths
+}
+
+fn collect_mod_path_attrs(items: &[syn::Item], dir: &Path, paths: &mut Vec<PathBuf>) {
+    for item in items {
+        if let syn::Item::Mod(item_mod) = item {
+            let path_attr = item_mod.attrs.iter().find_map(attr_mod_path);
+            match (&item_mod.content, path_attr) {
+                (None, Some(path)) => paths.push(dir.join(path)),
+                (Some((_, items)), path) => {
+                    let dir = dir.join(path.unwrap_or_else(|| item_mod.ident.to_string()));
+                    collect_mod_path_attrs(items, &dir, paths);
                 }
+                (None, None) => (),
             }
-            _ => ops.push(MutationOp::Default),
+        }
+    }
+}
+
+/// If this is `#[path = "..."]` or `#[cfg_attr(..., path = "...")]`, return the path.
+fn attr_mod_path(attr: &Attribute) -> Option<String> {
+    let path_value = |meta: &syn::Meta| match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) if path.is_ident("path") => Some(lit_str.value()),
+        _ => None,
+    };
+    let meta = attr.parse_meta().ok()?;
+    if attr.path.is_ident("path") {
+        path_value(&meta)
+    } else if attr.path.is_ident("cfg_attr") {
+        match meta {
+            syn::Meta::List(list) => list.nested.iter().skip(1).find_map(|nested| match nested {
+                syn::NestedMeta::Meta(meta) => path_value(meta),
+                syn::NestedMeta::Lit(_) => None,
+            }),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Return the attributes on a statement, if it's a kind that's commonly `#[cfg]`'d.
+fn stmt_attrs(stmt: &syn::Stmt) -> &[Attribute] {
+    match stmt {
+        syn::Stmt::Local(local) => &local.attrs,
+        syn::Stmt::Expr(expr) | syn::Stmt::Semi(expr, _) => match expr {
+            syn::Expr::Assign(e) => &e.attrs,
+            syn::Expr::AssignOp(e) => &e.attrs,
+            syn::Expr::Block(e) => &e.attrs,
+            syn::Expr::Call(e) => &e.attrs,
+            syn::Expr::ForLoop(e) => &e.attrs,
+            syn::Expr::If(e) => &e.attrs,
+            syn::Expr::Macro(e) => &e.attrs,
+            syn::Expr::Match(e) => &e.attrs,
+            syn::Expr::MethodCall(e) => &e.attrs,
+            syn::Expr::While(e) => &e.attrs,
+            _ => &[],
         },
+        syn::Stmt::Item(_) => &[],
+    }
+}
+
+/// Find the literal that initializes a field, if it's a literal itself or the only
+/// argument of a simple call or conversion, like `3`, `"main".into()`, or
+/// `Duration::from_secs(5)`.
+fn field_literal(expr: &syn::Expr) -> Option<&syn::Lit> {
+    let expr = match expr {
+        syn::Expr::MethodCall(syn::ExprMethodCall { receiver, args, .. }) if args.is_empty() => {
+            receiver
+        }
+        syn::Expr::Call(syn::ExprCall { args, .. }) if args.len() == 1 => &args[0],
+        _ => expr,
+    };
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => Some(lit),
+        _ => None,
+    }
+}
+
+/// If the expression is a call or method call, possibly followed by `?`, return the name
+/// of the outermost callee: the last segment of the function's path, or the method
+/// called last in a chain, like `push` for `self.m.lock().unwrap().push(x)`. Otherwise,
+/// return None.
+fn callee_name(expr: &syn::Expr) -> Option<String> {
+    let mut expr = expr;
+    if let syn::Expr::Try(syn::ExprTry { expr: inner, .. }) = expr {
+        expr = inner;
+    }
+    match expr {
+        syn::Expr::Call(syn::ExprCall { func, .. }) => match &**func {
+            syn::Expr::Path(syn::ExprPath { path, .. }) => {
+                path.segments.last().map(|s| s.ident.to_string())
+            }
+            _ => None,
+        },
+        syn::Expr::MethodCall(syn::ExprMethodCall { method, .. }) => Some(method.to_string()),
+        _ => None,
+    }
+}
+
+/// Cut text longer than `max_chars` characters, marking where it was cut with `…`.
+fn shorten(text: String, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text
+    } else {
+        let mut short: String = text.chars().take(max_chars - 1).collect();
+        short.push('…');
+        short
+    }
+}
+
+/// True if the tokens, including those in nested groups, contain `&&` or `||`.
+fn tokens_have_boolean_operator(tokens: proc_macro2::TokenStream) -> bool {
+    let mut joint_punct = None;
+    for tt in tokens {
+        match tt {
+            proc_macro2::TokenTree::Group(group) => {
+                if tokens_have_boolean_operator(group.stream()) {
+                    return true;
+                }
+                joint_punct = None;
+            }
+            proc_macro2::TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                if joint_punct == Some(c) && (c == '&' || c == '|') {
+                    return true;
+                }
+                joint_punct = (punct.spacing() == proc_macro2::Spacing::Joint).then_some(c);
+            }
+            _ => joint_punct = None,
+        }
+    }
+    false
+}
+
+/// True if the arm is `_ => ...`, with no guard, so it matches anything.
+fn arm_is_wildcard(arm: &syn::Arm) -> bool {
+    matches!(arm.pat, syn::Pat::Wild(_)) && arm.guard.is_none()
+}
+
+/// Return the span from the start of the first token to the end of the last, inclusive.
+fn tokens_span<T: ToTokens>(tokens: &T) -> Span {
+    let spans: Vec<proc_macro2::Span> = tokens
+        .to_token_stream()
+        .into_iter()
+        .map(|tt| tt.span())
+        .collect();
+    let start = spans.first().expect("tokens are not empty").start();
+    // proc_macro2's end column is exclusive and 0-based, so it's the same as the inclusive
+    // 1-based column of the last character.
+    let end = spans.last().expect("tokens are not empty").end();
+    Span {
+        start: start.into(),
+        end: LineColumn {
+            line: end.line,
+            column: end.column,
+        },
+    }
+}
+
+/// Add the names of types that derive or implement `Default` in these items, or in
+/// inline modules within them.
+fn collect_default_types(items: &[syn::Item], default_types: &mut BTreeSet<String>) {
+    for item in items {
+        match item {
+            syn::Item::Struct(syn::ItemStruct { attrs, ident, .. })
+            | syn::Item::Enum(syn::ItemEnum { attrs, ident, .. })
+                if attrs.iter().any(attr_derives_default) =>
+            {
+                default_types.insert(ident.to_string());
+            }
+            syn::Item::Impl(syn::ItemImpl {
+                trait_: Some((None, trait_path, _)),
+                self_ty,
+                ..
+            }) if path_ends_with(trait_path, "Default") => {
+                default_types.extend(type_last_ident(self_ty));
+            }
+            syn::Item::Mod(syn::ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => collect_default_types(items, default_types),
+            _ => (),
+        }
+    }
+}
+
+/// Add the fields of structs defined in these items, or in inline modules within them.
+fn collect_struct_fields(items: &[syn::Item], struct_fields: &mut BTreeMap<String, syn::Fields>) {
+    for item in items {
+        match item {
+            syn::Item::Struct(syn::ItemStruct { ident, fields, .. }) => {
+                struct_fields.insert(ident.to_string(), fields.clone());
+            }
+            syn::Item::Mod(syn::ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => collect_struct_fields(items, struct_fields),
+            _ => (),
+        }
+    }
+}
+
+/// True if the attribute is `#[derive(...)]` including `Default`.
+fn attr_derives_default(attr: &Attribute) -> bool {
+    if !attr.path.is_ident("derive") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(meta_list)) => meta_list.nested.iter().any(|nested| {
+            matches!(nested, syn::NestedMeta::Meta(syn::Meta::Path(path))
+                if path_ends_with(path, "Default"))
+        }),
+        _ => false,
+    }
+}
+
+/// True if the type parameter has `Default` among its bounds, either inline or in the
+/// where clause.
+fn type_param_has_default_bound(generics: &syn::Generics, ident: &syn::Ident) -> bool {
+    let inline_bounds = generics
+        .type_params()
+        .filter(|tp| tp.ident == *ident)
+        .flat_map(|tp| tp.bounds.iter());
+    let where_bounds = generics
+        .where_clause
+        .iter()
+        .flat_map(|wc| wc.predicates.iter())
+        .filter_map(|pred| match pred {
+            syn::WherePredicate::Type(pt)
+                if type_last_ident(&pt.bounded_ty).is_some_and(|i| *ident == i) =>
+            {
+                Some(pt.bounds.iter())
+            }
+            _ => None,
+        })
+        .flatten();
+    inline_bounds.chain(where_bounds).any(|bound| {
+        matches!(bound, syn::TypeParamBound::Trait(tb)
+            if path_ends_with(&tb.path, "Default"))
+    })
+}
+
+/// The name of the last segment of a type path, like `Foo` for `crate::Foo<T>`.
+fn type_last_ident(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(syn::TypePath { path, .. }) => {
+            path.segments.last().map(|s| s.ident.to_string())
+        }
+        _ => None,
     }
-    ops
 }
 
 fn type_name_string(ty: &syn::Type) -> String {
@@ -152,37 +1286,72 @@ fn type_name_string(ty: &syn::Type) -> String {
     }
 }
 
-fn path_is_result(path: &syn::Path) -> bool {
-    path.segments
-        .last()
-        .map(|segment| segment.ident == "Result")
-        .unwrap_or_default()
+/// True if the attribute is `#[cfg(test)]`, or another `cfg` that's only true in
+/// tests, like `#[cfg(all(test, unix))]`.
+fn attr_is_cfg_test(attr: &Attribute) -> bool {
+    if !attr.path.is_ident("cfg") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(meta_list)) => meta_list.nested.iter().any(cfg_requires_test),
+        _ => false,
+    }
 }
 
-/// True if any of the attrs indicate that we should skip this node and everything inside it.
-fn attrs_excluded(attrs: &[Attribute]) -> bool {
-    attrs
-        .iter()
-        .any(|attr| attr_is_cfg_test(attr) || attr_is_test(attr) || attr_is_mutants_skip(attr))
+/// True if a `cfg` predicate can only be true when building tests.
+fn cfg_requires_test(predicate: &syn::NestedMeta) -> bool {
+    match predicate {
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) => path.is_ident("test"),
+        syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("all") => {
+            list.nested.iter().any(cfg_requires_test)
+        }
+        syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("any") => {
+            !list.nested.is_empty() && list.nested.iter().all(cfg_requires_test)
+        }
+        _ => false,
+    }
 }
 
-/// True if the attribute is `#[cfg(test)]`.
-fn attr_is_cfg_test(attr: &Attribute) -> bool {
+/// True if the attribute is a `cfg` that can't be true, because it requires a feature
+/// that's known to be off, like `#[cfg(feature = "extra")]` or
+/// `#[cfg(all(unix, feature = "extra"))]`.
+fn attr_requires_off_feature(attr: &Attribute, features: &KnownFeatures) -> bool {
     if !attr.path.is_ident("cfg") {
         return false;
     }
-    if let syn::Meta::List(meta_list) = attr.parse_meta().unwrap() {
-        // We should have already checked this above, but to make sure:
-        assert!(meta_list.path.is_ident("cfg"));
-        for nested_meta in meta_list.nested {
-            if let syn::NestedMeta::Meta(syn::Meta::Path(cfg_path)) = nested_meta {
-                if cfg_path.is_ident("test") {
-                    return true;
-                }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(meta_list)) => meta_list
+            .nested
+            .iter()
+            .any(|predicate| cfg_requires_off_feature(predicate, features)),
+        _ => false,
+    }
+}
+
+/// True if a `cfg` predicate can only be true with a feature that's known to be off.
+fn cfg_requires_off_feature(predicate: &syn::NestedMeta, features: &KnownFeatures) -> bool {
+    match predicate {
+        syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+            if name_value.path.is_ident("feature") =>
+        {
+            match &name_value.lit {
+                syn::Lit::Str(feature) => features.is_off(&feature.value()),
+                _ => false,
             }
         }
+        syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("all") => list
+            .nested
+            .iter()
+            .any(|predicate| cfg_requires_off_feature(predicate, features)),
+        syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident("any") => {
+            !list.nested.is_empty()
+                && list
+                    .nested
+                    .iter()
+                    .all(|predicate| cfg_requires_off_feature(predicate, features))
+        }
+        _ => false,
     }
-    false
 }
 
 /// True if the attribute is `#[test]`.
@@ -190,10 +1359,28 @@ fn attr_is_test(attr: &Attribute) -> bool {
     attr.path.is_ident("test")
 }
 
-/// True if the attribute is `#[mutants::skip]`.
+/// True if the attribute is `#[mutants::skip]` or `#[cfg_attr(..., mutants::skip)]`.
+///
+/// The path is matched textually, so the crate doesn't need to depend on the `mutants`
+/// crate, and the `cfg_attr` predicate isn't evaluated.
 fn attr_is_mutants_skip(attr: &Attribute) -> bool {
-    attr.path
-        .segments
+    if path_is_mutants_skip(&attr.path) {
+        return true;
+    }
+    if !attr.path.is_ident("cfg_attr") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) => list.nested.iter().skip(1).any(|nested| {
+            matches!(nested, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path_is_mutants_skip(path))
+        }),
+        _ => false,
+    }
+}
+
+/// True if the path is `mutants::skip`.
+fn path_is_mutants_skip(path: &syn::Path) -> bool {
+    path.segments
         .iter()
         .map(|ps| &ps.ident)
         .eq(["mutants", "skip"].iter())
@@ -201,9 +1388,773 @@ fn attr_is_mutants_skip(attr: &Attribute) -> bool {
 
 #[cfg(test)]
 mod test {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    /// Discover the mutants in a `lib.rs` containing `code`.
+    fn discover_code(code: &str, options: &Options) -> Discovery {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("lib.rs"), code).unwrap();
+        let source_file = SourceFile::new(temp.path(), Path::new("lib.rs")).unwrap();
+        source_file.discover(options).unwrap()
+    }
+
+    #[test]
+    fn cfg_test_items_are_skipped_and_counted() {
+        let code = "fn one() -> u8 { 1 }\n\
+            #[cfg(test)]\nfn helper() -> u8 { 2 }\n\
+            #[cfg(all(test, unix))]\nmod unix_tests {\n    fn three() -> u8 { 3 }\n}\n\
+            #[cfg(any(test, feature = \"fake\"))]\nfn fake() -> u8 { 4 }\n\
+            #[cfg(not(test))]\nfn real() -> u8 { 5 }\n";
+        let mut options = Options::default();
+        let discovery = discover_code(code, &options);
+        let names: BTreeSet<&str> = discovery
+            .mutations
+            .iter()
+            .map(|m| m.function_name())
+            .collect();
+        assert_eq!(names, ["fake", "one", "real"].into());
+        assert_eq!(discovery.skipped.get(&SkipReason::CfgTest), Some(&2));
+
+        options.mutate_cfg_test = true;
+        let discovery = discover_code(code, &options);
+        let names: BTreeSet<&str> = discovery
+            .mutations
+            .iter()
+            .map(|m| m.function_name())
+            .collect();
+        assert_eq!(
+            names,
+            ["fake", "helper", "one", "real", "unix_tests::three"].into()
+        );
+        assert_eq!(discovery.skipped.get(&SkipReason::CfgTest), None);
+    }
+
+    #[test]
+    fn mutants_skip_items_are_skipped_and_counted() {
+        let code = "fn one() -> u8 { 1 }\n\
+            #[mutants::skip]\nfn two() -> u8 { 2 }\n\
+            #[cfg_attr(test, mutants::skip)]\nfn three() -> u8 { 3 }\n\
+            #[cfg_attr(test, inline, mutants::skip)]\nfn four() -> u8 { 4 }\n\
+            #[mutants::skip]\nmod inner {\n    fn five() -> u8 { 5 }\n}\n\
+            #[cfg_attr(test, inline)]\nfn six() -> u8 { 6 }\n";
+        let discovery = discover_code(code, &Options::default());
+        let names: BTreeSet<&str> = discovery
+            .mutations
+            .iter()
+            .map(|m| m.function_name())
+            .collect();
+        assert_eq!(names, ["one", "six"].into());
+        assert_eq!(discovery.skipped.get(&SkipReason::MutantsSkip), Some(&4));
+    }
+
+    #[test]
+    fn functions_matching_exclude_regexps_are_skipped() {
+        let code = "fn one() -> u8 { 1 }\n\
+            fn ffi_two() -> u8 { 2 }\n\
+            struct S;\n\
+            impl std::fmt::Display for S {\n\
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n\
+            }\n\
+            mod migrations {\n    fn three() -> u8 { 3 }\n}\n";
+        let mut options = Options::default();
+        options.exclude_functions = ["^ffi_", "::fmt$", "^migrations::"]
+            .iter()
+            .map(|re| regex::Regex::new(re).unwrap())
+            .collect();
+        let discovery = discover_code(code, &options);
+        let names: BTreeSet<&str> = discovery
+            .mutations
+            .iter()
+            .map(|m| m.function_name())
+            .collect();
+        assert_eq!(names, ["one"].into());
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::ExcludedByConfig),
+            Some(&3)
+        );
+    }
+
+    #[test]
+    fn only_pub_functions_are_mutated() {
+        let code = "pub fn one() -> u8 { 1 }\n\
+            fn two() -> u8 { 2 }\n\
+            pub(crate) fn three() -> u8 { 3 }\n\
+            pub(super) fn four() -> u8 { 4 }\n\
+            mod private {\n    pub fn five() -> u8 { 5 }\n}\n\
+            pub mod api {\n    pub fn six() -> u8 { 6 }\n}\n\
+            pub struct S;\n\
+            impl S {\n    pub fn seven() -> u8 { 7 }\n    fn eight() -> u8 { 8 }\n}\n\
+            impl std::fmt::Display for S {\n\
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n\
+            }\n\
+            pub trait T {\n    fn nine() -> u8 { 9 }\n}\n\
+            trait U {\n    fn ten() -> u8 { 10 }\n}\n\
+            pub fn outer() -> u8 {\n    fn inner() -> u8 { 11 }\n    inner()\n}\n";
+        let mut options = Options::default();
+        let names = |options: &Options| -> (BTreeSet<String>, Option<usize>) {
+            let discovery = discover_code(code, options);
+            let names = discovery
+                .mutations
+                .iter()
+                .map(|m| m.function_name().to_owned())
+                .collect();
+            (names, discovery.skipped.get(&SkipReason::NotPub).copied())
+        };
+        assert_eq!(names(&options).0.len(), 13);
+
+        options.only_pub = Some(OnlyPub::Pub);
+        let (pub_names, not_pub) = names(&options);
+        assert_eq!(
+            pub_names,
+            ["S::fmt", "S::seven", "T::nine", "api::six", "one", "outer"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect()
+        );
+        assert_eq!(not_pub, Some(7));
+
+        options.only_pub = Some(OnlyPub::Crate);
+        let (crate_names, not_pub) = names(&options);
+        assert_eq!(
+            crate_names.difference(&pub_names).collect::<Vec<_>>(),
+            ["three"]
+        );
+        assert_eq!(not_pub, Some(6));
+    }
+
+    #[test]
+    fn const_fns_only_get_const_replacements() {
+        let code = "const fn one() -> u8 { 1 }\n\
+            const fn maybe() -> Option<u8> { Some(1) }\n\
+            const fn name() -> String { String::new() }\n";
+        let discovery = discover_code(code, &Options::default());
+        let descriptions: Vec<String> = discovery.mutations.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "replace one with 0 in lib.rs:1",
+                "replace one with 1 in lib.rs:1",
+                "replace maybe with None in lib.rs:2",
+            ]
+        );
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::NoViableReplacement),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn self_constructors_build_the_struct_from_its_fields() {
+        let code = "struct Tally { count: u32, name: String }\n\
+            impl Tally { fn new() -> Self { todo!() } }\n\
+            struct Pair(bool, Option<u8>);\n\
+            impl Pair { fn new() -> Self { todo!() } }\n\
+            struct Marker;\n\
+            impl Marker { fn new() -> Self { Marker } }\n\
+            struct Handle { f: &'static dyn Fn() }\n\
+            impl Handle { fn new() -> Self { todo!() } }\n\
+            impl Remote { fn new() -> Self { todo!() } }\n";
+        let discovery = discover_code(code, &Options::default());
+        let replacements: Vec<(&str, &str)> = discovery
+            .mutations
+            .iter()
+            .map(|m| (m.function_name(), m.replacement_text()))
+            .collect();
+        assert_eq!(
+            replacements,
+            [
+                ("Tally::new", "Self { count: 0, name: \"\".into() }"),
+                ("Pair::new", "Self(true, None)"),
+                ("Marker::new", "Self"),
+                ("Handle::new", "Default::default()"),
+                ("Remote::new", "Default::default()"),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_functions_with_no_viable_replacement() {
+        let code = "fn shapes() -> impl Shape { Circle }\n\
+            fn error() -> Box<dyn Error> { todo!() }\n\
+            fn fail(message: &str) -> ! { panic!(\"{}\", message) }\n\
+            unsafe extern \"C\" fn callback(x: u32) -> u32 { x }\n\
+            fn one() -> u8 { 1 }\n";
+        let discovery = discover_code(code, &Options::default());
+        assert_eq!(discovery.mutations.len(), 2);
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::NoViableReplacement),
+            Some(&3)
+        );
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::UnknownImplTrait),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn default_impl_field_literals_are_mutated_instead_of_the_body() {
+        let code = "impl Default for Config {\n\
+            \x20   fn default() -> Self {\n\
+            \x20       Config { retries: 3, verbose: true, name: \"main\".into(), dir: None }\n\
+            \x20   }\n\
+            }\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let discovery = discover_code(code, &options);
+        let names: Vec<String> = discovery.mutations.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "replace 3 with 2 in Config::default at lib.rs:3:27",
+                "replace 3 with 4 in Config::default at lib.rs:3:27",
+                "replace \"main\" with \"\" in Config::default at lib.rs:3:51",
+                "replace true with false in Config::default at lib.rs:3:39",
+            ]
+        );
+
+        // Without operator mutants the bool isn't flipped, but the other fields are still mutated.
+        options.mutate_operators = false;
+        let discovery = discover_code(code, &options);
+        assert_eq!(discovery.mutations.len(), 3);
+        assert!(discovery
+            .mutations
+            .iter()
+            .all(|m| !m.to_string().contains("true")));
+    }
+
+    #[test]
+    fn bool_literals_are_flipped_within_functions() {
+        let code = "fn enabled() -> bool { true }\n\
+            fn start(addr: &str) {\n\
+            \x20   if false {\n        return;\n    }\n\
+            \x20   client.connect(addr, true);\n\
+            \x20   #[cfg(feature = \"trace\")]\n\
+            \x20   client.trace(true);\n\
+            }\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let discovery = discover_code(code, &options);
+        let literal_mutants: Vec<String> = discovery
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Literal)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            literal_mutants,
+            ["replace true with false in start at lib.rs:6:26"]
+        );
+    }
+
+    #[test]
+    fn string_literals_are_replaced_only_when_enabled() {
+        let code = "fn name() -> &'static str { \"main\" }\n\
+            fn header(key: &str) -> String {\n\
+            \x20   match key {\n        \"host\" => format!(\"{}: {}\", key, HOST),\n        _ => key.replace(\"-\", \"\"),\n    }\n\
+            }\n";
+        let mut options = Options::default();
+        let discovery = discover_code(code, &options);
+        assert!(discovery
+            .mutations
+            .iter()
+            .all(|m| m.genre() != Genre::Literal));
+
+        options.mutate_strings = true;
+        let discovery = discover_code(code, &options);
+        let literal_mutants: Vec<String> = discovery
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Literal)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            literal_mutants,
+            [
+                "replace \"-\" with \"\" in header at lib.rs:5:26",
+                "replace \"\" with \"xyzzy\" in header at lib.rs:5:31",
+            ]
+        );
+    }
+
+    #[test]
+    fn integer_literals_are_mutated_only_when_enabled() {
+        let code = "fn header(kind: u8) -> Vec<u8> {\n\
+            \x20   let mut buf: [u8; 4] = [0; 4];\n\
+            \x20   #[cfg(feature = \"extra\")]\n\
+            \x20   let kind = kind + 100;\n\
+            \x20   match kind {\n        7 => buf[1] = 2,\n        _ => (),\n    }\n\
+            \x20   buf.to_vec()\n\
+            }\n";
+        let mut options = Options::default();
+        let discovery = discover_code(code, &options);
+        assert!(discovery
+            .mutations
+            .iter()
+            .all(|m| m.genre() != Genre::Literal));
+
+        options.mutate_literals = true;
+        let discovery = discover_code(code, &options);
+        let literal_mutants: Vec<String> = discovery
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Literal)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            literal_mutants,
+            [
+                "replace 0 with 1 in header at lib.rs:2:29",
+                "replace 4 with 3 in header at lib.rs:2:32",
+                "replace 4 with 5 in header at lib.rs:2:32",
+                "replace 1 with 0 in header at lib.rs:6:18",
+                "replace 1 with 2 in header at lib.rs:6:18",
+                "replace 2 with 1 in header at lib.rs:6:23",
+                "replace 2 with 3 in header at lib.rs:6:23",
+            ]
+        );
+
+        options.max_literal_mutants = Some(2);
+        let discovery = discover_code(code, &options);
+        assert_eq!(
+            discovery
+                .mutations
+                .iter()
+                .filter(|m| m.genre() == Genre::Literal)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn trait_default_methods_are_mutated() {
+        let code = "trait Shape {\n\
+            \x20   fn sides(&self) -> u32;\n\
+            \x20   fn is_triangle(&self) -> bool {\n        self.sides() == 3\n    }\n\
+            \x20   fn unit() -> Self {\n        todo!()\n    }\n\
+            }\n";
+        let discovery = discover_code(code, &Options::default());
+        let names: Vec<String> = discovery.mutations.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "replace Shape::is_triangle with true in lib.rs:3",
+                "replace Shape::is_triangle with false in lib.rs:3",
+            ]
+        );
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::GenericWithoutDefault),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn async_fns_are_replaced_by_values_of_their_output_type() {
+        let code = "async fn count() -> usize { fetch().await.len() }\n";
+        let discovery = discover_code(code, &Options::default());
+        let replacements: Vec<&str> = discovery
+            .mutations
+            .iter()
+            .map(|m| m.replacement_text())
+            .collect();
+        assert_eq!(replacements, ["0", "1"]);
+    }
+
+    #[test]
+    fn generic_return_types_are_mutated_if_they_have_a_default_bound() {
+        let code = "fn get<T: Default + Clone>() -> T { todo!() }\n\
+            fn take<T>(t: T) -> T where T: Default { t }\n\
+            fn clone<T: Clone>(t: &T) -> T { t.clone() }\n";
+        let discovery = discover_code(code, &Options::default());
+        let names: Vec<String> = discovery
+            .mutations
+            .iter()
+            .map(|m| format!("{} {}", m.function_name(), m.replacement_text()))
+            .collect();
+        assert_eq!(names, ["get Default::default()", "take Default::default()"]);
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::GenericWithoutDefault),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn self_is_mutated_if_the_type_derives_default() {
+        let code = "#[derive(Debug, Default)]\nstruct Counter(u32);\n\
+            impl Counter {\n    fn start() -> Self { Counter(1) }\n}\n\
+            struct Handle(File);\n\
+            impl Handle {\n    fn open() -> Self { todo!() }\n}\n";
+        let discovery = discover_code(code, &Options::default());
+        let replacements: Vec<(&str, &str)> = discovery
+            .mutations
+            .iter()
+            .map(|m| (m.function_name(), m.replacement_text()))
+            .collect();
+        // `Handle` isn't `Default`, so it's built from its fields instead.
+        assert_eq!(
+            replacements,
+            [
+                ("Counter::start", "Default::default()"),
+                ("Handle::open", "Self(Default::default())"),
+            ]
+        );
+        assert_eq!(
+            discovery.skipped.get(&SkipReason::GenericWithoutDefault),
+            None
+        );
+    }
+
+    #[test]
+    fn binary_operators_are_mutated_within_functions() {
+        let code = "const N: usize = 1 + 2;\nfn double(a: u8) -> u8 {\n    a * 2\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = discover_code(code, &options).mutations;
+        let descriptions: Vec<String> = mutations
+            .iter()
+            .map(|m| format!("{}: {}", m.describe_location(), m.describe_change()))
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "lib.rs:2: replace double with 0",
+                "lib.rs:2: replace double with 1",
+                "lib.rs:3:7: replace * with / in double",
+            ]
+        );
+        assert_eq!(
+            mutations[2].mutated_code(),
+            "const N: usize = 1 + 2;\nfn double(a: u8) -> u8 {\n    a / /* ~ changed by cargo-mutants ~ */ 2\n}\n"
+        );
+
+        options.mutate_operators = false;
+        assert_eq!(discover_code(code, &options).mutations.len(), 2);
+    }
+
+    #[test]
+    fn comparison_operators_are_described_with_their_span() {
+        let code = "fn check_limit(n: usize) -> bool {\n    n <= 10\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = discover_code(code, &options).mutations;
+        assert_eq!(mutations.len(), 3);
+        assert_eq!(
+            mutations[2].to_string(),
+            "replace <= with < in check_limit at lib.rs:2:7"
+        );
+        let json = serde_json::to_value(&mutations[2]).unwrap();
+        assert_eq!(
+            json["span"],
+            serde_json::json!({
+                "start": { "line": 2, "column": 7 },
+                "end": { "line": 2, "column": 8 },
+            })
+        );
+    }
+
+    #[test]
+    fn boolean_operators_are_found_in_macro_tokens() {
+        let tokens = |code: &str| code.parse::<proc_macro2::TokenStream>().unwrap();
+        assert!(tokens_have_boolean_operator(tokens("a && b")));
+        assert!(tokens_have_boolean_operator(tokens("x, (a || (b))")));
+        assert!(!tokens_have_boolean_operator(tokens("a & &b, c | d")));
+        assert!(!tokens_have_boolean_operator(tokens("\"a && b\"")));
+    }
+
+    #[test]
+    fn boolean_operators_inside_macros_are_not_mutated() {
+        let code =
+            "fn both(a: bool, b: bool) {\n    assert!(a && b);\n    if cfg!(unix) || a {}\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let descriptions: Vec<String> = discover_code(code, &options)
+            .mutations
+            .iter()
+            .filter(|m| m.genre() != Genre::IfCondition)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "replace both with () in lib.rs:1",
+                "replace || with && in both at lib.rs:3:19",
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_not_only_in_conditions() {
+        let code = "fn wait(done: bool) -> u8 {\n    while !done {}\n    if !ready() && x != 1 { !0 } else { 2 }\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = discover_code(code, &options).mutations;
+        let descriptions: Vec<String> = mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::UnaryOperator)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "delete ! in wait at lib.rs:2:11",
+                "delete ! in wait at lib.rs:3:8",
+            ]
+        );
+        assert_eq!(
+            mutations
+                .iter()
+                .find(|m| m.genre() == Genre::UnaryOperator)
+                .unwrap()
+                .mutated_code(),
+            "fn wait(done: bool) -> u8 {\n    while /* ~ changed by cargo-mutants ~ */ done {}\n    if !ready() && x != 1 { !0 } else { 2 }\n}\n"
+        );
+    }
+
+    #[test]
+    fn compound_assignment_mutants_change_one_token() {
+        let source_file = SourceFile::new(
+            Path::new("testdata/tree/factorial"),
+            Path::new("src/bin/main.rs"),
+        )
+        .unwrap();
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = source_file.discover(&options).unwrap().mutations;
+        let mutation = mutations
+            .iter()
+            .find(|m| m.genre() == Genre::BinaryOperator)
+            .unwrap();
+        assert_eq!(
+            mutation.to_string(),
+            "replace *= with /= in factorial at src/bin/main.rs:10:11"
+        );
+        let diff = mutation.diff();
+        assert!(diff
+            .contains("\n-        a *= i;\n+        a /= /* ~ changed by cargo-mutants ~ */ i;\n"));
+        assert_eq!(diff.matches("@@ -").count(), 1, "one hunk");
+    }
+
+    #[test]
+    fn shift_mutants_diff_one_line() {
+        let source_file = SourceFile::new(
+            Path::new("testdata/tree/shift_codec"),
+            Path::new("src/lib.rs"),
+        )
+        .unwrap();
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations = source_file.discover(&options).unwrap().mutations;
+        let mutation = mutations
+            .iter()
+            .find(|m| m.genre() == Genre::Literal && m.function_name() == "decode_u16")
+            .unwrap();
+        assert_eq!(mutation.describe_change(), "replace 8 with 7 in decode_u16");
+        assert!(mutation.diff().contains(
+            "\n-    ((b[0] as u16) << 8) | b[1] as u16\n+    ((b[0] as u16) << 7 /* ~ changed by cargo-mutants ~ */) | b[1] as u16\n"
+        ));
+    }
+
+    #[test]
+    fn ranges_are_mutated_except_in_patterns() {
+        let code = "fn f(v: &[u8]) {\n    for i in 0..v.len() {}\n    let _ = &v[1..=2];\n    match v[0] { 0..=9 => (), _ => () }\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let descriptions: Vec<String> = discover_code(code, &options)
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Range)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "replace .. with ..= in f at lib.rs:2:15",
+                "replace ..= with .. in f at lib.rs:3:17",
+            ]
+        );
+    }
+
+    #[test]
+    fn match_arms_before_a_wildcard_are_deleted() {
+        let code = "fn handle_event(e: Event) {\n    match e {\n        Event::Click => click(),\n        Event::Key(k) if k > 0 => {\n            key(k)\n        }\n        _ => (),\n    }\n    match e {\n        Event::Click => (),\n        Event::Key(_) => (),\n    }\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations: Vec<Mutation> = discover_code(code, &options)
+            .mutations
+            .into_iter()
+            .filter(|m| m.genre() == Genre::MatchArm)
+            .collect();
+        let descriptions: Vec<String> = mutations.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "delete match arm `Event::Click => ...` in handle_event at lib.rs:3:9",
+                "delete match arm `Event::Key(k) if k > 0 => ...` in handle_event at lib.rs:4:9",
+            ]
+        );
+        assert_eq!(
+            mutations[1].mutated_code(),
+            "fn handle_event(e: Event) {\n    match e {\n        Event::Click => click(),\n        /* ~ changed by cargo-mutants ~ */ \n        _ => (),\n    }\n    match e {\n        Event::Click => (),\n        Event::Key(_) => (),\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn swap_if_branches_except_else_if() {
+        let code = "fn sign(a: i32) -> i32 {\n    if a < 0 {\n        -1\n    } else if a == 0 {\n        0\n    } else {\n        1\n    }\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations: Vec<Mutation> = discover_code(code, &options)
+            .mutations
+            .into_iter()
+            .filter(|m| m.genre() == Genre::SwapBranches)
+            .collect();
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(
+            mutations[0].to_string(),
+            "swap branches of `if a == 0` in sign at lib.rs:4:22"
+        );
+        assert_eq!(
+            mutations[0].mutated_code(),
+            "fn sign(a: i32) -> i32 {\n    if a < 0 {\n        -1\n    } else if a == 0 {\n        1\n    } else {\n        0\n    } /* ~ changed by cargo-mutants ~ */\n}\n"
+        );
+    }
+
+    #[test]
+    fn long_swapped_conditions_are_described_from_the_source_and_shortened() {
+        let code = "fn pick(a: &[u8]) -> u8 {\n    if a.len() > 2 && a[0] == b'x' {\n        1\n    } else {\n        2\n    }\n}\n\
+            fn check(config: &Config) -> bool {\n    if config.retries_allowed_after_failure() > config.maximum_retries_for_client() {\n        true\n    } else {\n        false\n    }\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let descriptions: Vec<String> = discover_code(code, &options)
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::SwapBranches)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "swap branches of `if a.len() > 2 && a[0] == b'x'` in pick at lib.rs:2:36",
+                "swap branches of `if config.retries_allowed_after_failure() > config.m…` in check at lib.rs:9:85",
+            ]
+        );
+    }
+
+    #[test]
+    fn call_statements_are_deleted_unless_skipped() {
+        let code = "fn save(&mut self, x: u8) -> Result<()> {\n    log_event(x);\n    self.flush()?;\n    drop(self.guard.take());\n    self.m.lock().unwrap().push(x);\n    self.notify(x);\n    let _y = f();\n    self.count += 1;\n    g()\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        options.skip_calls = ["drop", "lock", "notify"].map(str::to_owned).to_vec();
+        let mutations: Vec<Mutation> = discover_code(code, &options)
+            .mutations
+            .into_iter()
+            .filter(|m| m.genre() == Genre::Statement)
+            .collect();
+        let descriptions: Vec<String> = mutations.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "delete call to `log_event` in save at lib.rs:2:5",
+                "delete call to `flush` in save at lib.rs:3:5",
+                "delete call to `push` in save at lib.rs:5:5",
+            ]
+        );
+        assert!(mutations[1]
+            .mutated_code()
+            .contains("\n    /* ~ changed by cargo-mutants ~ */ \n    drop("));
+    }
+
+    #[test]
+    fn only_the_outermost_call_is_matched_against_skip_calls() {
+        let code = "fn fill(&mut self) {\n    self.m.lock();\n    self.m.lock().unwrap().push(1);\n    Vec::with_capacity(4).extend(self.items());\n    Vec::<u8>::with_capacity(4);\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        options.skip_calls = DEFAULT_SKIP_CALLS.iter().map(|&s| s.to_owned()).collect();
+        let descriptions: Vec<String> = discover_code(code, &options)
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Statement)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [
+                "delete call to `push` in fill at lib.rs:3:5",
+                "delete call to `extend` in fill at lib.rs:4:5",
+            ]
+        );
+    }
+
+    #[test]
+    fn unary_minus_is_deleted() {
+        let code = "fn back(offset: i64) -> i64 {\n    -offset * *SCALE\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations: Vec<Mutation> = discover_code(code, &options)
+            .mutations
+            .into_iter()
+            .filter(|m| m.genre() == Genre::UnaryOperator)
+            .collect();
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(mutations[0].to_string(), "delete - in back at lib.rs:2:5");
+        assert_eq!(
+            mutations[0].mutated_code(),
+            "fn back(offset: i64) -> i64 {\n    /* ~ changed by cargo-mutants ~ */ offset * *SCALE\n}\n"
+        );
+    }
+
+    #[test]
+    fn closures_passed_to_iterator_adapters_are_replaced() {
+        let code = "fn active(items: &[Item]) -> usize {\n\
+            \x20   items.iter().filter(|x| x.active).count()\n\
+            }\n\
+            fn total(items: &[Item]) -> u32 {\n\
+            \x20   items.iter().map(|x| x.size).sum::<u32>()\n\
+            }\n\
+            fn names(items: &[Item]) -> Vec<String> {\n\
+            \x20   items.iter().map(|x| x.name.clone()).collect()\n\
+            }\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let discovery = discover_code(code, &options);
+        let closure_mutants: Vec<String> = discovery
+            .mutations
+            .iter()
+            .filter(|m| m.genre() == Genre::Closure)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(
+            closure_mutants,
+            [
+                "replace closure in active with true at lib.rs:2:29",
+                "replace closure in active with false at lib.rs:2:29",
+                "replace closure in total with 0 at lib.rs:5:26",
+                "replace closure in total with 1 at lib.rs:5:26",
+            ]
+        );
+    }
+
     #[test]
-    fn path_is_result() {
-        let path: syn::Path = syn::parse_quote! { Result<(), ()> };
-        assert!(super::path_is_result(&path));
+    fn paired_method_names_are_swapped() {
+        let code =
+            "fn pick(v: &[u8]) -> Option<&u8> {\n    v.iter().filter(|x| **x > 1).max()\n}\n";
+        let mut options = Options::default();
+        options.mutate_operators = true;
+        let mutations: Vec<Mutation> = discover_code(code, &options)
+            .mutations
+            .into_iter()
+            .filter(|m| m.genre() == Genre::MethodName)
+            .collect();
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(
+            mutations[0].to_string(),
+            "replace max with min in pick at lib.rs:2:34"
+        );
+        assert!(mutations[0]
+            .mutated_code()
+            .contains(".min /* ~ changed by cargo-mutants ~ */()"));
     }
 }
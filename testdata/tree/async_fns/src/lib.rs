@@ -0,0 +1,53 @@
+//! Async functions, for testing how their mutants are built and classified.
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Double a number, after giving other tasks a chance to run.
+pub async fn double(x: u32) -> u32 {
+    tokio::task::yield_now().await;
+    x * 2
+}
+
+/// Return the length of a name, as a future that's not written as an `async fn`.
+pub fn name_len(name: &str) -> impl Future<Output = usize> {
+    std::future::ready(name.len())
+}
+
+/// True when the service is ready.
+async fn is_ready() -> bool {
+    true
+}
+
+/// Wait, polling as long as necessary, until the service is ready.
+pub async fn wait_until_ready() {
+    while !is_ready().await {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Print a progress message, which is not tested.
+pub async fn report_progress(done: usize) {
+    println!("{} done", done);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn double_is_twice() {
+        assert_eq!(double(3).await, 6);
+    }
+
+    #[tokio::test]
+    async fn name_len_counts_bytes() {
+        assert_eq!(name_len("tokio").await, 5);
+    }
+
+    #[tokio::test]
+    async fn ready_eventually() {
+        wait_until_ready().await;
+        report_progress(1).await;
+    }
+}
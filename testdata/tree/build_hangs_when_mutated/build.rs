@@ -0,0 +1,15 @@
+//! A build script that hangs when `should_build` is mutated to return false, as a stand
+//! in for a mutant that sends the compiler into an infinite loop.
+
+use std::fs;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn main() {
+    let lib = fs::read_to_string("src/lib.rs").unwrap();
+    if lib.contains("false /* ~ changed by cargo-mutants ~ */") {
+        loop {
+            sleep(Duration::from_secs(1));
+        }
+    }
+}
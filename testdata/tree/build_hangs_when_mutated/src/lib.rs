@@ -0,0 +1,13 @@
+//! An example of a function whose mutant makes the build hang.
+
+pub fn should_build() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn builds() {
+        assert!(super::should_build());
+    }
+}
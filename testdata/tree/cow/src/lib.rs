@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+/// Remove surrounding double quotes, borrowing the input if there are none.
+pub fn trim_quotes(s: &str) -> Cow<'_, str> {
+    if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        Cow::Owned(s[1..s.len() - 1].to_owned())
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Replace Windows line endings with Unix ones, borrowing the input if there are none.
+pub fn normalize_newlines(s: &str) -> Cow<'_, str> {
+    if s.contains("\r\n") {
+        Cow::Owned(s.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Remove a UTF-8 byte order mark from the start of the input.
+pub fn strip_bom(bytes: &[u8]) -> Cow<'_, [u8]> {
+    match bytes.strip_prefix(&[0xef, 0xbb, 0xbf]) {
+        Some(rest) => Cow::Borrowed(rest),
+        None => Cow::Borrowed(bytes),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trim_quotes_removes_only_surrounding_quotes() {
+        assert_eq!(trim_quotes("\"hello\""), "hello");
+        assert_eq!(trim_quotes("plain"), "plain");
+        assert_eq!(trim_quotes("\""), "\"");
+        assert_eq!(trim_quotes("\"\""), "");
+        assert_eq!(trim_quotes("\"plain"), "\"plain");
+        assert_eq!(trim_quotes("plain\""), "plain\"");
+        assert!(matches!(trim_quotes("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn normalize_newlines_returns_something() {
+        // This doesn't check what's returned, so some mutants are missed.
+        assert!(!normalize_newlines("a\r\nb").is_empty());
+    }
+
+    #[test]
+    fn strip_bom_keeps_other_bytes() {
+        let bytes: &[u8] = b"abc";
+        assert_eq!(strip_bom(bytes), bytes);
+    }
+}
@@ -0,0 +1,38 @@
+//! Functions that are only built with some features, so that their mutants are only
+//! generated when those features are on.
+
+pub fn plain() -> u32 {
+    10
+}
+
+#[cfg(feature = "std")]
+pub fn with_std() -> u32 {
+    20
+}
+
+#[cfg(all(unix, feature = "fancy"))]
+pub fn fancy() -> u32 {
+    30
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_is_ten() {
+        assert_eq!(plain(), 10);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn with_std_is_twenty() {
+        assert_eq!(with_std(), 20);
+    }
+
+    #[cfg(all(unix, feature = "fancy"))]
+    #[test]
+    fn fancy_is_thirty() {
+        assert_eq!(fancy(), 30);
+    }
+}
@@ -9,10 +9,17 @@ fn should_stop() -> bool {
     true
 }
 
+/// Also skipped: the attribute is matched textually, so the `cfg_attr`
+/// predicate doesn't matter.
+#[cfg_attr(test, mutants::skip)]
+fn should_really_stop() -> bool {
+    true
+}
+
 pub fn controlled_loop() {
     for i in 0.. {
         println!("{}", i);
-        if should_stop() {
+        if should_stop() && should_really_stop() {
             break;
         }
     }
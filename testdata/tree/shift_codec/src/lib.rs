@@ -0,0 +1,25 @@
+/// Encode a u16 as two big-endian bytes.
+pub fn encode_u16(x: u16) -> [u8; 2] {
+    [(x >> 8) as u8, x as u8]
+}
+
+/// Decode two big-endian bytes into a u16.
+pub fn decode_u16(b: [u8; 2]) -> u16 {
+    ((b[0] as u16) << 8) | b[1] as u16
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(encode_u16(0x1234), [0x12, 0x34]);
+    }
+
+    #[test]
+    fn decode_low_byte() {
+        // This doesn't test the high byte, so mutations of the shift are missed.
+        assert_eq!(decode_u16([0, 5]), 5);
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Return the even numbers less than `limit`.
+fn evens(limit: u32) -> Vec<u32> {
+    (0..limit).filter(|i| i % 2 == 0).collect()
+}
+
+/// Iterate the odd numbers less than `limit`.
+fn odds(limit: u32) -> impl Iterator<Item = u32> {
+    (0..limit).filter(|i| i % 2 == 1)
+}
+
+/// Return the total length of some words.
+fn total_length(words: &[&str]) -> usize {
+    words.iter().map(|w| w.len()).sum::<usize>()
+}
+
+/// Count how many times each word occurs.
+fn word_counts(text: &str) -> BTreeMap<&str, usize> {
+    let mut counts = BTreeMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word).or_default() += 1;
+    }
+    counts
+}
+
+/// Return the distinct bytes in a buffer.
+fn distinct(buf: &[u8]) -> BTreeSet<u8> {
+    buf.iter().copied().collect()
+}
+
+/// Return a shared copy of a list of names.
+fn shared_names(names: &[&str]) -> std::rc::Rc<Vec<String>> {
+    std::rc::Rc::new(names.iter().map(|s| s.to_string()).collect())
+}
+
+/// Return the largest number in a list.
+fn largest(v: &[u32]) -> Option<u32> {
+    v.iter().copied().max()
+}
+
+/// Return the bytes of a header, without the trailing newline.
+fn header(buf: &[u8]) -> &[u8] {
+    buf.split(|b| *b == b'\n').next().unwrap()
+}
+
+#[test]
+fn evens_below_four() {
+    assert_eq!(evens(4), [0, 2]);
+}
+
+#[test]
+fn odds_below_five() {
+    assert_eq!(odds(5).collect::<Vec<_>>(), [1, 3]);
+}
+
+#[test]
+fn count_repeated_words() {
+    let counts = word_counts("a b a");
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts["a"], 2);
+    assert_eq!(counts["b"], 1);
+}
+
+#[test]
+fn distinct_bytes() {
+    assert_eq!(distinct(b"abca").into_iter().collect::<Vec<_>>(), b"abc");
+}
+
+#[test]
+fn shared_names_are_copied() {
+    assert_eq!(*shared_names(&["a", "b"]), ["a", "b"]);
+}
+
+#[test]
+fn total_length_of_two_words() {
+    assert_eq!(total_length(&["ab", "cde"]), 5);
+}
+
+#[test]
+fn largest_of_three() {
+    assert_eq!(largest(&[3, 9, 4]), Some(9));
+}
+
+#[test]
+fn header_of_two_lines() {
+    assert_eq!(header(b"one\ntwo\n"), b"one");
+}
@@ -6,11 +6,13 @@
 //! 1. The tests should all pass in a clean tree.
 //! 2. Every mutant is caught.
 
-#![allow(unused, dead_code)]
+#![allow(unused, dead_code, clippy::all)]
 
+mod collections;
 mod inside_mod;
 mod item_mod;
 mod methods;
 mod nested_function;
+mod option;
 mod result;
 pub mod simple_fns;
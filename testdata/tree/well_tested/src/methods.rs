@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 struct Foo {
     i: u32,
 }
@@ -19,6 +21,121 @@ impl Default for Foo {
     }
 }
 
+#[derive(Default)]
+struct Counter {
+    n: u32,
+}
+
+impl Counter {
+    fn starting_at(n: u32) -> Self {
+        Counter { n }
+    }
+}
+
+trait Describe {
+    fn name(&self) -> String;
+
+    fn shout(&self) -> String {
+        self.name().to_uppercase()
+    }
+}
+
+impl Describe for Counter {
+    fn name(&self) -> String {
+        format!("counter {}", self.n)
+    }
+}
+
+/// Settings with hand-written defaults.
+struct Settings {
+    retries: u32,
+    verbose: bool,
+    name: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            retries: 3,
+            verbose: true,
+            name: "main".into(),
+        }
+    }
+}
+
+#[test]
+fn default_settings() {
+    let settings = Settings::default();
+    assert_eq!(settings.retries, 3);
+    assert!(settings.verbose);
+    assert_eq!(settings.name, "main");
+}
+
+/// A named count, which has no `Default`.
+struct Tally {
+    count: u32,
+    label: String,
+}
+
+impl Tally {
+    fn new(label: &str) -> Self {
+        Tally {
+            count: 1,
+            label: label.to_owned(),
+        }
+    }
+}
+
+#[test]
+fn new_tally() {
+    let tally = Tally::new("apples");
+    assert_eq!(tally.count, 1);
+    assert_eq!(tally.label, "apples");
+}
+
+/// Versions compare by their major number, and then by their minor number.
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+    }
+}
+
+#[test]
+fn versions_compare_by_major_then_minor() {
+    let a = Version { major: 1, minor: 2 };
+    let b = Version { major: 1, minor: 3 };
+    assert_eq!(a.cmp(&b), Ordering::Less);
+    assert_eq!(b.cmp(&a), Ordering::Greater);
+    assert_eq!(a.cmp(&a), Ordering::Equal);
+    assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+    assert_eq!(b.partial_cmp(&a), Some(Ordering::Greater));
+    assert_eq!(a.partial_cmp(&a), Some(Ordering::Equal));
+}
+
+#[test]
+fn counter_starts_where_asked() {
+    assert_eq!(Counter::starting_at(3).n, 3);
+}
+
+#[test]
+fn shout_counter() {
+    assert_eq!(Counter::starting_at(2).shout(), "COUNTER 2");
+}
+
 #[test]
 fn double() {
     let mut foo = Foo::new();
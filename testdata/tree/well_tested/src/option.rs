@@ -0,0 +1,10 @@
+/// Return the index of the first element equal to `needle`, if any.
+fn find_position(haystack: &[u32], needle: u32) -> Option<usize> {
+    haystack.iter().position(|x| *x == needle)
+}
+
+#[test]
+fn find_position_of_present_and_missing_values() {
+    assert_eq!(find_position(&[1, 2, 3], 3), Some(2));
+    assert_eq!(find_position(&[1, 2, 3], 4), None);
+}
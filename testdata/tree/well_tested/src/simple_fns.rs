@@ -29,6 +29,79 @@ pub fn double_string(s: &str) -> String {
     r
 }
 
+/// Return the mean of two numbers.
+fn mean(a: f64, b: f64) -> f64 {
+    (a + b) / 2.0
+}
+
+/// Return the length of a name and its uppercase form.
+fn measure_name(name: &str) -> (usize, String) {
+    (name.len(), name.to_uppercase())
+}
+
+/// True if `x` is in the half-open range from `lo` to `hi`.
+fn in_range(x: u32, lo: u32, hi: u32) -> bool {
+    lo <= x && x < hi
+}
+
+/// Remove and return the first item, or 0 if there are none.
+fn take_one(items: &mut Vec<u32>) -> u32 {
+    if !items.is_empty() {
+        items.remove(0)
+    } else {
+        0
+    }
+}
+
+/// Return the low byte of a word.
+fn low_byte(x: u16) -> u16 {
+    x & 0xff
+}
+
+/// Flip the bits of `x` that are set in `mask`.
+fn toggle(x: u8, mask: u8) -> u8 {
+    x ^ mask
+}
+
+/// Name a small number.
+fn number_name(n: u32) -> &'static str {
+    match n {
+        0 => "zero",
+        1 => "one",
+        _ => "many",
+    }
+}
+
+/// Return the opposite of a number.
+fn opposite(x: i32) -> i32 {
+    -x
+}
+
+/// A greeting, as an opaque type.
+fn greeting() -> impl std::fmt::Display {
+    "hello"
+}
+
+/// Return a copy of the first item, or the default if there are none.
+fn first_or_default<T: Default + Clone>(items: &[T]) -> T {
+    items.first().cloned().unwrap_or_default()
+}
+
+/// Return a function that adds `n` to its argument.
+fn adder(n: u32) -> impl Fn(u32) -> u32 {
+    move |x| x + n
+}
+
+/// Convert kibibytes to bytes, at compile time if possible.
+const fn kibibytes(n: u32) -> u32 {
+    n * 1024
+}
+
+/// How long to wait before the next retry.
+fn backoff(retries: u64) -> std::time::Duration {
+    std::time::Duration::from_secs(retries * 2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,5 +129,35 @@ mod tests {
         let mut a = 0;
         returns_unit(&mut a);
         assert_eq!(a, 1);
+
+        assert_eq!(mean(2.0, 4.0), 3.0);
+
+        assert_eq!(greeting().to_string(), "hello");
+
+        assert_eq!(adder(2)(3), 5);
+
+        assert_eq!(first_or_default(&[3, 4]), 3);
+
+        assert_eq!(measure_name("ab"), (2, "AB".to_owned()));
+
+        assert!(in_range(1, 1, 3));
+        assert!(!in_range(3, 1, 3));
+        assert!(!in_range(0, 1, 3));
+
+        assert_eq!(take_one(&mut vec![7, 8]), 7);
+        assert_eq!(take_one(&mut Vec::new()), 0);
+
+        assert_eq!(low_byte(0x1234), 0x34);
+        assert_eq!(toggle(0b1100, 0b0110), 0b1010);
+
+        assert_eq!(number_name(0), "zero");
+        assert_eq!(number_name(1), "one");
+        assert_eq!(number_name(5), "many");
+
+        assert_eq!(opposite(3), -3);
+
+        assert_eq!(kibibytes(2), 2048);
+
+        assert_eq!(backoff(3), std::time::Duration::from_secs(6));
     }
 }
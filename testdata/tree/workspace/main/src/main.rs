@@ -0,0 +1,14 @@
+use cargo_mutants_testdata_workspace_utils::triple;
+
+fn main() {
+    println!("{}", triple_plus_one(4));
+}
+
+fn triple_plus_one(a: u32) -> u32 {
+    triple(a) + 1
+}
+
+#[test]
+fn triple_plus_one_of_four_is_thirteen() {
+    assert_eq!(triple_plus_one(4), 13);
+}
@@ -0,0 +1,8 @@
+pub fn triple(a: u32) -> u32 {
+    a * 3
+}
+
+#[test]
+fn triple_of_two_is_six() {
+    assert_eq!(triple(2), 6);
+}
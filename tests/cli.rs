@@ -22,12 +22,18 @@ lazy_static! {
     static ref SIZE_RE: Regex = Regex::new(r"\d+ MB").unwrap();
 }
 
+/// Run cargo-mutants, without the GitHub Actions environment that would turn on
+/// annotations when these tests run in CI.
 fn run_assert_cmd() -> assert_cmd::Command {
-    assert_cmd::Command::new(MAIN_BINARY.as_os_str())
+    let mut cmd = assert_cmd::Command::new(MAIN_BINARY.as_os_str());
+    cmd.env_remove("GITHUB_ACTIONS");
+    cmd
 }
 
 fn run() -> std::process::Command {
-    Command::new(MAIN_BINARY.as_os_str())
+    let mut cmd = Command::new(MAIN_BINARY.as_os_str());
+    cmd.env_remove("GITHUB_ACTIONS");
+    cmd
 }
 
 trait CommandInstaExt {
@@ -101,9 +107,108 @@ fn uses_cargo_env_var_to_run_cargo_so_invalid_value_fails() {
                 ))
             .and(predicates::str::contains(bogus_cargo)),
         )
-        .code(1);
-    // TODO: Preferably there would be a more specific exit code for the
-    // clean build failing.
+        .code(70); // exit_code::SOFTWARE
+}
+
+#[test]
+fn dir_that_is_not_a_crate_is_an_error() {
+    let tmp = tempdir().unwrap();
+    run_assert_cmd()
+        .args(["mutants", "-d"])
+        .arg(tmp.path().join("nonexistent"))
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "nonexistent does not exist: specify a crate directory\n",
+        ));
+    run_assert_cmd()
+        .args(["mutants", "--json", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .code(1)
+        .stdout(contains(r#""event":"error""#))
+        .stderr(contains(
+            "does not contain a Cargo.toml: specify a crate directory\n",
+        ));
+}
+
+#[test]
+fn manifest_path_lists_the_same_mutants_as_dir() {
+    let list = |args: &[&str]| {
+        run_assert_cmd()
+            .args([
+                "mutants",
+                "--list",
+                "-p",
+                "cargo_mutants_testdata_workspace_utils",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let by_dir = list(&["-d", "testdata/tree/workspace"]);
+    let by_manifest = list(&["--manifest-path", "testdata/tree/workspace/Cargo.toml"]);
+    assert!(by_manifest.status.success());
+    assert!(String::from_utf8_lossy(&by_manifest.stdout).contains("utils/src/lib.rs:1: "));
+    assert_eq!(by_dir.stdout, by_manifest.stdout);
+}
+
+#[test]
+fn manifest_path_must_name_a_cargo_toml_and_excludes_dir() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "--manifest-path",
+            "testdata/tree/factorial/src/bin/main.rs",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "--manifest-path must be a path to a Cargo.toml file: testdata/tree/factorial/src/bin/main.rs\n",
+        ));
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "--manifest-path",
+            "testdata/tree/factorial/Cargo.toml",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "--dir and --manifest-path can't be used together\n",
+        ));
+}
+
+#[test]
+fn output_is_written_next_to_the_tree_given_by_dir() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let cwd = tempdir().unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--check", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .current_dir(cwd.path())
+        .assert()
+        .success();
+    assert!(tmp_src_dir
+        .path()
+        .join("mutants.out/outcomes.json")
+        .is_file());
+    assert!(!cwd.path().join("mutants.out").exists());
+}
+
+#[test]
+fn help_describes_exit_codes() {
+    run_assert_cmd()
+        .args(["mutants", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Error codes:"))
+        .stdout(contains("2 some mutants were not caught by tests."))
+        .stdout(contains("70 internal error."));
 }
 
 #[test]
@@ -136,119 +241,1933 @@ fn list_mutants_in_factorial_json() {
 }
 
 #[test]
-fn list_mutants_with_dir_option() {
+fn completions_script_for_bash_names_the_flags() {
+    // Completions don't need a source tree.
+    let tmp = tempdir().unwrap();
+    let output = run_assert_cmd()
+        .args(["mutants", "--completions", "bash"])
+        .current_dir(tmp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    for flag in ["--check", "--timeout", "--shard", "--re", "--completions"] {
+        assert!(script.contains(flag), "{} not in {}", flag, script);
+    }
+    assert!(script.contains("complete -o default -F _cargo_mutants cargo-mutants\n"));
+    for shell in ["zsh", "fish", "powershell"] {
+        run_assert_cmd()
+            .args(["mutants", "--completions", shell])
+            .assert()
+            .success()
+            .stdout(contains("in-diff"));
+    }
+    run_assert_cmd()
+        .args(["mutants", "--completions", "tcsh"])
+        .assert()
+        .code(1)
+        .stderr(contains("unknown shell \"tcsh\""));
+}
+
+#[test]
+fn count_matches_the_number_of_listed_mutants() {
+    let listed = run_assert_cmd()
+        .args(["mutants", "--list", "--json", "--re", "simple_fns|option"])
+        .current_dir("testdata/tree/well_tested")
+        .output()
+        .unwrap();
+    let listed: serde_json::Value = serde_json::from_slice(&listed.stdout).unwrap();
+    let n_listed = listed.as_array().unwrap().len();
+    run_assert_cmd()
+        .args(["mutants", "--count", "--re", "simple_fns|option"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", n_listed));
+
+    let counted = run_assert_cmd()
+        .args(["mutants", "--count", "--json", "--re", "simple_fns|option"])
+        .current_dir("testdata/tree/well_tested")
+        .output()
+        .unwrap();
+    let counted: serde_json::Value = serde_json::from_slice(&counted.stdout).unwrap();
+    assert_eq!(counted["total"], n_listed);
+    let files = counted["files"].as_object().unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        ["src/option.rs", "src/simple_fns.rs"]
+    );
+    assert_eq!(
+        files.values().map(|n| n.as_u64().unwrap()).sum::<u64>(),
+        n_listed as u64
+    );
+    assert!(!Path::new("testdata/tree/well_tested/mutants.out").exists());
+}
+
+#[test]
+fn count_cant_be_used_with_list() {
+    for list in ["--list", "--list-files"] {
+        run_assert_cmd()
+            .args(["mutants", "--count", list])
+            .current_dir("testdata/tree/factorial")
+            .assert()
+            .code(1)
+            .stdout(contains(
+                "--count can't be used with --list or --list-files",
+            ));
+    }
+}
+
+#[test]
+fn count_of_zero_is_only_an_error_with_fail_on_empty() {
+    run_assert_cmd()
+        .args(["mutants", "--count", "--re", "no such function"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .success()
+        .stdout("0\n");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--count",
+            "--fail-on-empty",
+            "--re",
+            "no such function",
+        ])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(5) // exit_code::EMPTY
+        .stdout("0\n")
+        .stderr(contains("no mutants found"));
+}
+
+#[test]
+fn list_mutants_in_factorial_with_int_values() {
     run()
         .arg("mutants")
         .arg("--list")
-        .arg("--dir")
-        .arg("testdata/tree/factorial")
-        .assert_insta("list_mutants_with_dir_option");
+        .args(["--int-value", "2", "--int-value", "MAX"])
+        .current_dir("testdata/tree/factorial")
+        .assert_insta("list_mutants_in_factorial_with_int_values");
 }
 
 #[test]
-fn list_mutants_with_diffs_in_factorial() {
-    run()
+fn list_mutants_well_tested_without_operators() {
+    run_assert_cmd()
         .arg("mutants")
         .arg("--list")
-        .arg("--diff")
+        .arg("--no-operators")
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(contains("replace mean -> f64 with 0.0"))
+        .stdout(contains("replace + with -").not());
+}
+
+#[test]
+fn list_mutants_well_tested_with_exclude_functions_config() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "exclude_functions = [\"^divisible_by_three$\", \"^Counter::\"]\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--list")
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("replace mean -> f64 with 0.0"))
+        .stdout(contains("divisible_by_three").not())
+        .stdout(contains("Counter::").not());
+}
+
+#[test]
+fn function_selects_functions_by_name_or_glob() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "--function",
+            "Foo::double",
+            "--function",
+            "Counter::*",
+            "--function",
+            "Foo::dubble",
+            "-f",
+            "src/methods.rs",
+        ])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(contains("replace Foo::double with ()\n"))
+        .stdout(contains("replace *= with /= in Foo::double\n"))
+        .stdout(contains(
+            "replace Counter::name -> String with \"\".into()\n",
+        ))
+        .stdout(contains("Foo::new").not())
+        .stdout(contains("Describe::shout").not())
+        .stderr(contains(
+            "warning: --function \"Foo::dubble\" matched no functions\n",
+        ))
+        .stderr(contains("\"Foo::double\"").not());
+}
+
+#[test]
+fn only_pub_lists_only_public_functions() {
+    let output = run_assert_cmd()
+        .args(["mutants", "--list", "--only-pub", "-f", "src/methods.rs"])
+        .current_dir("testdata/tree/well_tested")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let listed = String::from_utf8(output.stdout).unwrap();
+    assert!(listed.contains("replace Foo::new -> Foo with Default::default()\n"));
+    // Methods of trait impls are taken to be public, although this trait isn't.
+    assert!(listed.contains("replace Counter::name -> String with \"\".into()\n"));
+    // `Describe` is a private trait.
+    assert!(!listed.contains("Describe::shout"), "{}", listed);
+    let all = run_assert_cmd()
+        .args(["mutants", "--count", "-f", "src/methods.rs"])
+        .current_dir("testdata/tree/well_tested")
+        .output()
+        .unwrap();
+    let all: usize = String::from_utf8(all.stdout)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+    assert!(listed.lines().count() < all);
+}
+
+#[test]
+fn list_mutants_well_tested_in_files_matching_globs() {
+    run_assert_cmd()
+        .args(["mutants", "--list"])
+        .args(["-f", "src/*_mod.rs", "--file", "src/nested_function.rs"])
+        .args(["-f", "**/missing.rs"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(
+            "src/inside_mod.rs:3: replace outer::inner::name -> & 'static str with \"\"
+src/inside_mod.rs:3: replace outer::inner::name -> & 'static str with \"xyzzy\"
+src/nested_function.rs:1: replace has_nested -> u32 with 0
+src/nested_function.rs:1: replace has_nested -> u32 with 1
+src/nested_function.rs:2: replace has_nested::inner -> u32 with 0
+src/nested_function.rs:2: replace has_nested::inner -> u32 with 1
+src/nested_function.rs:5:13: replace * with / in has_nested
+",
+        )
+        .stderr(contains(
+            "warning: --file \"**/missing.rs\" did not match any source files\n",
+        ));
+}
+
+#[test]
+fn list_mutants_well_tested_excluding_files_matching_globs() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--verbose"])
+        .args(["-e", "**/*_mod.rs", "--exclude", "src/[mo]*.rs"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(contains("src/nested_function.rs:"))
+        .stdout(contains("src/simple_fns.rs:"))
+        .stdout(contains("_mod.rs:").not())
+        .stdout(contains("src/methods.rs:").not())
+        .stdout(contains("src/option.rs:").not())
+        .stderr(contains("--exclude glob \"**/*_mod.rs\"\n"))
+        .stderr(contains(
+            "skip src/inside_mod.rs: matches --exclude \"**/*_mod.rs\"\n",
+        ))
+        .stderr(contains(
+            "skip src/option.rs: matches --exclude \"src/[mo]*.rs\"\n",
+        ));
+}
+
+#[test]
+fn verbose_notes_operators_skipped_in_macro_arguments() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let main_path = tmp_src_dir.path().join("src/bin/main.rs");
+    let mut code = fs::read_to_string(&main_path).unwrap();
+    code.push_str("\nfn check(a: bool, b: bool) {\n    assert!(a && b);\n}\n");
+    fs::write(&main_path, &code).unwrap();
+    let line = code.lines().count() - 1;
+    run_assert_cmd()
+        .args(["mutants", "--list", "--verbose", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("&&").not())
+        .stderr(contains(format!(
+            "skip src/bin/main.rs:{}: operators in assert! arguments aren't mutated\n",
+            line
+        )));
+}
+
+#[test]
+fn re_matches_each_mutant_as_listed() {
+    let list_output = run_assert_cmd()
+        .args(["mutants", "--list"])
+        .current_dir("testdata/tree/well_tested")
+        .output()
+        .unwrap();
+    let listed = String::from_utf8(list_output.stdout).unwrap();
+    // Some names, such as for functions with the same name in different methods, are
+    // listed more than once.
+    for line in listed.lines() {
+        let count = listed.lines().filter(|other| *other == line).count();
+        run_assert_cmd()
+            .args(["mutants", "--list", "--re"])
+            .arg(format!("^{}$", regex::escape(line)))
+            .current_dir("testdata/tree/well_tested")
+            .assert()
+            .success()
+            .stdout(format!("{}\n", line).repeat(count));
+    }
+}
+
+#[test]
+fn re_options_select_any_matching_mutant_to_test() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--re",
+            "factorial -> u32 with 0",
+            "--re",
+        ])
+        .arg(r"\*=")
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("found 2 mutations to test"));
+}
+
+#[test]
+fn exclude_re_drops_mutants_after_re_selects_them() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "--re",
+            "factorial",
+            "--exclude-re",
+            "with 1$",
+        ])
+        .args(["--exclude-re", r"\*="])
         .current_dir("testdata/tree/factorial")
-        .assert_insta("list_mutants_with_diffs_in_factorial");
+        .assert()
+        .success()
+        .stdout(
+            "src/bin/main.rs:7: replace factorial -> u32 with 0
+src/bin/main.rs:9:15: replace ..= with .. in factorial
+",
+        );
+}
+
+#[test]
+fn output_switches_are_read_from_config_unless_no_config() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "show_times = false\nprint_caught = true\n",
+    )
+    .unwrap();
+    let run_factorial = |args: &[&str]| {
+        run_assert_cmd()
+            .args(["mutants", "--re", "factorial -> u32 with 0", "-d"])
+            .arg(tmp_src_dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    run_factorial(&[])
+        .stdout(contains("replace factorial -> u32 with 0 ... caught\n"))
+        .stdout(contains("source tree ... ok\n"));
+    run_factorial(&["--no-config"])
+        .stdout(contains("caught\n").not())
+        .stdout(is_match(r"source tree \.\.\. ok in \d+\.\d{3}s").unwrap());
+}
+
+#[test]
+fn unknown_config_keys_are_errors_unless_no_config() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "show_time = false\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--list", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(contains("parse config"))
+        .stdout(contains("unknown field `show_time`"));
+    run_assert_cmd()
+        .args(["mutants", "--list", "--no-config", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("replace factorial -> u32 with 0"));
+}
+
+#[test]
+fn re_filters_from_config_and_args_are_counted_as_skipped() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "examine_re = [\"factorial\"]\nexclude_re = [\"with 1$\"]\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--exclude-re", r"\*=", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("skipped 2 mutants: don't match --re\n"))
+        .stdout(contains("skipped 2 mutants: match --exclude-re\n"))
+        .stdout(contains("found 2 mutations to test\n"));
+}
+
+#[test]
+fn shards_together_list_every_mutant_once() {
+    let list = |args: &[&str]| {
+        let output = run_assert_cmd()
+            .args(["mutants", "--list"])
+            .args(args)
+            .current_dir("testdata/tree/well_tested")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect_vec()
+    };
+    let all = list(&[]);
+    let mut sharded = Vec::new();
+    for k in 0..3 {
+        let shard = list(&["--shard", &format!("{}/3", k)]);
+        assert!(shard.len() > all.len() / 6, "shard {} is too small", k);
+        sharded.extend(shard);
+    }
+    sharded.sort();
+    let mut all_sorted = all;
+    all_sorted.sort();
+    assert_eq!(sharded, all_sorted);
+}
+
+#[test]
+fn shard_skips_mutants_in_other_shards() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--shard", "1/2", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("skipped 5 mutants: in other shards\n"))
+        .stdout(contains("found 1 mutation to test\n"));
+}
+
+#[test]
+fn invalid_shard_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--shard", "3/3"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .failure()
+        .stderr(contains(
+            "invalid shard \"3/3\": expected k/n, with k from 0 to n-1",
+        ));
+}
+
+#[test]
+fn jobs_record_outcomes_in_the_order_of_the_mutants() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--jobs", "3", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("copy scratch directory for job 3 ... done\n"))
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"));
+    let read_json = |name: &str| -> serde_json::Value {
+        serde_json::from_str(
+            &fs::read_to_string(tmp_src_dir.path().join("mutants.out").join(name)).unwrap(),
+        )
+        .unwrap()
+    };
+    let mutants = read_json("mutants.json");
+    let outcomes = read_json("outcomes.json");
+    let outcome_mutants: Vec<&serde_json::Value> = outcomes["outcomes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .skip(2) // the source tree and baseline
+        .map(|outcome| &outcome["scenario"]["Mutant"]["mutation"])
+        .collect();
+    assert_eq!(
+        outcome_mutants,
+        mutants.as_array().unwrap().iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn zero_jobs_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--jobs", "0"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(1)
+        .stdout(contains("--jobs must be at least 1"));
+}
+
+#[test]
+fn invalid_re_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--re", "(unclosed"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .code(1)
+        .stdout(contains("parse --re regex \"(unclosed\""));
+}
+
+#[test]
+fn in_diff_lists_only_mutants_of_changed_lines() {
+    // The function is included because its body changed, even though its signature
+    // didn't. Paths in the diff are relative to the top of the repository.
+    run_assert_cmd()
+        .args(["mutants", "--list", "--in-diff", "-"])
+        .current_dir("testdata/tree/factorial")
+        .write_stdin(
+            "\
+--- a/testdata/tree/factorial/src/bin/main.rs
++++ b/testdata/tree/factorial/src/bin/main.rs
+@@ -10 +10 @@
+-        a *= i;
++        a *= i; // changed
+",
+        )
+        .assert()
+        .success()
+        .stdout(
+            "\
+src/bin/main.rs:7: replace factorial -> u32 with 0
+src/bin/main.rs:7: replace factorial -> u32 with 1
+src/bin/main.rs:10:11: replace *= with /= in factorial
+",
+        );
+}
+
+#[test]
+fn in_diff_from_a_file_skips_untouched_mutants() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let diff_path = tmp_src_dir.path().join("changes.patch");
+    fs::write(
+        &diff_path,
+        "--- a/src/bin/main.rs\n+++ b/src/bin/main.rs\n@@ -3 +3 @@\n-x\n+y\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--in-diff"])
+        .arg(&diff_path)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains(
+            "src/bin/main.rs:1: replace main with () ... NOT CAUGHT\n",
+        ))
+        .stdout(contains("skipped 5 mutants: not changed in --in-diff\n"))
+        .stdout(contains("found 1 mutation to test\n"));
+}
+
+#[test]
+fn in_diff_that_doesnt_exist_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--in-diff", "nonexistent.patch"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(1)
+        .stdout(contains("read diff from nonexistent.patch"));
+}
+
+#[test]
+fn list_mutants_in_one_package_of_a_workspace() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "-p",
+            "cargo_mutants_testdata_workspace_utils",
+        ])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .success()
+        .stdout(
+            "\
+utils/src/lib.rs:1: replace triple -> u32 with 0
+utils/src/lib.rs:1: replace triple -> u32 with 1
+utils/src/lib.rs:2:7: replace * with / in triple
+",
+        );
+}
+
+#[test]
+fn test_mutants_in_one_package_of_a_workspace() {
+    let tmp_src_dir = copy_of_testdata("workspace");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .args(["--package", "cargo_mutants_testdata_workspace_utils"])
+        .assert()
+        .success()
+        .stdout(contains("found 3 mutations to test\n"))
+        .stdout(contains("main/src").not());
+    let log = fs::read_to_string(tmp_src_dir.path().join("mutants.out/log/baseline.log")).unwrap();
+    assert!(log.contains("test -p cargo_mutants_testdata_workspace_utils\n"));
+}
+
+#[test]
+fn list_mutants_in_default_members_of_a_virtual_workspace() {
+    run_assert_cmd()
+        .args(["mutants", "--list"])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .success()
+        .stdout(contains(
+            "main/src/main.rs:7: replace triple_plus_one -> u32 with 0\n",
+        ))
+        .stdout(contains("utils/src").not());
+}
+
+#[test]
+fn list_mutants_in_every_package_of_a_workspace() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--workspace"])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .success()
+        .stdout(contains(
+            "main/src/main.rs:7: replace triple_plus_one -> u32 with 0\n",
+        ))
+        .stdout(contains(
+            "utils/src/lib.rs:1: replace triple -> u32 with 0\n",
+        ));
+}
+
+#[test]
+fn workspace_mutants_are_tested_by_their_own_package() {
+    let tmp_src_dir = copy_of_testdata("workspace");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--workspace",
+            "--re",
+            "with 0$",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("found 2 mutations to test\n"));
+    let log_dir = tmp_src_dir.path().join("mutants.out/log");
+    let baseline_log = fs::read_to_string(log_dir.join("baseline.log")).unwrap();
+    assert!(baseline_log.contains("test --workspace\n"));
+    for (file, package) in [
+        (
+            "main__src__main.rs",
+            "cargo_mutants_testdata_workspace_main",
+        ),
+        (
+            "utils__src__lib.rs",
+            "cargo_mutants_testdata_workspace_utils",
+        ),
+    ] {
+        let log_name = fs::read_dir(&log_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .find(|name| name.starts_with(file))
+            .unwrap();
+        let log = fs::read_to_string(log_dir.join(log_name)).unwrap();
+        assert!(log.contains(&format!("test -p {}\n", package)), "{}", log);
+    }
+}
+
+#[test]
+fn list_mutants_in_workspace_excluding_a_package() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--workspace"])
+        .args(["--exclude-package", "cargo_mutants_testdata_workspace_main"])
+        .args(["--exclude-package", "nonexistent"])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .success()
+        .stdout(contains(
+            "utils/src/lib.rs:1: replace triple -> u32 with 0\n",
+        ))
+        .stdout(contains("main/src").not())
+        .stderr(contains(
+            "warning: excluded package \"nonexistent\" is not in the workspace\n",
+        ));
+}
+
+#[test]
+fn list_files_honors_file_and_exclude_globs() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list-files",
+            "-f",
+            "src/*.rs",
+            "-e",
+            "src/[i-n]*.rs",
+        ])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(contains("src/lib.rs\n").not())
+        .stdout(contains("src/collections.rs\n"))
+        .stdout(contains("src/simple_fns.rs\n"));
+    // Nothing is built or copied, so there's no output directory.
+    assert!(!Path::new("testdata/tree/well_tested/mutants.out").exists());
+}
+
+#[test]
+fn config_globs_are_narrowed_by_file_and_exclude() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "examine_globs = [\"src/[a-m]*.rs\"]\nexclude_globs = [\"**/item_mod.rs\"]\n",
+    )
+    .unwrap();
+    let list_files = |args: &[&str]| {
+        run_assert_cmd()
+            .args(["mutants", "--list-files", "-d"])
+            .arg(tmp_src_dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    list_files(&[]).stdout("src/collections.rs\nsrc/inside_mod.rs\nsrc/lib.rs\nsrc/methods.rs\n");
+    list_files(&["-f", "src/*s.rs", "-e", "src/methods.rs"]).stdout("src/collections.rs\n");
+    // Files that `examine_globs` leaves out can't be added back with `--file`.
+    run_assert_cmd()
+        .args(["mutants", "--list-files", "-f", "src/option.rs", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(5)
+        .stderr(contains("no source files found\n"));
+    run_assert_cmd()
+        .args(["mutants", "--list", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("src/methods.rs"))
+        .stdout(contains("src/simple_fns.rs").not())
+        .stdout(contains("src/item_mod.rs").not());
+}
+
+#[test]
+fn list_files_json_includes_packages() {
+    let output = run_assert_cmd()
+        .args(["mutants", "--list-files", "--json", "--workspace"])
+        .current_dir("testdata/tree/workspace")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap(),
+        serde_json::json!([
+            {
+                "path": "main/src/main.rs",
+                "package": "cargo_mutants_testdata_workspace_main",
+            },
+            {
+                "path": "utils/src/lib.rs",
+                "package": "cargo_mutants_testdata_workspace_utils",
+            },
+        ])
+    );
+}
+
+#[test]
+fn list_files_with_no_files_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list-files", "-e", "**"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(5)
+        .stdout("")
+        .stderr(contains("no source files found\n"));
+}
+
+#[test]
+fn packages_excluded_by_config_are_not_tested() {
+    let tmp_src_dir = copy_of_testdata("workspace");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "exclude_packages = [\"cargo_mutants_testdata_workspace_utils\"]\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--workspace",
+            "--re",
+            "with 0$",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("found 1 mutation to test\n"));
+    let baseline_log =
+        fs::read_to_string(tmp_src_dir.path().join("mutants.out/log/baseline.log")).unwrap();
+    assert!(baseline_log
+        .contains("test --workspace --exclude cargo_mutants_testdata_workspace_utils\n"));
+}
+
+#[test]
+fn workspace_and_package_are_exclusive() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--workspace", "-p", "x"])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .code(1)
+        .stdout(contains("--workspace and --package can't be used together"));
+}
+
+#[test]
+fn unknown_package_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "-p", "nonexistent"])
+        .current_dir("testdata/tree/workspace")
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "package \"nonexistent\" is not in the workspace: the packages are cargo_mutants_testdata_workspace_main, cargo_mutants_testdata_workspace_utils",
+        ));
+}
+
+#[test]
+fn invalid_file_glob_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "-f", "src/[unclosed"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .code(1)
+        .stdout(contains("parse --file glob \"src/[unclosed\""));
+}
+
+#[test]
+fn invalid_config_is_an_error() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "exclude_functions = [\"(unclosed\"]\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--list")
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(contains("parse exclude_functions regex \"(unclosed\""));
+    // Errors in the config are reported in the requested output format.
+    run_assert_cmd()
+        .args(["mutants", "--json"])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(contains(r#""event":"error""#))
+        .stdout(contains("parse exclude_functions regex"));
+    run_assert_cmd()
+        .args(["mutants", "--tap"])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(predicates::str::starts_with(
+            "Bail out! parse exclude_functions regex",
+        ));
+}
+
+#[test]
+fn list_mutants_with_dir_option() {
+    run()
+        .arg("mutants")
+        .arg("--list")
+        .arg("--dir")
+        .arg("testdata/tree/factorial")
+        .assert_insta("list_mutants_with_dir_option");
+}
+
+#[test]
+fn list_mutants_with_diffs_in_factorial() {
+    run()
+        .arg("mutants")
+        .arg("--list")
+        .arg("--diff")
+        .current_dir("testdata/tree/factorial")
+        .assert_insta("list_mutants_with_diffs_in_factorial");
+}
+
+#[test]
+fn list_mutants_with_diff_dir_writes_patches_that_apply() {
+    let tmp = tempdir().unwrap();
+    let diff_dir = tmp.path().join("patches");
+    run_assert_cmd()
+        .args(["mutants", "--list", "--diff-dir"])
+        .arg(&diff_dir)
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .success();
+    let patches = fs::read_dir(&diff_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .sorted()
+        .collect::<Vec<_>>();
+    assert_eq!(patches.len(), 6);
+    assert_eq!(
+        patches[0].file_name().unwrap(),
+        "0001-replace_main_with_()_in_src_bin_main.rs_1.patch"
+    );
+    let tree = copy_of_testdata("factorial");
+    for patch in &patches {
+        let content = fs::read_to_string(patch).unwrap();
+        assert!(content.starts_with("--- a/src/bin/main.rs\n+++ b/src/bin/main.rs\n@@ "));
+        let status = Command::new("git")
+            .args(["apply", "--check"])
+            .arg(patch)
+            .current_dir(tree.path())
+            .status()
+            .unwrap();
+        assert!(status.success(), "{:?} applies to the tree", patch);
+    }
+}
+
+#[test]
+fn list_mutants_with_diffs_of_str_replacements() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--diff"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stdout(contains(
+            "src/inside_mod.rs:3: replace outer::inner::name -> & 'static str with \"\"\n",
+        ))
+        .stdout(contains("+\"\" /* ~ changed by cargo-mutants ~ */\n"))
+        .stdout(contains(
+            "src/inside_mod.rs:3: replace outer::inner::name -> & 'static str with \"xyzzy\"\n",
+        ))
+        .stdout(contains("+\"xyzzy\" /* ~ changed by cargo-mutants ~ */\n"));
+}
+
+#[test]
+fn list_diffs_highlight_changed_words_with_color_always() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--diff", "--color", "always"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .success()
+        // The changed range is highlighted within the removed and added lines.
+        .stdout(contains(
+            "\x1b[31m-\x1b[0m\x1b[31m    for i in \x1b[0m\x1b[31m\x1b[7m1..=6\x1b[0m\x1b[31m {\x1b[0m\n",
+        ))
+        .stdout(contains(
+            "\x1b[32m+\x1b[0m\x1b[32m    for i in \x1b[0m\x1b[32m\x1b[7m1.. /* ~ changed by cargo-mutants ~ */6\x1b[0m\x1b[32m {\x1b[0m\n",
+        ));
+}
+
+#[test]
+fn list_diffs_are_plain_with_color_never() {
+    let plain = run_assert_cmd()
+        .args(["mutants", "--list", "--diff"])
+        .current_dir("testdata/tree/factorial")
+        .env_remove("CLICOLOR_FORCE")
+        .output()
+        .unwrap()
+        .stdout;
+    run_assert_cmd()
+        .args(["mutants", "--list", "--diff", "--color", "never"])
+        .current_dir("testdata/tree/factorial")
+        .env("CLICOLOR_FORCE", "1")
+        .assert()
+        .success()
+        .stdout(contains("\x1b[").not())
+        .stdout(predicate::eq(plain.as_slice()));
+}
+
+#[test]
+fn unknown_color_choice_is_an_error() {
+    run_assert_cmd()
+        .args(["mutants", "--list", "--color", "sometimes"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(1)
+        .stderr(contains("unknown color choice \"sometimes\""));
+}
+
+#[test]
+fn list_mutants_well_tested() {
+    run()
+        .arg("mutants")
+        .arg("--list")
+        .current_dir("testdata/tree/well_tested")
+        .assert_insta("list_mutants_well_tested");
+}
+
+#[test]
+fn list_mutants_json_well_tested() {
+    run()
+        .arg("mutants")
+        .arg("--list")
+        .arg("--json")
+        .current_dir("testdata/tree/well_tested")
+        .assert_insta("list_mutants_json_well_tested");
+}
+
+#[test]
+fn list_mutants_json_parses_back() {
+    let output = run_assert_cmd()
+        .args(["mutants", "--list", "--json"])
+        .current_dir("testdata/tree/well_tested")
+        .assert()
+        .success()
+        .stderr("")
+        .get_output()
+        .clone();
+    // Nothing but the JSON is printed to stdout.
+    let mutants: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("stdout is a JSON array");
+    assert!(!mutants.is_empty());
+    for mutant in &mutants {
+        let object = mutant.as_object().expect("each mutant is an object");
+        for key in [
+            "genre",
+            "file",
+            "function",
+            "return_type",
+            "replacement",
+            "change",
+            "description",
+        ] {
+            assert!(
+                object[key].is_string(),
+                "{} is a string in {:?}",
+                key,
+                object
+            );
+        }
+        for key in ["line", "column"] {
+            assert!(object[key].is_u64(), "{} is a number in {:?}", key, object);
+        }
+    }
+    let divisible = mutants
+        .iter()
+        .find(|m| m["function"] == "divisible_by_three" && m["genre"] == "FnValue")
+        .expect("found a divisible_by_three mutant");
+    assert_eq!(divisible["file"], "src/simple_fns.rs");
+    assert_eq!(divisible["line"], 17);
+    assert_eq!(divisible["return_type"], "-> bool");
+    assert_eq!(divisible["replacement"], "true");
+}
+
+#[test]
+fn list_mutants_well_tested_with_error_value() {
+    run()
+        .arg("mutants")
+        .arg("--list")
+        .args(["--error", "()"])
+        .current_dir("testdata/tree/well_tested")
+        .assert_insta("list_mutants_well_tested_with_error_value");
+}
+
+#[test]
+fn error_values_from_config_are_added_to_those_from_the_command_line() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    let config_path = tmp_src_dir.path().join(".cargo/mutants.toml");
+    fs::write(&config_path, "error_values = [\"Default::default()\"]\n").unwrap();
+    let list_results = |args: &[&str]| {
+        run_assert_cmd()
+            .args(["mutants", "--list", "-f", "src/result.rs", "-d"])
+            .arg(tmp_src_dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    list_results(&["--error", "()"])
+        .stdout(contains(
+            "replace error_if_negative -> Result < () , () > with Err(())\n",
+        ))
+        .stdout(contains(
+            "replace error_if_negative -> Result < () , () > with Err(Default::default())\n",
+        ))
+        .stdout(contains("anyhow").not());
+    fs::write(&config_path, "error_values = [\"MyError::(\"]\n").unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--list", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "parse error_values in .cargo/mutants.toml expression \"MyError::(\"",
+        ));
+}
+
+#[test]
+fn skip_calls_from_config_replace_or_add_to_the_defaults() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    let lib_path = tmp_src_dir.path().join("src/simple_fns.rs");
+    let mut code = fs::read_to_string(&lib_path).unwrap();
+    code.push_str("\npub fn consume(v: Vec<u8>) {\n    drop(v);\n}\n");
+    fs::write(&lib_path, code).unwrap();
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    let config_path = tmp_src_dir.path().join(".cargo/mutants.toml");
+    let list_deletions = || {
+        let output = run_assert_cmd()
+            .args(["mutants", "--list", "-f", "src/simple_fns.rs", "-d"])
+            .arg(tmp_src_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter(|line| line.contains(": delete call to "))
+            .map(str::to_owned)
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(
+        list_deletions(),
+        ["src/simple_fns.rs:28:5: delete call to `push_str` in double_string"]
+    );
+    fs::write(&config_path, "skip_calls = [\"push_str\"]\n").unwrap();
+    assert_eq!(list_deletions(), Vec::<String>::new());
+    fs::write(&config_path, "skip_calls_defaults = false\n").unwrap();
+    assert_eq!(
+        list_deletions(),
+        [
+            "src/simple_fns.rs:28:5: delete call to `push_str` in double_string",
+            "src/simple_fns.rs:166:5: delete call to `drop` in consume",
+        ]
+    );
+}
+
+#[test]
+fn copy_testdata_doesnt_include_build_artifacts() {
+    // If there is a target or mutants.out in the source directory, we don't want it in the copy,
+    // so that the tests are (more) hermetic.
+    let tmp_src_dir = copy_of_testdata("factorial");
+    assert!(!tmp_src_dir.path().join("mutants.out").exists());
+    assert!(!tmp_src_dir.path().join("target").exists());
+    assert!(!tmp_src_dir.path().join("mutants.out.old").exists());
+    assert!(tmp_src_dir.path().join("Cargo.toml").exists());
+}
+
+#[test]
+fn well_tested_tree_quiet() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+    // The format of outcomes.json is not pinned down yet, but it should exist.
+    assert!(tmp_src_dir
+        .path()
+        .join("mutants.out/outcomes.json")
+        .exists());
+}
+
+#[test]
+fn well_tested_tree_finds_no_problems() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .arg("--caught")
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+    // The format of outcomes.json is not pinned down yet, but it should exist.
+    assert!(tmp_src_dir
+        .path()
+        .join("mutants.out/outcomes.json")
+        .exists());
+    insta::assert_snapshot!(
+        "well_tested_files_table",
+        fs::read_to_string(tmp_src_dir.path().join("mutants.out/files.txt")).unwrap()
+    );
+}
+
+#[test]
+fn well_tested_tree_check_only() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    run_assert_cmd()
+        .args(["mutants", "--check", "--no-times"])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+    // Each mutant of a function has its own log, even when they're on the same line.
+    let log_dir = tmp_src_dir.path().join("mutants.out/log");
+    for replacement in ["true", "false"] {
+        assert!(log_dir
+            .join(format!(
+                "src__simple_fns.rs_line17_replace_divisible_by_three_with_{}.log",
+                replacement
+            ))
+            .is_file());
+    }
+}
+
+#[test]
+fn well_tested_tree_check_only_shuffled() {
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    run_assert_cmd()
+        .args(["mutants", "--check", "--no-times", "--shuffle"])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success();
+    // Caution: No assertions about output here, we just check that it runs.
+}
+
+#[test]
+fn check_only_results_are_recorded_as_untested() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--check", "--no-times", "--caught", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains(
+            "src/bin/main.rs:1: replace main with () ... check ok\n",
+        ))
+        .stdout(contains("caught").not())
+        .stdout(contains(
+            "6 mutants checked: 6 ok, 0 unviable, 0 timeouts\n",
+        ));
+    let out_dir = tmp_src_dir.path().join("mutants.out");
+    let outcomes: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out_dir.join("outcomes.json")).unwrap()).unwrap();
+    assert_eq!(outcomes["check_only"], true);
+    let statuses: Vec<&serde_json::Value> = outcomes["outcomes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|outcome| &outcome["status"])
+        .collect();
+    assert_eq!(statuses.len(), 8);
+    assert_eq!(statuses[0], &serde_json::Value::Null); // the source tree
+    assert!(statuses[2..].iter().all(|status| *status == "untested"));
+    assert!(fs::read_to_string(out_dir.join("summary.md"))
+        .unwrap()
+        .contains("| Total | Viable | Unviable | Timeouts |\n"));
+}
+
+#[test]
+fn seed_repeats_the_shuffled_order() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let read_json = |name: &str| -> serde_json::Value {
+        serde_json::from_str(
+            &fs::read_to_string(tmp_src_dir.path().join("mutants.out").join(name)).unwrap(),
+        )
+        .unwrap()
+    };
+    let mut orders = Vec::new();
+    for _ in 0..2 {
+        run_assert_cmd()
+            .args(["mutants", "--check", "--no-times", "--seed", "1234", "-d"])
+            .arg(tmp_src_dir.path())
+            .assert()
+            .success()
+            .stdout(contains("shuffled mutants with --seed 1234\n"));
+        assert_eq!(read_json("outcomes.json")["seed"], 1234);
+        orders.push(read_json("mutants.json"));
+    }
+    assert_eq!(orders[0], orders[1]);
+
+    // Listing, and runs with --no-shuffle, are in the order the mutants are found.
+    let list = run_assert_cmd()
+        .args(["mutants", "--list", "--json", "--seed", "1234", "-d"])
+        .arg(tmp_src_dir.path())
+        .output()
+        .unwrap();
+    let listed: serde_json::Value = serde_json::from_slice(&list.stdout).unwrap();
+    assert_ne!(listed, orders[0]);
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--check",
+            "--no-times",
+            "--seed",
+            "1234",
+            "--no-shuffle",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("shuffled").not());
+    assert_eq!(read_json("mutants.json"), listed);
+    assert_eq!(read_json("outcomes.json")["seed"], serde_json::Value::Null);
+}
+
+#[test]
+fn shift_mutants_in_codec_are_caught_or_missed() {
+    let tmp_src_dir = copy_of_testdata("shift_codec");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .arg("--caught")
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stderr("")
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+}
+
+#[test]
+fn cow_mutants_are_caught_or_missed() {
+    let tmp_src_dir = copy_of_testdata("cow");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .arg("--caught")
+        .args(["--max-missed-in-summary", "3"])
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stderr("")
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+    // Each mutant is listed in the file for its outcome, in the same format as the console.
+    let read_list =
+        |name: &str| fs::read_to_string(tmp_src_dir.path().join("mutants.out").join(name)).unwrap();
+    let missed = read_list("missed.txt");
+    assert_eq!(missed.lines().count(), 4);
+    assert!(missed.contains(
+        "src/lib.rs:13: replace normalize_newlines -> Cow < '_ , str > with Cow::Borrowed(\"xyzzy\")\n"
+    ));
+    let caught = read_list("caught.txt");
+    assert_eq!(caught.lines().count(), 14);
+    assert!(caught.contains(
+        "src/lib.rs:22: replace strip_bom -> Cow < '_ , [u8] > with Cow::Borrowed(&[])\n"
+    ));
+    assert_eq!(read_list("unviable.txt"), "");
+    assert_eq!(read_list("timeout.txt"), "");
+    // The HTML report has a summary and the diff of each missed mutant.
+    let html = read_list("mutants.html");
+    assert!(html.contains("<tr><th>Missed</th><td class=\"count missed\">4</td></tr>"));
+    assert!(html.contains(
+        "<summary class=\"missed\">replace normalize_newlines with Cow::Borrowed(&quot;xyzzy&quot;) in src/lib.rs:13</summary>"
+    ));
+    assert!(html.contains("+Cow::Borrowed(&quot;xyzzy&quot;)"));
+    // Missed mutants are failed test cases in junit.xml.
+    let junit = read_list("junit.xml");
+    assert!(junit.contains(
+        r#"<testsuites name="cargo-mutants" tests="18" failures="4" errors="0" skipped="0">"#
+    ));
+    assert_eq!(junit.matches("<failure ").count(), 4);
+    assert!(junit.contains(r#"<testcase classname="src/lib.rs" name="replace strip_bom with Cow::Borrowed(&amp;[]) in src/lib.rs:22""#));
+    // The badge has the score, rounded down, without unviable mutants.
+    let badge: serde_json::Value = serde_json::from_str(&read_list("badge.json")).unwrap();
+    assert_eq!(
+        badge,
+        serde_json::json!({
+            "schemaVersion": 1,
+            "label": "mutation score",
+            "message": "77%",
+            "color": "green",
+        })
+    );
+    // The Markdown summary has the score and a limited list of missed mutants.
+    let summary = read_list("summary.md");
+    assert!(summary.contains("| 18 | 14 | 4 | 0 | 0 |\n"));
+    assert!(summary.contains("Mutation score: 77.8% (14 of 18 tested mutants caught)\n"));
+    assert!(summary.contains(
+        "- src/lib.rs:13 — `replace normalize_newlines with Cow::Borrowed(\"xyzzy\")`\n"
+    ));
+    assert_eq!(summary.matches("\n- src/").count(), 3);
+    assert!(summary.ends_with("- and 1 more\n"));
+    // Missed mutants are results in the SARIF report, located at the mutated code.
+    let sarif: serde_json::Value = serde_json::from_str(&read_list("mutants.sarif")).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        13
+    );
+    // Lines with missed mutants are uncovered in lcov.info.
+    let lcov = read_list("lcov.info");
+    assert!(lcov.starts_with("TN:\nSF:src/lib.rs\n"));
+    assert!(lcov.contains("\nDA:13,0\n"));
+    assert!(lcov.contains("\nBRH:14\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+}
+
+#[test]
+fn tap_output_has_a_plan_and_a_test_point_for_each_mutant() {
+    let tmp_src_dir = copy_of_testdata("cow");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .arg("--tap")
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .env("GITHUB_ACTIONS", "true")
+        .assert()
+        .code(2)
+        .stderr("")
+        .stdout(predicate::function(|stdout: &str| {
+            // Every line is part of the TAP stream, without annotations.
+            assert!(stdout.lines().all(|line| line.starts_with("# ")
+                || line.starts_with("ok ")
+                || line.starts_with("not ok ")
+                || line == "1..18"));
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+}
+
+#[test]
+fn missed_mutants_are_annotated_under_github_actions() {
+    let tmp_src_dir = copy_of_testdata("cow");
+    let output = run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .args(["--annotation-level", "error"])
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .env("GITHUB_ACTIONS", "true")
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    let annotations: Vec<&str> = stdout.lines().filter(|l| l.starts_with("::")).collect();
+    assert_eq!(annotations.len(), 4);
+    assert!(annotations.contains(
+        &"::error file=src/lib.rs,line=13::mutant not caught: replace normalize_newlines with Cow::Borrowed(\"xyzzy\")"
+    ));
+}
+
+#[test]
+fn async_fn_mutants_are_caught_missed_or_time_out() {
+    let tmp_src_dir = copy_of_testdata("async_fns");
+    run_assert_cmd()
+        .arg("mutants")
+        .arg("--no-times")
+        .arg("--caught")
+        .args(["-t", "10"])
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(3) // exit_code::TIMEOUT
+        .stderr("")
+        .stdout(predicate::function(|stdout| {
+            insta::assert_snapshot!(stdout);
+            true
+        }));
+}
+
+#[test]
+fn json_events_are_streamed_on_stdout() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let output = run_assert_cmd()
+        .args(["mutants", "--json", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("GITHUB_ACTIONS", "true")
+        .assert()
+        .code(2)
+        .stderr(contains("6 mutants tested: 4 caught, 2 missed"))
+        .get_output()
+        .clone();
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a JSON object"))
+        .collect();
+    let count = |kind: &str| events.iter().filter(|e| e["event"] == kind).count();
+    assert_eq!(count("start"), 1);
+    assert_eq!(count("mutant_start"), 6);
+    assert_eq!(count("outcome"), 8); // including the source tree and baseline
+    assert_eq!(events[0]["scenario"], "source_tree");
+    assert_eq!(events[1]["scenario"], "baseline");
+    assert_eq!(events[1]["result"], "success");
+    assert_eq!(events[1]["log_path"], "log/baseline.log");
+    let missed: Vec<_> = events
+        .iter()
+        .filter(|e| e["result"] == "missed")
+        .map(|e| e["mutation"]["description"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        missed,
+        [
+            "replace main with () in src/bin/main.rs:1",
+            "replace ..= with .. in main at src/bin/main.rs:2:15"
+        ]
+    );
+    let summary = events.last().unwrap();
+    assert_eq!(summary["event"], "summary");
+    assert_eq!(
+        (&summary["caught"], &summary["missed"]),
+        (&4.into(), &2.into())
+    );
+}
+
+#[test]
+fn tap_and_json_are_exclusive() {
+    run_assert_cmd()
+        .args(["mutants", "--tap", "--json"])
+        .current_dir("testdata/tree/factorial")
+        .assert()
+        .code(1)
+        .stdout(contains("--tap and --json can't be used together"));
+}
+
+#[test]
+fn diff_missed_shows_the_diff_of_each_missed_mutant() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--caught", "--diff-missed", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains(
+            "src/bin/main.rs:1: replace main with () ... NOT CAUGHT\n--- a/src/bin/main.rs\n+++ b/src/bin/main.rs\n@@ ",
+        ))
+        .stdout(contains("+    for i in 1.. /* ~ changed by cargo-mutants ~ */6 {\n"))
+        .stdout(contains("replace factorial -> u32 with 0 ... caught\n"))
+        // Caught mutants don't have diffs.
+        .stdout(predicate::function(|stdout: &str| {
+            stdout.matches("--- a/").count() == 2
+        }));
+}
+
+#[test]
+fn iterate_skips_mutants_caught_in_the_previous_run() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"));
+    assert!(tmp_src_dir.path().join("mutants.out/cache.json").is_file());
+
+    // Only the missed mutants are tested again.
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--iterate", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("skipping 4 mutants caught in a previous run\n"))
+        .stdout(contains("replace main with () ... NOT CAUGHT"))
+        .stdout(contains(
+            "2 mutants tested: 0 caught, 2 missed, 0 unviable, 0 timeouts\n\
+            4 mutants caught (cached) in a previous run, not retested\n",
+        ));
+    // The cached mutants are remembered for the next iteration.
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--iterate", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("skipping 4 mutants caught in a previous run\n"));
+
+    // Changing a function causes its mutants to be tested again.
+    let main_rs = tmp_src_dir.path().join("src/bin/main.rs");
+    let code = fs::read_to_string(&main_rs).unwrap();
+    fs::write(
+        &main_rs,
+        code.replace("    a\n}", "    // The result.\n    a\n}"),
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--iterate", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("skipping 0 mutants caught in a previous run\n"))
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"));
+
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--iterate", "--no-cache", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("skipping").not())
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"));
+}
+
+#[test]
+fn filtered_runs_keep_the_cached_outcomes_of_other_mutants() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"));
+    // Only the mutants in main are tested, and they're missed.
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--re", "in main|main with", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("2 mutants tested: 0 caught, 2 missed"));
+    // The mutants in factorial, caught in the first run, are still in the cache.
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--iterate", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("skipping 4 mutants caught in a previous run\n"));
+}
+
+#[test]
+fn baseline_is_reused_while_the_tree_is_unchanged() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    let run_check = |extra_args: &[&str]| {
+        run_assert_cmd()
+            .args(["mutants", "--check", "--no-times", "-d"])
+            .arg(tmp_src_dir.path())
+            .args(extra_args)
+            .assert()
+            .success()
+    };
+    run_check(&[]).stdout(contains("unmutated baseline ... ok\n"));
+    assert!(tmp_src_dir
+        .path()
+        .join("mutants.out/baseline.json")
+        .is_file());
+    run_check(&[]).stdout(contains(
+        "unmutated baseline ... ok (unchanged since the previous run)\n",
+    ));
+    // The record is kept in the new output directory, for the run after that.
+    assert!(tmp_src_dir
+        .path()
+        .join("mutants.out/baseline.json")
+        .is_file());
+    run_check(&["--baseline", "run"]).stdout(contains("unmutated baseline ... ok\n"));
+
+    // Changing the source, or the options, means the baseline is tested again.
+    let main_rs = tmp_src_dir.path().join("src/bin/main.rs");
+    let code = fs::read_to_string(&main_rs).unwrap();
+    fs::write(
+        &main_rs,
+        code.replace("    a\n}", "    // The result.\n    a\n}"),
+    )
+    .unwrap();
+    run_check(&[]).stdout(contains("unmutated baseline ... ok\n"));
+    run_check(&["--", "--all-targets"]).stdout(contains("unmutated baseline ... ok\n"));
+}
+
+#[test]
+fn baseline_skip_tests_only_the_mutants() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--baseline", "skip", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("unmutated baseline ... skipped\n"))
+        .stdout(contains("6 mutants tested: 4 caught, 2 missed"))
+        .stdout(contains("warning").not());
+    let output_dir = tmp_src_dir.path().join("mutants.out");
+    assert!(!output_dir.join("log/baseline.log").exists());
+    assert!(!output_dir.join("baseline.json").exists());
+}
+
+#[test]
+fn skipped_baseline_that_does_not_build_is_warned_about() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::write(
+        tmp_src_dir.path().join("src/lib.rs"),
+        "pub fn broken() -> u32 {\n    \"not a number\"\n}\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--check",
+            "--no-times",
+            "--no-copy-target",
+            "--baseline",
+            "skip",
+            "--file",
+            "src/bin/main.rs",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("FAILED").not())
+        .stdout(contains(
+            "warning: a mutant failed to build because of errors in other files, \
+            so the unmutated tree may be broken: try again without --baseline skip\n",
+        ))
+        .stdout(contains("6 mutants checked: 0 ok, 6 unviable"));
+}
+
+#[test]
+fn invalid_baseline_strategy_is_an_error() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--baseline",
+            "never",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "unknown baseline strategy \"never\": expected run, auto, or skip",
+        ));
+}
+
+#[test]
+fn slowest_mutants_are_listed_with_their_phase_times() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--slowest", "2", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(is_match(
+            r"\nslowest mutants:\n  \d+\.\d{3}s src/bin/main.rs:\S+ .* \(check \d+\.\d{3}s, build \d+\.\d{3}s, test \d+\.\d{3}s\)\n  \d+\.\d{3}s src/bin/main.rs:\S+ [^\n]*\ntotal time in each phase: check \d+\.\d{3}s, build \d+\.\d{3}s, test \d+\.\d{3}s\n$",
+        ).unwrap());
+    run_assert_cmd()
+        .args(["mutants", "--slowest", "0", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("slowest mutants").not());
+    // Nothing is listed without times.
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("slowest mutants").not());
 }
 
-#[test]
-fn list_mutants_well_tested() {
-    run()
-        .arg("mutants")
-        .arg("--list")
-        .current_dir("testdata/tree/well_tested")
-        .assert_insta("list_mutants_well_tested");
+/// Return the `kept_dir` of each outcome in `outcomes.json` that has one.
+fn kept_dirs(tree: &Path) -> Vec<(String, PathBuf)> {
+    let json = fs::read_to_string(tree.join("mutants.out/outcomes.json")).unwrap();
+    let outcomes: serde_json::Value = serde_json::from_str(&json).unwrap();
+    outcomes["outcomes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|outcome| {
+            let kept_dir = outcome["kept_dir"].as_str()?;
+            Some((
+                outcome["log_path"].as_str().unwrap().to_owned(),
+                kept_dir.into(),
+            ))
+        })
+        .collect()
 }
 
 #[test]
-fn list_mutants_json_well_tested() {
-    run()
-        .arg("mutants")
-        .arg("--list")
-        .arg("--json")
-        .current_dir("testdata/tree/well_tested")
-        .assert_insta("list_mutants_json_well_tested");
+fn keep_missed_dirs_keeps_the_mutated_tree() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--keep-missed-dirs", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(
+            is_match(
+                r"keep mutated tree in \S+ ... done\nsrc/bin/main.rs:1: replace main with \(\) ... NOT CAUGHT \(tree kept in \S+\)\n",
+            )
+            .unwrap(),
+        );
+    let kept = kept_dirs(tmp_src_dir.path());
+    assert_eq!(kept.len(), 2);
+    for (log_path, kept_dir) in kept {
+        assert!(
+            log_path.starts_with("log/src__bin__main.rs_line"),
+            "{}",
+            log_path
+        );
+        let code = fs::read_to_string(kept_dir.join("src/bin/main.rs")).unwrap();
+        assert!(code.contains("/* ~ changed by cargo-mutants ~ */"));
+        assert!(kept_dir.join("target").is_dir());
+        fs::remove_dir_all(kept_dir).unwrap();
+    }
 }
 
 #[test]
-fn copy_testdata_doesnt_include_build_artifacts() {
-    // If there is a target or mutants.out in the source directory, we don't want it in the copy,
-    // so that the tests are (more) hermetic.
+fn json_outcomes_say_where_missed_trees_are_kept() {
     let tmp_src_dir = copy_of_testdata("factorial");
-    assert!(!tmp_src_dir.path().join("mutants.out").exists());
-    assert!(!tmp_src_dir.path().join("target").exists());
-    assert!(!tmp_src_dir.path().join("mutants.out.old").exists());
-    assert!(tmp_src_dir.path().join("Cargo.toml").exists());
+    let output = run_assert_cmd()
+        .args([
+            "mutants",
+            "--json",
+            "--no-times",
+            "--keep-missed-dirs",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let kept: Vec<PathBuf> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .filter(|event| event["event"] == "outcome")
+        .filter_map(|event| {
+            assert_eq!(
+                event["kept_dir"].is_string(),
+                event["result"] == "missed",
+                "{}",
+                event
+            );
+            event["kept_dir"].as_str().map(PathBuf::from)
+        })
+        .collect();
+    assert_eq!(kept.len(), 2);
+    for kept_dir in kept {
+        assert!(kept_dir.join("src/bin/main.rs").is_file());
+        fs::remove_dir_all(kept_dir).unwrap();
+    }
 }
 
 #[test]
-fn well_tested_tree_quiet() {
-    let tmp_src_dir = copy_of_testdata("well_tested");
+fn leak_dirs_keeps_the_scratch_dir_and_every_mutated_tree() {
+    let tmp_src_dir = copy_of_testdata("factorial");
     run_assert_cmd()
-        .arg("mutants")
-        .arg("--no-times")
-        .current_dir(&tmp_src_dir.path())
+        .args(["mutants", "--no-times", "--leak-dirs", "-d"])
+        .arg(tmp_src_dir.path())
         .assert()
-        .success()
-        .stdout(predicate::function(|stdout| {
-            insta::assert_snapshot!(stdout);
-            true
-        }));
-    // The format of outcomes.json is not pinned down yet, but it should exist.
-    assert!(tmp_src_dir
-        .path()
-        .join("mutants.out/outcomes.json")
-        .exists());
+        .code(2)
+        .stdout(contains("scratch directory kept in "));
+    let kept = kept_dirs(tmp_src_dir.path());
+    assert_eq!(kept.len(), 7);
+    assert_eq!(kept[0].0, "log/baseline.log");
+    // The scratch directory is left unmutated.
+    let code = fs::read_to_string(kept[0].1.join("src/bin/main.rs")).unwrap();
+    assert!(!code.contains("cargo-mutants"));
+    for (_, kept_dir) in kept {
+        assert!(kept_dir.is_dir());
+        fs::remove_dir_all(kept_dir).unwrap();
+    }
 }
 
 #[test]
-fn well_tested_tree_finds_no_problems() {
-    let tmp_src_dir = copy_of_testdata("well_tested");
+fn warn_if_the_baseline_runs_no_tests() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::write(tmp_src_dir.path().join("src/bin/main.rs"), "fn main() {}\n").unwrap();
     run_assert_cmd()
-        .arg("mutants")
-        .arg("--no-times")
-        .arg("--caught")
-        .current_dir(&tmp_src_dir.path())
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
         .assert()
-        .success()
-        .stdout(predicate::function(|stdout| {
-            insta::assert_snapshot!(stdout);
-            true
-        }));
-    // The format of outcomes.json is not pinned down yet, but it should exist.
-    assert!(tmp_src_dir
-        .path()
-        .join("mutants.out/outcomes.json")
-        .exists());
+        .code(2)
+        .stdout(contains(
+            "unmutated baseline ... ok\n\
+            warning: no tests were run in the unmutated tree, so no mutants can be caught\n",
+        ));
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(outcomes["warnings"], serde_json::json!(["no_tests"]));
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--fail-on-empty", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(5);
 }
 
 #[test]
-fn well_tested_tree_check_only() {
-    let tmp_src_dir = copy_of_testdata("well_tested");
+fn warn_if_no_mutants_are_found() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "exclude_functions = [\"main\", \"factorial\"]\n",
+    )
+    .unwrap();
     run_assert_cmd()
-        .args(["mutants", "--check", "--no-times"])
-        .current_dir(&tmp_src_dir.path())
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
         .assert()
-        .success()
-        .stdout(predicate::function(|stdout| {
-            insta::assert_snapshot!(stdout);
-            true
-        }));
+        .code(0)
+        .stdout(contains(
+            "warning: no mutants were found, so nothing was tested\n",
+        ));
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--fail-on-empty", "--json", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(5)
+        .stdout(contains(
+            r#"{"event":"warning","message":"no mutants were found, so nothing was tested","warning":"no_mutants"}"#,
+        ))
+        .stdout(contains(r#""warnings":["no_mutants"]"#));
 }
 
 #[test]
-fn well_tested_tree_check_only_shuffled() {
-    let tmp_src_dir = copy_of_testdata("well_tested");
+fn missed_mutants_succeed_with_no_fail_on_missed() {
+    let tmp_src_dir = copy_of_testdata("factorial");
     run_assert_cmd()
-        .args(["mutants", "--check", "--no-times", "--shuffle"])
-        .current_dir(&tmp_src_dir.path())
+        .args(["mutants", "--no-times", "--no-fail-on-missed", "-d"])
+        .arg(tmp_src_dir.path())
         .assert()
-        .success();
-    // Caution: No assertions about output here, we just check that it runs.
+        .code(0)
+        .stdout(contains(
+            "src/bin/main.rs:1: replace main with () ... NOT CAUGHT",
+        ));
+    assert!(
+        fs::read_to_string(tmp_src_dir.path().join("mutants.out/missed.txt"))
+            .unwrap()
+            .contains("replace main with ()")
+    );
 }
 
 #[test]
@@ -258,7 +2177,9 @@ fn uncaught_mutant_in_factorial() {
     run_assert_cmd()
         .arg("mutants")
         .arg("-d")
-        .arg(&tmp_src_dir.path())
+        .arg(tmp_src_dir.path())
+        // The order of the slowest mutants varies from run to run.
+        .args(["--slowest", "0"])
         .assert()
         .code(2)
         .stderr("")
@@ -280,6 +2201,22 @@ fn uncaught_mutant_in_factorial() {
 
     insta::assert_debug_snapshot!("factorial__log_names", &names);
 
+    // Each outcome records its log, relative to mutants.out.
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    let outcomes = outcomes["outcomes"].as_array().unwrap();
+    assert_eq!(outcomes[1]["log_path"], "log/baseline.log");
+    for outcome in outcomes {
+        let log_path = outcome["log_path"].as_str().unwrap();
+        assert!(tmp_src_dir
+            .path()
+            .join("mutants.out")
+            .join(log_path)
+            .is_file());
+    }
+
     // A mutants.json is in the mutants.out directory.
     let mutants_json =
         fs::read_to_string(tmp_src_dir.path().join("mutants.out/mutants.json")).unwrap();
@@ -297,7 +2234,7 @@ fn factorial_mutants_with_all_logs() {
         .arg("-v")
         .arg("-V")
         .arg("-d")
-        .arg(&tmp_src_dir.path())
+        .arg(tmp_src_dir.path())
         .assert()
         .code(2)
         .stderr("")
@@ -312,7 +2249,10 @@ r"unmutated baseline \.\.\. ok in \d+\.\d\d\ds"
 r"src/bin/main\.rs:1: replace main with \(\) \.\.\. NOT CAUGHT in \d+\.\d\d\ds"
         ).unwrap())
         .stdout(is_match(
-r"src/bin/main\.rs:7: replace factorial -> u32 with Default::default\(\) \.\.\. caught in \d+\.\d\d\ds"
+r"src/bin/main\.rs:7: replace factorial -> u32 with 0 \.\.\. caught in \d+\.\d\d\ds"
+        ).unwrap())
+        .stdout(is_match(
+r"src/bin/main\.rs:7: replace factorial -> u32 with 1 \.\.\. caught in \d+\.\d\d\ds"
         ).unwrap());
 }
 
@@ -323,7 +2263,7 @@ fn factorial_mutants_with_all_logs_and_nocapture() {
         .arg("mutants")
         .args(["--all-logs", "-v", "-V"])
         .arg("-d")
-        .arg(&tmp_src_dir.path())
+        .arg(tmp_src_dir.path())
         .args(["--", "--", "--nocapture"])
         .assert()
         .code(2)
@@ -340,7 +2280,7 @@ fn factorial_mutants_no_copy_target() {
         .arg("mutants")
         .args(["--no-copy-target", "--no-times"])
         .arg("-d")
-        .arg(&tmp_src_dir.path())
+        .arg(tmp_src_dir.path())
         .assert()
         .code(2)
         .stderr("")
@@ -358,7 +2298,7 @@ fn check_succeeds_in_tree_that_builds_but_fails_tests() {
         .arg("mutants")
         .arg("--check")
         .arg("--no-times")
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .success()
@@ -375,7 +2315,7 @@ fn check_tree_with_mutants_skip() {
         .arg("mutants")
         .arg("--check")
         .arg("--no-times")
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .success()
@@ -390,7 +2330,7 @@ fn already_failing_tests_are_detected_before_running_mutants() {
     let tmp_src_dir = copy_of_testdata("already_failing_tests");
     run_assert_cmd()
         .arg("mutants")
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .code(4)
@@ -417,7 +2357,7 @@ fn already_failing_doctests_are_detected() {
     let tmp_src_dir = copy_of_testdata("already_failing_doctests");
     run_assert_cmd()
         .arg("mutants")
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .code(4) // CLEAN_TESTS_FAILED
@@ -436,11 +2376,250 @@ fn already_failing_doctests_can_be_skipped_with_cargo_arg() {
     run_assert_cmd()
         .arg("mutants")
         .args(["--", "--all-targets"])
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .code(0)
-        .stdout(contains("found 1 mutation to test"));
+        .stdout(contains("found 3 mutations to test"));
+}
+
+#[test]
+fn cargo_args_are_passed_to_every_phase_and_recorded() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--re",
+            "replace main with",
+            "--cargo-arg",
+            "--offline",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .args(["--", "--all-targets", "--", "--nocapture"])
+        .assert()
+        .code(2)
+        .stdout(contains("1 mutant tested: 0 caught, 1 missed"));
+    let output_dir = tmp_src_dir.path().join("mutants.out");
+    for log_name in ["source_tree.log", "baseline.log"] {
+        let log = fs::read_to_string(output_dir.join("log").join(log_name)).unwrap();
+        assert!(
+            log.contains(" check --tests --offline --all-targets\n"),
+            "{}",
+            log
+        );
+        assert!(
+            log.contains(" build --tests --offline --all-targets\n"),
+            "{}",
+            log
+        );
+    }
+    let baseline_log = fs::read_to_string(output_dir.join("log/baseline.log")).unwrap();
+    assert!(baseline_log.contains(" test --offline --all-targets -- --nocapture\n"));
+    let outcomes: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("outcomes.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        outcomes["cargo_args"],
+        serde_json::json!(["--offline", "--all-targets"])
+    );
+    assert_eq!(outcomes["cargo_test_args"], serde_json::json!([]));
+    assert_eq!(outcomes["test_args"], serde_json::json!(["--nocapture"]));
+}
+
+#[test]
+fn test_args_are_passed_only_to_cargo_test() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--verbose",
+            "--re",
+            "replace main with",
+            "--test-arg",
+            "--skip",
+            "--test-arg",
+            "test_factorial",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .args(["--", "--offline", "--", "--test-threads=1"])
+        .assert()
+        .code(2)
+        .stdout(contains(
+            "test command: cargo test --offline -- --skip test_factorial --test-threads=1\n",
+        ));
+    let log = fs::read_to_string(tmp_src_dir.path().join("mutants.out/log/baseline.log")).unwrap();
+    assert!(log.contains(" build --tests --offline\n"), "{}", log);
+    assert!(
+        log.contains(" test --offline -- --skip test_factorial --test-threads=1\n"),
+        "{}",
+        log
+    );
+    // The only test was skipped.
+    assert!(log.contains("0 passed"), "{}", log);
+}
+
+#[test]
+fn cargo_args_from_config_come_before_the_command_line() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "additional_cargo_args = [\"--offline\"]\n\
+         additional_cargo_test_args = [\"--bins\", \"--\", \"--test-threads=1\"]\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--verbose",
+            "--re",
+            "replace main with",
+            "--cargo-arg",
+            "--locked",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .args(["--", "--", "--skip", "test_factorial"])
+        .assert()
+        .code(2)
+        .stdout(contains(
+            "build command: cargo build --tests --offline --locked\n",
+        ))
+        .stdout(contains(
+            "test command: cargo test --offline --locked --bins -- --test-threads=1 --skip test_factorial\n",
+        ));
+    let output_dir = tmp_src_dir.path().join("mutants.out");
+    let outcomes: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("outcomes.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        outcomes["cargo_args"],
+        serde_json::json!(["--offline", "--locked"])
+    );
+    assert_eq!(outcomes["cargo_test_args"], serde_json::json!(["--bins"]));
+    assert_eq!(
+        outcomes["test_args"],
+        serde_json::json!(["--test-threads=1", "--skip", "test_factorial"])
+    );
+}
+
+#[test]
+fn features_select_which_feature_gated_functions_are_mutated() {
+    let list = |args: &[&str]| {
+        let output = run_assert_cmd()
+            .args([
+                "mutants",
+                "--list",
+                "--json",
+                "-d",
+                "testdata/tree/features",
+            ])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let mutants: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+        mutants
+            .iter()
+            .map(|mutant| mutant["function"].as_str().unwrap().to_owned())
+            .dedup()
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(list(&[]), ["plain", "with_std"]);
+    assert_eq!(list(&["--no-default-features"]), ["plain"]);
+    assert_eq!(
+        list(&["--features", "fancy"]),
+        ["plain", "with_std", "fancy"]
+    );
+    assert_eq!(
+        list(&["-F", "fancy,std", "--no-default-features"]),
+        ["plain", "with_std", "fancy"]
+    );
+    assert_eq!(list(&["--all-features"]), ["plain", "with_std", "fancy"]);
+}
+
+#[test]
+fn features_are_passed_to_cargo_and_recorded() {
+    let tmp_src_dir = copy_of_testdata("features");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--no-default-features", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(contains(
+            "skipped 2 items: only built with features that are off\n\
+            2 mutants tested: 2 caught, 0 missed",
+        ));
+    let output_dir = tmp_src_dir.path().join("mutants.out");
+    let log = fs::read_to_string(output_dir.join("log/baseline.log")).unwrap();
+    assert!(
+        log.contains(" build --tests --no-default-features\n"),
+        "{}",
+        log
+    );
+    assert!(log.contains(" test --no-default-features\n"), "{}", log);
+    let outcomes: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("outcomes.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        outcomes["features"],
+        serde_json::json!({"features": [], "all_features": false, "no_default_features": true})
+    );
+}
+
+#[test]
+fn release_builds_and_tests_every_phase_in_the_release_profile() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--no-times",
+            "--release",
+            "--re",
+            "replace main with",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(contains("1 mutant tested: 0 caught, 1 missed"));
+    let output_dir = tmp_src_dir.path().join("mutants.out");
+    let log = fs::read_to_string(output_dir.join("log/baseline.log")).unwrap();
+    for phase in ["check --tests", "build --tests", "test"] {
+        assert!(
+            log.contains(&format!(" {} --profile release\n", phase)),
+            "{}",
+            log
+        );
+    }
+    assert!(tmp_src_dir.path().join("target/release").is_dir());
+    assert!(!tmp_src_dir.path().join("target/debug").exists());
+    let outcomes: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("outcomes.json")).unwrap())
+            .unwrap();
+    assert_eq!(outcomes["profile"], "release");
+}
+
+#[test]
+fn release_and_profile_conflict() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--list",
+            "--release",
+            "--profile",
+            "bench",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains("--release and --profile can't be used together"));
 }
 
 #[test]
@@ -448,10 +2627,10 @@ fn source_tree_build_fails() {
     let tmp_src_dir = copy_of_testdata("build_fails");
     run_assert_cmd()
         .arg("mutants")
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
-        .failure() // TODO: This should be a distinct error code
+        .code(4) // exit_code::CLEAN_TESTS_FAILED
         .stdout(is_match(r"source tree \.\.\. FAILED in \d+\.\d{3}s").unwrap())
         .stdout(contains(r"This isn't Rust").name("The problem source line"))
         .stdout(contains("*** source tree"))
@@ -467,7 +2646,7 @@ fn timeout_when_unmutated_tree_test_hangs() {
     run_assert_cmd()
         .arg("mutants")
         .args(["--timeout", "2.9"])
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .code(4) // exit_code::CLEAN_TESTS_FAILED
@@ -484,7 +2663,7 @@ fn hang_when_mutated() {
     run_assert_cmd()
         .arg("mutants")
         .args(["-t", "1.9"])
-        .current_dir(&tmp_src_dir.path())
+        .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .assert()
         .code(3) // exit_code::TIMEOUT
@@ -495,3 +2674,242 @@ fn hang_when_mutated() {
             "replace should_stop -> bool with true ... NOT CAUGHT",
         ));
 }
+
+#[test]
+fn auto_timeout_is_at_least_the_minimum_from_config() {
+    let tmp_src_dir = copy_of_testdata("hang_when_mutated");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "minimum_test_timeout = 60\n",
+    )
+    .unwrap();
+    // The tests take almost no time on the unmutated tree, so the timeout is the
+    // minimum, and the command line overrides the config.
+    run_assert_cmd()
+        .args(["mutants", "--minimum-test-timeout", "1.5", "-d"])
+        .arg(tmp_src_dir.path())
+        .env_remove("RUST_BACKTRACE")
+        .assert()
+        .code(3) // exit_code::TIMEOUT
+        .stdout(contains("auto-set test timeout to 1.5s"))
+        .stdout(contains(
+            "replace should_stop -> bool with false ... TIMEOUT",
+        ));
+}
+
+#[test]
+fn timeout_multiplier_is_combined_with_the_minimum_and_recorded() {
+    let tmp_src_dir = copy_of_testdata("hang_when_mutated");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    fs::write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "timeout_multiplier = 3\n",
+    )
+    .unwrap();
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--timeout-multiplier",
+            "1.5",
+            "--minimum-test-timeout",
+            "1",
+            "--re",
+            "with true",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2) // exit_code::FOUND_PROBLEMS
+        .stdout(contains("auto-set test timeout to 1.0s"));
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(outcomes["timeout_multiplier"], 1.5);
+    assert_eq!(
+        outcomes["test_timeout"],
+        serde_json::json!({ "secs": 1, "nanos": 0 })
+    );
+    assert_eq!(
+        outcomes["minimum_test_timeout"],
+        serde_json::json!({ "secs": 1, "nanos": 0 })
+    );
+}
+
+#[test]
+fn build_timeout_is_reported_apart_from_test_timeouts() {
+    let tmp_src_dir = copy_of_testdata("build_hangs_when_mutated");
+    run_assert_cmd()
+        .args(["mutants", "--no-times", "--build-timeout", "10", "-d"])
+        .arg(tmp_src_dir.path())
+        .env_remove("RUST_BACKTRACE")
+        .assert()
+        .code(3) // exit_code::TIMEOUT
+        .stdout(contains(
+            "replace should_build -> bool with false ... BUILD TIMEOUT",
+        ))
+        .stdout(contains(
+            "replace should_build -> bool with true ... NOT CAUGHT",
+        ));
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        outcomes["build_timeout"],
+        serde_json::json!({ "secs": 10, "nanos": 0 })
+    );
+    let statuses: Vec<&serde_json::Value> = outcomes["outcomes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|outcome| &outcome["status"])
+        .collect();
+    assert!(statuses.contains(&&serde_json::json!("build_timeout")));
+    assert_eq!(
+        fs::read_to_string(tmp_src_dir.path().join("mutants.out/timeout.txt"))
+            .unwrap()
+            .lines()
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn build_timeout_multiplier_sets_the_build_timeout_from_the_baseline() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--check",
+            "--build-timeout-multiplier",
+            "2",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        // The baseline check is quick, so the timeout is the minimum.
+        .stdout(contains("auto-set build timeout to 20.0s"));
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(outcomes["build_timeout_multiplier"], 2.0);
+    assert_eq!(outcomes["test_timeout"], serde_json::Value::Null);
+}
+
+#[test]
+fn build_timeout_and_build_timeout_multiplier_conflict() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--build-timeout",
+            "10",
+            "--build-timeout-multiplier",
+            "2",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "--build-timeout and --build-timeout-multiplier can't be used together",
+        ));
+}
+
+#[test]
+fn timeouts_from_config_are_overridden_by_the_command_line() {
+    let tmp_src_dir = copy_of_testdata("factorial");
+    fs::create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    let config_path = tmp_src_dir.path().join(".cargo/mutants.toml");
+    fs::write(&config_path, "timeout = 30\nbuild_timeout = 120\n").unwrap();
+    let check_factorial = |args: &[&str]| {
+        run_assert_cmd()
+            .args([
+                "mutants",
+                "--check",
+                "--verbose",
+                "--re",
+                "factorial -> u32 with 0",
+                "-d",
+            ])
+            .arg(tmp_src_dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    check_factorial(&[])
+        .stdout(contains("test timeout: 30.0s\n"))
+        .stdout(contains("build timeout: 120.0s\n"));
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        outcomes["test_timeout"],
+        serde_json::json!({ "secs": 30, "nanos": 0 })
+    );
+    assert_eq!(
+        outcomes["build_timeout"],
+        serde_json::json!({ "secs": 120, "nanos": 0 })
+    );
+    check_factorial(&[
+        "--timeout-multiplier",
+        "2",
+        "--build-timeout-multiplier",
+        "3",
+    ])
+    .stdout(contains(
+        "test timeout: 2x the baseline test time, at least 20.0s\n",
+    ))
+    .stdout(contains(
+        "build timeout: 3x the baseline build time, at least 20.0s\n",
+    ));
+    fs::write(&config_path, "timeout = 30\ntimeout_multiplier = 2\n").unwrap();
+    run_assert_cmd()
+        .args(["mutants", "--list", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "timeout and timeout_multiplier can't be used together in .cargo/mutants.toml",
+        ));
+}
+
+#[test]
+fn timeout_and_timeout_multiplier_conflict() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--timeout",
+            "10",
+            "--timeout-multiplier",
+            "2",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "--timeout and --timeout-multiplier can't be used together",
+        ));
+}
+
+#[test]
+fn negative_timeout_is_an_error() {
+    run_assert_cmd()
+        .args([
+            "mutants",
+            "--timeout",
+            "-1",
+            "-d",
+            "testdata/tree/factorial",
+        ])
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "--timeout must be a non-negative number of seconds",
+        ));
+}